@@ -0,0 +1,277 @@
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::tile::bullet::Bullet;
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::laser::LaserOrientation;
+
+/// Sources of danger threatening a single tile in a single tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Danger {
+    /// A bullet passes through the tile.
+    pub bullet: bool,
+
+    /// The tile is covered by a laser.
+    pub laser: bool,
+
+    /// The tile contains a mine.
+    pub mine: bool,
+}
+
+impl Danger {
+    /// Returns true if nothing threatens the tile.
+    pub fn is_safe(&self) -> bool {
+        !(self.bullet || self.laser || self.mine)
+    }
+}
+
+/// Prediction of which tiles are going to be dangerous in the upcoming ticks.
+///
+/// Tick `0` is the game state the map was built from, tick `1` the state after
+/// the next server update and so on. Only entities visible in the game state
+/// are taken into account, and every prediction errs on the side of caution:
+///
+/// - bullets fly in a straight line at the speed sent by the server until they
+///   hit a wall, covering every tile they may pass within a tick, so faster bullets
+///   threaten several tiles per tick,
+/// - lasers cover the tiles they are seen on and continue along their orientation
+///   through tiles hidden by fog up to the first wall, for the given number of ticks,
+///   as their remaining duration is not sent by the server,
+/// - armed mines threaten their tile in every tick, and exploding mines
+///   until their explosion ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DangerMap {
+    /// Indexed by `[tick][y][x]`.
+    ticks: Vec<Vec<Vec<Danger>>>,
+}
+
+impl DangerMap {
+    /// Predicts the dangers for the current tick and the following `ticks` ticks.
+    ///
+    /// Lasers are assumed to stay active for `laser_ticks` ticks after the current one.
+    pub fn new(game_state: &GameState, ticks: usize, laser_ticks: usize) -> DangerMap {
+        let empty: Vec<Vec<Danger>> = game_state
+            .map
+            .iter()
+            .map(|row| vec![Danger::default(); row.len()])
+            .collect();
+        let mut danger_map = DangerMap {
+            ticks: vec![empty; ticks + 1],
+        };
+
+        for (position, bullet) in game_state.bullets() {
+            danger_map.add_bullet(game_state, position, bullet);
+        }
+
+        // A beam continues through the tiles hidden by fog up to the first wall,
+        // visible tiles without its laser show where it ends
+        let last_laser_tick = ticks.min(laser_ticks);
+        for (position, laser) in game_state.lasers() {
+            let directions = match laser.orientation {
+                LaserOrientation::Horizontal => [Direction::Left, Direction::Right],
+                LaserOrientation::Vertical => [Direction::Up, Direction::Down],
+            };
+            let hidden = directions.into_iter().flat_map(|direction| {
+                game_state
+                    .line_of_fire(position, direction)
+                    .take_while(|&tile| game_state.tile_at(tile).is_some_and(|tile| !tile.visible))
+            });
+
+            for tile in std::iter::once(position).chain(hidden) {
+                for tick in 0..=last_laser_tick {
+                    danger_map.mark(tile, tick, |danger| danger.laser = true);
+                }
+            }
+        }
+
+        for (position, mine) in game_state.mines() {
+            let last_tick = match mine.explosion_remaining_ticks {
+                Some(remaining) => ticks.min(remaining.max(0) as usize),
+                None => ticks,
+            };
+
+            for tick in 0..=last_tick {
+                danger_map.mark(position, tick, |danger| danger.mine = true);
+            }
+        }
+
+        danger_map
+    }
+
+    /// Returns the number of predicted ticks after the current one.
+    pub fn ticks(&self) -> usize {
+        self.ticks.len() - 1
+    }
+
+    /// Returns the dangers threatening the given tile in the given tick.
+    ///
+    /// Tiles outside of the map and ticks beyond the prediction are reported as safe.
+    pub fn danger_at(&self, position: Position, tick: usize) -> Danger {
+        self.ticks
+            .get(tick)
+            .and_then(|rows| rows.get(position.y))
+            .and_then(|row| row.get(position.x))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if anything threatens the given tile in the given tick.
+    pub fn is_dangerous(&self, position: Position, tick: usize) -> bool {
+        !self.danger_at(position, tick).is_safe()
+    }
+
+    fn add_bullet(&mut self, game_state: &GameState, position: Position, bullet: &Bullet) {
+        // Tiles the bullet flies through, starting with the one it is on now
+        let path: Vec<Position> = std::iter::once(position)
+            .chain(game_state.line_of_fire(position, bullet.direction))
+            .collect();
+
+        for tick in 0..=self.ticks() {
+            // The progress the bullet made towards the next tile is unknown,
+            // so it may be up to a tile ahead or behind the exact distance
+            let nearest = (tick.saturating_sub(1) as f64 * bullet.speed).floor() as usize;
+            let farthest = (tick as f64 * bullet.speed).ceil() as usize;
+
+            if nearest >= path.len() {
+                break;
+            }
+
+            for &tile in &path[nearest..=farthest.min(path.len() - 1)] {
+                self.mark(tile, tick, |danger| danger.bullet = true);
+            }
+        }
+    }
+
+    fn mark(&mut self, position: Position, tick: usize, update: impl FnOnce(&mut Danger)) {
+        if let Some(danger) = self
+            .ticks
+            .get_mut(tick)
+            .and_then(|rows| rows.get_mut(position.y))
+            .and_then(|row| row.get_mut(position.x))
+        {
+            update(danger);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ascii_map::game_state_from_rows;
+    use crate::ws_client::packet::packets::game_state::tile::bullet::BulletType;
+    use crate::ws_client::packet::packets::game_state::tile::laser::Laser;
+    use crate::ws_client::packet::packets::game_state::tile::mine::Mine;
+    use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+
+    /// Builds a game state from rows of `#` (wall) and `.` (empty).
+    fn game_state(rows: &[&str]) -> GameState {
+        game_state_from_rows(rows, 0, |_| vec![], |_| true)
+    }
+
+    fn place(game_state: &mut GameState, position: Position, entity: TileEntity) {
+        game_state.map[position.y][position.x].entities.push(entity);
+    }
+
+    fn bullet(direction: Direction, bullet_type: BulletType, speed: f64) -> TileEntity {
+        TileEntity::Bullet(Bullet::new(direction, 1, speed, bullet_type))
+    }
+
+    #[test]
+    fn test_bullet_stops_at_wall() {
+        let mut game_state = game_state(&["......#."]);
+        place(
+            &mut game_state,
+            Position::new(0, 0),
+            bullet(Direction::Right, BulletType::Basic, 1.0),
+        );
+
+        let danger_map = DangerMap::new(&game_state, 10, 3);
+
+        assert!(danger_map.danger_at(Position::new(0, 0), 0).bullet);
+        assert!(danger_map.is_dangerous(Position::new(1, 0), 1));
+        assert!(!danger_map.is_dangerous(Position::new(3, 0), 1));
+        assert!(danger_map.is_dangerous(Position::new(3, 0), 3));
+        assert!((0..=10).all(|tick| !danger_map.is_dangerous(Position::new(7, 0), tick)));
+    }
+
+    #[test]
+    fn test_double_bullet_covers_more_tiles() {
+        let mut game_state = game_state(&["........"]);
+        place(
+            &mut game_state,
+            Position::new(0, 0),
+            bullet(Direction::Right, BulletType::Double, 2.0),
+        );
+
+        let danger_map = DangerMap::new(&game_state, 3, 3);
+
+        assert!(danger_map.is_dangerous(Position::new(1, 0), 1));
+        assert!(danger_map.is_dangerous(Position::new(2, 0), 1));
+        assert!(danger_map.is_dangerous(Position::new(4, 0), 2));
+        assert!(!danger_map.is_dangerous(Position::new(5, 0), 2));
+    }
+
+    #[test]
+    fn test_laser_covers_its_beam_only() {
+        let mut game_state = game_state(&["......", ".#....", "......"]);
+        // Fired to the right by a tank at (2, 1)
+        for x in 3..6 {
+            place(
+                &mut game_state,
+                Position::new(x, 1),
+                TileEntity::Laser(Laser::new(1, LaserOrientation::Horizontal)),
+            );
+        }
+
+        let danger_map = DangerMap::new(&game_state, 10, 3);
+
+        assert!(danger_map.danger_at(Position::new(5, 1), 0).laser);
+        assert!(danger_map.danger_at(Position::new(3, 1), 1).laser);
+        assert!(!danger_map.is_dangerous(Position::new(2, 1), 0));
+        assert!(!danger_map.is_dangerous(Position::new(0, 1), 0));
+        assert!(!danger_map.is_dangerous(Position::new(3, 0), 0));
+        assert!(!danger_map.is_dangerous(Position::new(3, 1), 4));
+    }
+
+    #[test]
+    fn test_laser_continues_into_fog_up_to_wall() {
+        // Fired down by a tank at (0, 0), only the tile below it is visible
+        let rows = [".", ".", ".", ".", "#", "."];
+        let mut game_state = game_state_from_rows(&rows, 0, |_| vec![], |position| position.y < 2);
+        place(
+            &mut game_state,
+            Position::new(0, 1),
+            TileEntity::Laser(Laser::new(1, LaserOrientation::Vertical)),
+        );
+
+        let danger_map = DangerMap::new(&game_state, 10, 3);
+
+        assert!(!danger_map.is_dangerous(Position::new(0, 0), 0));
+        assert!(danger_map.danger_at(Position::new(0, 1), 0).laser);
+        assert!(danger_map.danger_at(Position::new(0, 2), 0).laser);
+        assert!(danger_map.danger_at(Position::new(0, 3), 3).laser);
+        assert!(!danger_map.is_dangerous(Position::new(0, 4), 0));
+        assert!(!danger_map.is_dangerous(Position::new(0, 5), 0));
+    }
+
+    #[test]
+    fn test_mines() {
+        let mut game_state = game_state(&["...."]);
+        place(
+            &mut game_state,
+            Position::new(0, 0),
+            TileEntity::Mine(Mine::new(1, None)),
+        );
+        place(
+            &mut game_state,
+            Position::new(2, 0),
+            TileEntity::Mine(Mine::new(2, Some(2))),
+        );
+
+        let danger_map = DangerMap::new(&game_state, 5, 3);
+
+        assert!(danger_map.danger_at(Position::new(0, 0), 5).mine);
+        assert!(danger_map.danger_at(Position::new(2, 0), 2).mine);
+        assert!(danger_map.danger_at(Position::new(2, 0), 3).is_safe());
+        assert!(danger_map.danger_at(Position::new(1, 0), 0).is_safe());
+    }
+}
@@ -0,0 +1,685 @@
+use crate::ws_client::packet::packets::bot_response::ability_type::AbilityType;
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::bot_response::move_direction::MoveDirection;
+use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::tile::bullet::{Bullet, BulletType};
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::item::ItemType;
+use crate::ws_client::packet::packets::game_state::tile::laser::LaserOrientation;
+use derive_more::derive::IsVariant;
+use std::collections::HashMap;
+
+/// A change between two consecutive game states.
+///
+/// Only visible entities are tracked, so an entity leaving vision is reported
+/// the same way as one being destroyed, and one entering vision as a new one.
+#[derive(Debug, Clone, PartialEq, IsVariant)]
+pub enum TrackerEvent {
+    BulletSpawned {
+        id: i64,
+        position: Position,
+        bullet: Bullet,
+    },
+
+    /// The distance between `from` and `to` is the speed of the bullet in tiles per tick.
+    BulletMoved {
+        id: i64,
+        from: Position,
+        to: Position,
+    },
+
+    BulletDespawned {
+        id: i64,
+        /// The position the bullet was last seen at.
+        position: Position,
+    },
+
+    LaserFired {
+        id: i64,
+        orientation: LaserOrientation,
+        tiles: Vec<Position>,
+    },
+
+    LaserExpired {
+        id: i64,
+    },
+
+    MinePlaced {
+        id: i64,
+        position: Position,
+    },
+
+    MineExploded {
+        id: i64,
+        position: Position,
+    },
+
+    MineDespawned {
+        id: i64,
+        position: Position,
+    },
+
+    /// An item appeared on a tile that was already visible before.
+    ItemSpawned {
+        position: Position,
+        item_type: ItemType,
+    },
+
+    /// An item disappeared from a tile that is still visible.
+    ItemPickedUp {
+        position: Position,
+        item_type: ItemType,
+        /// The owner of the tank standing on the tile, if any.
+        owner_id: Option<String>,
+    },
+
+    TankAppeared {
+        owner_id: String,
+        position: Position,
+    },
+
+    TankMoved {
+        owner_id: String,
+        from: Position,
+        to: Position,
+    },
+
+    TankRotated {
+        owner_id: String,
+        from: Direction,
+        to: Direction,
+    },
+
+    TurretRotated {
+        owner_id: String,
+        from: Direction,
+        to: Direction,
+    },
+
+    TankDisappeared {
+        owner_id: String,
+        /// The position the tank was last seen at.
+        position: Position,
+    },
+
+    /// The player's `ticks_to_regen` became `Some`.
+    PlayerDied {
+        player_id: String,
+    },
+
+    /// The player's `ticks_to_regen` became `None`.
+    PlayerRespawned {
+        player_id: String,
+    },
+}
+
+/// Follows entities across consecutive game states.
+///
+/// Feed every game state to [`EntityTracker::update`] to get the events
+/// that happened since the previous one.
+#[derive(Debug, Clone, Default)]
+pub struct EntityTracker {
+    previous: Option<GameState>,
+
+    /// The most likely action of each tank visible in both of the last two game states.
+    last_actions: HashMap<String, BotResponse>,
+}
+
+impl EntityTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compares the game state with the previous one and returns the events between them.
+    ///
+    /// The first game state produces no events.
+    pub fn update(&mut self, game_state: &GameState) -> Vec<TrackerEvent> {
+        let Some(previous) = self.previous.replace(game_state.clone()) else {
+            return vec![];
+        };
+
+        self.last_actions = game_state
+            .tanks()
+            .filter_map(|(_, tank)| {
+                let action = infer_action(&previous, game_state, &tank.owner_id)?;
+                Some((tank.owner_id.clone(), action))
+            })
+            .collect();
+
+        diff(&previous, game_state)
+    }
+
+    /// Returns the most likely action the tank of the given player performed
+    /// in the last tick, or `None` if it was not visible in both of the last two game states.
+    pub fn last_action(&self, owner_id: &str) -> Option<&BotResponse> {
+        self.last_actions.get(owner_id)
+    }
+
+    /// Returns the most likely actions of all tanks in the last tick, by the id of their owner.
+    pub fn last_actions(&self) -> &HashMap<String, BotResponse> {
+        &self.last_actions
+    }
+}
+
+/// Returns the events that happened between two consecutive game states.
+pub fn diff(previous: &GameState, current: &GameState) -> Vec<TrackerEvent> {
+    let mut events = vec![];
+
+    diff_bullets(previous, current, &mut events);
+    diff_lasers(previous, current, &mut events);
+    diff_mines(previous, current, &mut events);
+    diff_items(previous, current, &mut events);
+    diff_tanks(previous, current, &mut events);
+
+    for player in &current.players {
+        let Some(before) = previous
+            .players
+            .iter()
+            .find(|before| before.id == player.id)
+        else {
+            continue;
+        };
+
+        let player_id = player.id.clone();
+        match (before.ticks_to_regen, player.ticks_to_regen) {
+            (None, Some(_)) => events.push(TrackerEvent::PlayerDied { player_id }),
+            (Some(_), None) => events.push(TrackerEvent::PlayerRespawned { player_id }),
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Guesses which action the tank of the given player performed between two consecutive game states.
+///
+/// Returns `None` if the tank is not visible in both of them or the change cannot be
+/// explained by a single action. Radar usage is only detected for your own tank.
+pub fn infer_action(
+    previous: &GameState,
+    current: &GameState,
+    owner_id: &str,
+) -> Option<BotResponse> {
+    let (from, before) = previous.my_tank(owner_id)?;
+    let (to, after) = current.my_tank(owner_id)?;
+
+    if from != to {
+        let direction = if from.neighbour(before.direction) == Some(to) {
+            MoveDirection::Forward
+        } else if from.neighbour(before.direction.opposite()) == Some(to) {
+            MoveDirection::Backward
+        } else {
+            return None;
+        };
+
+        return Some(BotResponse::Movement { direction });
+    }
+
+    let tank_rotation = rotation_between(before.direction, after.direction);
+    let turret_rotation = rotation_between(before.turret.direction, after.turret.direction);
+    if tank_rotation.is_some() || turret_rotation.is_some() {
+        return Some(BotResponse::Rotation {
+            tank_rotation,
+            turret_rotation,
+        });
+    }
+
+    let ability_type = if let Some(bullet) =
+        new_bullet_fired_from(previous, current, to, after.turret.direction)
+    {
+        Some(match bullet.bullet_type {
+            BulletType::Basic => AbilityType::FireBullet,
+            BulletType::Double => AbilityType::FireDoubleBullet,
+        })
+    } else if laser_fired_from(previous, current, to, after.turret.direction) {
+        Some(AbilityType::UseLaser)
+    } else if mine_dropped_behind(previous, current, to, after.direction) {
+        Some(AbilityType::DropMine)
+    } else if current
+        .players
+        .iter()
+        .any(|player| player.id == owner_id && player.is_using_radar == Some(true))
+    {
+        Some(AbilityType::UseRadar)
+    } else {
+        None
+    };
+
+    Some(match ability_type {
+        Some(ability_type) => BotResponse::AbilityUse { ability_type },
+        None => BotResponse::Pass,
+    })
+}
+
+fn rotation_between(from: Direction, to: Direction) -> Option<Rotation> {
+    [Rotation::Left, Rotation::Right]
+        .into_iter()
+        .find(|&rotation| from.rotate(rotation) == to)
+}
+
+/// Finds a bullet that did not exist before and is now in the line of fire of a turret at `position`,
+/// no further than it could have flown since being fired.
+fn new_bullet_fired_from<'a>(
+    previous: &GameState,
+    current: &'a GameState,
+    position: Position,
+    direction: Direction,
+) -> Option<&'a Bullet> {
+    current
+        .bullets()
+        .filter(|(_, bullet)| previous.bullets().all(|(_, old)| old.id != bullet.id))
+        .find(|(bullet_position, bullet)| {
+            let range = bullet.speed.max(1.0).ceil() as usize;
+            *bullet_position == position
+                || current
+                    .line_of_fire(position, direction)
+                    .take(range)
+                    .any(|tile| tile == *bullet_position)
+        })
+        .map(|(_, bullet)| bullet)
+}
+
+fn laser_fired_from(
+    previous: &GameState,
+    current: &GameState,
+    position: Position,
+    direction: Direction,
+) -> bool {
+    let Some(first_tile) = position.neighbour(direction) else {
+        return false;
+    };
+
+    current.lasers().any(|(laser_position, laser)| {
+        laser_position == first_tile && previous.lasers().all(|(_, old)| old.id != laser.id)
+    })
+}
+
+fn mine_dropped_behind(
+    previous: &GameState,
+    current: &GameState,
+    position: Position,
+    direction: Direction,
+) -> bool {
+    let Some(behind) = position.neighbour(direction.opposite()) else {
+        return false;
+    };
+
+    current.mines().any(|(mine_position, mine)| {
+        mine_position == behind && previous.mines().all(|(_, old)| old.id != mine.id)
+    })
+}
+
+fn diff_bullets(previous: &GameState, current: &GameState, events: &mut Vec<TrackerEvent>) {
+    let before: HashMap<i64, Position> = previous
+        .bullets()
+        .map(|(position, bullet)| (bullet.id, position))
+        .collect();
+    let after: HashMap<i64, Position> = current
+        .bullets()
+        .map(|(position, bullet)| (bullet.id, position))
+        .collect();
+
+    for (position, bullet) in current.bullets() {
+        match before.get(&bullet.id) {
+            None => events.push(TrackerEvent::BulletSpawned {
+                id: bullet.id,
+                position,
+                bullet: bullet.clone(),
+            }),
+            Some(&from) if from != position => events.push(TrackerEvent::BulletMoved {
+                id: bullet.id,
+                from,
+                to: position,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (position, bullet) in previous.bullets() {
+        if !after.contains_key(&bullet.id) {
+            events.push(TrackerEvent::BulletDespawned {
+                id: bullet.id,
+                position,
+            });
+        }
+    }
+}
+
+fn diff_lasers(previous: &GameState, current: &GameState, events: &mut Vec<TrackerEvent>) {
+    // A laser covers several tiles, so it is listed once for each of them
+    let mut fired: Vec<(i64, LaserOrientation, Vec<Position>)> = vec![];
+    for (position, laser) in current.lasers() {
+        if previous.lasers().any(|(_, old)| old.id == laser.id) {
+            continue;
+        }
+
+        match fired.iter_mut().find(|(id, _, _)| *id == laser.id) {
+            Some((_, _, tiles)) => tiles.push(position),
+            None => fired.push((laser.id, laser.orientation, vec![position])),
+        }
+    }
+
+    events.extend(
+        fired
+            .into_iter()
+            .map(|(id, orientation, tiles)| TrackerEvent::LaserFired {
+                id,
+                orientation,
+                tiles,
+            }),
+    );
+
+    let mut expired: Vec<i64> = vec![];
+    for (_, laser) in previous.lasers() {
+        if !expired.contains(&laser.id) && current.lasers().all(|(_, new)| new.id != laser.id) {
+            expired.push(laser.id);
+        }
+    }
+
+    events.extend(
+        expired
+            .into_iter()
+            .map(|id| TrackerEvent::LaserExpired { id }),
+    );
+}
+
+fn diff_mines(previous: &GameState, current: &GameState, events: &mut Vec<TrackerEvent>) {
+    for (position, mine) in current.mines() {
+        let old = previous.mines().find(|(_, old)| old.id == mine.id);
+
+        match old {
+            None => events.push(TrackerEvent::MinePlaced {
+                id: mine.id,
+                position,
+            }),
+            Some((_, old))
+                if old.explosion_remaining_ticks.is_none()
+                    && mine.explosion_remaining_ticks.is_some() =>
+            {
+                events.push(TrackerEvent::MineExploded {
+                    id: mine.id,
+                    position,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    for (position, mine) in previous.mines() {
+        if current.mines().all(|(_, new)| new.id != mine.id) {
+            events.push(TrackerEvent::MineDespawned {
+                id: mine.id,
+                position,
+            });
+        }
+    }
+}
+
+fn diff_items(previous: &GameState, current: &GameState, events: &mut Vec<TrackerEvent>) {
+    let is_visible = |game_state: &GameState, position: Position| {
+        game_state
+            .tile_at(position)
+            .is_some_and(|tile| tile.visible)
+    };
+
+    for (position, item) in previous.items() {
+        let still_there = current
+            .items()
+            .any(|(new_position, new)| new_position == position && new == item);
+
+        if !still_there && is_visible(current, position) {
+            let owner_id = current
+                .tanks()
+                .find(|(tank_position, _)| *tank_position == position)
+                .map(|(_, tank)| tank.owner_id.clone());
+
+            events.push(TrackerEvent::ItemPickedUp {
+                position,
+                item_type: item.item_type,
+                owner_id,
+            });
+        }
+    }
+
+    for (position, item) in current.items() {
+        let was_there = previous
+            .items()
+            .any(|(old_position, old)| old_position == position && old == item);
+
+        if !was_there && is_visible(previous, position) {
+            events.push(TrackerEvent::ItemSpawned {
+                position,
+                item_type: item.item_type,
+            });
+        }
+    }
+}
+
+fn diff_tanks(previous: &GameState, current: &GameState, events: &mut Vec<TrackerEvent>) {
+    for (to, tank) in current.tanks() {
+        let owner_id = tank.owner_id.clone();
+        let Some((from, before)) = previous.my_tank(&tank.owner_id) else {
+            events.push(TrackerEvent::TankAppeared {
+                owner_id,
+                position: to,
+            });
+            continue;
+        };
+
+        if from != to {
+            events.push(TrackerEvent::TankMoved {
+                owner_id: owner_id.clone(),
+                from,
+                to,
+            });
+        }
+
+        if before.direction != tank.direction {
+            events.push(TrackerEvent::TankRotated {
+                owner_id: owner_id.clone(),
+                from: before.direction,
+                to: tank.direction,
+            });
+        }
+
+        if before.turret.direction != tank.turret.direction {
+            events.push(TrackerEvent::TurretRotated {
+                owner_id,
+                from: before.turret.direction,
+                to: tank.turret.direction,
+            });
+        }
+    }
+
+    for (position, tank) in previous.tanks() {
+        if current.my_tank(&tank.owner_id).is_none() {
+            events.push(TrackerEvent::TankDisappeared {
+                owner_id: tank.owner_id.clone(),
+                position,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::packet::packets::game_state::player::Player;
+    use crate::ws_client::packet::packets::game_state::tile::item::Item;
+    use crate::ws_client::packet::packets::game_state::tile::mine::Mine;
+    use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+    use crate::ws_client::packet::packets::game_state::tile::tile::{Tile, TileEntity};
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+
+    fn empty_game_state(tick: u64) -> GameState {
+        let map = vec![vec![Tile::new(true, None, vec![]); 6]; 6];
+        let player = Player::new("enemy".into(), "Enemy".into(), 0, 0, None, None, None);
+        GameState::new(tick.to_string(), map, vec![player], tick, vec![])
+    }
+
+    fn tank(direction: Direction, turret_direction: Direction) -> TileEntity {
+        TileEntity::Tank(Tank::new(
+            direction,
+            None,
+            "enemy".into(),
+            Turret::new(None, None, turret_direction),
+            None,
+        ))
+    }
+
+    fn place(game_state: &mut GameState, x: usize, y: usize, entity: TileEntity) {
+        game_state.map[y][x].entities.push(entity);
+    }
+
+    #[test]
+    fn test_bullet_events() {
+        let mut first = empty_game_state(0);
+        place(
+            &mut first,
+            0,
+            0,
+            TileEntity::Bullet(Bullet::new(Direction::Right, 1, 2.0, BulletType::Double)),
+        );
+        place(
+            &mut first,
+            0,
+            5,
+            TileEntity::Bullet(Bullet::new(Direction::Up, 2, 1.0, BulletType::Basic)),
+        );
+
+        let mut second = empty_game_state(1);
+        place(
+            &mut second,
+            2,
+            0,
+            TileEntity::Bullet(Bullet::new(Direction::Right, 1, 2.0, BulletType::Double)),
+        );
+
+        let mut tracker = EntityTracker::new();
+        assert!(tracker.update(&first).is_empty());
+
+        let events = tracker.update(&second);
+        assert_eq!(
+            events,
+            vec![
+                TrackerEvent::BulletMoved {
+                    id: 1,
+                    from: Position::new(0, 0),
+                    to: Position::new(2, 0),
+                },
+                TrackerEvent::BulletDespawned {
+                    id: 2,
+                    position: Position::new(0, 5),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_infers_movement_and_rotation() {
+        let mut first = empty_game_state(0);
+        place(&mut first, 2, 2, tank(Direction::Right, Direction::Up));
+
+        let mut moved = empty_game_state(1);
+        place(&mut moved, 1, 2, tank(Direction::Right, Direction::Up));
+
+        let mut rotated = empty_game_state(2);
+        place(&mut rotated, 1, 2, tank(Direction::Down, Direction::Left));
+
+        let mut tracker = EntityTracker::new();
+        tracker.update(&first);
+
+        let events = tracker.update(&moved);
+        assert_eq!(
+            events,
+            vec![TrackerEvent::TankMoved {
+                owner_id: "enemy".into(),
+                from: Position::new(2, 2),
+                to: Position::new(1, 2),
+            }]
+        );
+        assert_eq!(
+            tracker.last_action("enemy"),
+            Some(&BotResponse::Movement {
+                direction: MoveDirection::Backward
+            })
+        );
+
+        let events = tracker.update(&rotated);
+        assert_eq!(events.len(), 2);
+        assert!(events[0].is_tank_rotated() && events[1].is_turret_rotated());
+        assert_eq!(
+            tracker.last_action("enemy"),
+            Some(&BotResponse::Rotation {
+                tank_rotation: Some(Rotation::Right),
+                turret_rotation: Some(Rotation::Left),
+            })
+        );
+    }
+
+    #[test]
+    fn test_infers_abilities() {
+        let mut first = empty_game_state(0);
+        place(&mut first, 2, 2, tank(Direction::Right, Direction::Down));
+
+        let mut fired = empty_game_state(1);
+        place(&mut fired, 2, 2, tank(Direction::Right, Direction::Down));
+        place(
+            &mut fired,
+            2,
+            3,
+            TileEntity::Bullet(Bullet::new(Direction::Down, 7, 1.0, BulletType::Basic)),
+        );
+        assert_eq!(
+            infer_action(&first, &fired, "enemy"),
+            Some(BotResponse::AbilityUse {
+                ability_type: AbilityType::FireBullet
+            })
+        );
+
+        let mut dropped = empty_game_state(1);
+        place(&mut dropped, 2, 2, tank(Direction::Right, Direction::Down));
+        place(&mut dropped, 1, 2, TileEntity::Mine(Mine::new(8, None)));
+        assert_eq!(
+            infer_action(&first, &dropped, "enemy"),
+            Some(BotResponse::AbilityUse {
+                ability_type: AbilityType::DropMine
+            })
+        );
+        assert!(diff(&first, &dropped)[0].is_mine_placed());
+
+        assert_eq!(
+            infer_action(&first, &first, "enemy"),
+            Some(BotResponse::Pass)
+        );
+        assert_eq!(infer_action(&first, &empty_game_state(1), "enemy"), None);
+    }
+
+    #[test]
+    fn test_item_picked_up_and_player_died() {
+        let mut first = empty_game_state(0);
+        place(
+            &mut first,
+            3,
+            3,
+            TileEntity::Item(Item::new(ItemType::Laser)),
+        );
+        place(&mut first, 3, 2, tank(Direction::Down, Direction::Down));
+
+        let mut second = empty_game_state(1);
+        place(&mut second, 3, 3, tank(Direction::Down, Direction::Down));
+        second.players[0].ticks_to_regen = Some(10);
+
+        let events = diff(&first, &second);
+        assert!(events.contains(&TrackerEvent::ItemPickedUp {
+            position: Position::new(3, 3),
+            item_type: ItemType::Laser,
+            owner_id: Some("enemy".into()),
+        }));
+        assert!(events.contains(&TrackerEvent::PlayerDied {
+            player_id: "enemy".into()
+        }));
+    }
+}
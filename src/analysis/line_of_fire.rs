@@ -0,0 +1,189 @@
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+
+impl GameState {
+    /// Iterates over the tiles in the given direction from `from`, excluding it,
+    /// up to the first wall or the edge of the map.
+    ///
+    /// These are the tiles a bullet fired from `from` flies through
+    /// if nothing stops it earlier.
+    pub fn line_of_fire(
+        &self,
+        from: Position,
+        direction: Direction,
+    ) -> impl Iterator<Item = Position> + '_ {
+        std::iter::successors(Some(from), move |&position| position.neighbour(direction))
+            .skip(1)
+            .take_while(|&position| !self.is_wall(position))
+    }
+
+    /// Returns the tiles covered by a laser fired from `from` in the given direction.
+    pub fn laser_tiles(&self, from: Position, direction: Direction) -> Vec<Position> {
+        self.line_of_fire(from, direction).collect()
+    }
+
+    /// Returns the first visible tank in the line of fire, together with its
+    /// position and its distance from `from` in tiles.
+    pub fn first_tank_in_line(
+        &self,
+        from: Position,
+        direction: Direction,
+    ) -> Option<(Position, &Tank, usize)> {
+        self.line_of_fire(from, direction)
+            .enumerate()
+            .find_map(|(index, position)| {
+                let tank = self.tank_on(position)?;
+                Some((position, tank, index + 1))
+            })
+    }
+
+    /// Returns the number of ticks after which a bullet fired now from the turret
+    /// of the `shooter_id` player's tank would hit the tank of the `target_id` player.
+    ///
+    /// The `bullet_speed` is in tiles per tick, as sent in `Bullet::speed`
+    /// for the bullets on the map.
+    ///
+    /// Returns `None` if the bullet would miss, because the target is not
+    /// in the line of fire, a wall or another tank is in the way,
+    /// or either of the tanks is not visible, or if `bullet_speed` is not a positive number.
+    /// The target is assumed to stay in place.
+    pub fn ticks_to_hit(
+        &self,
+        shooter_id: &str,
+        target_id: &str,
+        bullet_speed: f64,
+    ) -> Option<u64> {
+        if bullet_speed.is_nan() || bullet_speed <= 0.0 {
+            return None;
+        }
+
+        let (position, shooter) = self.my_tank(shooter_id)?;
+        let (_, target, distance) = self.first_tank_in_line(position, shooter.turret.direction)?;
+
+        if target.owner_id != target_id {
+            return None;
+        }
+
+        Some((distance as f64 / bullet_speed).ceil() as u64)
+    }
+
+    /// Returns the enemy tanks in the same row or column as the tank
+    /// of the given player with no wall between them, together with
+    /// the direction the turret has to face to aim at each of them.
+    ///
+    /// Returns an empty vector if the player's tank is not visible.
+    pub fn enemies_in_line_of_sight(&self, player_id: &str) -> Vec<(Position, &Tank, Direction)> {
+        let Some((from, _)) = self.my_tank(player_id) else {
+            return vec![];
+        };
+
+        [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ]
+        .into_iter()
+        .flat_map(|direction| {
+            self.line_of_fire(from, direction)
+                .filter_map(move |position| {
+                    let tank = self.tank_on(position)?;
+                    (tank.owner_id != player_id).then_some((position, tank, direction))
+                })
+        })
+        .collect()
+    }
+
+    fn tank_on(&self, position: Position) -> Option<&Tank> {
+        self.tile_at(position)?
+            .entities
+            .iter()
+            .find_map(|entity| match entity {
+                TileEntity::Tank(tank) => Some(tank),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ascii_map::game_state_from_rows;
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+
+    /// Builds a game state from rows of `#` (wall), `.` (empty) and digits,
+    /// which are tanks of players with that id and their turret facing right.
+    fn game_state(rows: &[&str]) -> GameState {
+        let legend = |symbol: char| match symbol {
+            '.' => vec![],
+            owner => vec![TileEntity::Tank(Tank::new(
+                Direction::Up,
+                None,
+                owner.to_string(),
+                Turret::new(None, None, Direction::Right),
+                None,
+            ))],
+        };
+        game_state_from_rows(rows, 0, legend, |_| true)
+    }
+
+    #[test]
+    fn test_laser_tiles_stop_at_wall() {
+        let game_state = game_state(&["....", ".#..", "....", "...."]);
+
+        assert_eq!(
+            game_state.laser_tiles(Position::new(1, 3), Direction::Up),
+            vec![Position::new(1, 2)]
+        );
+        assert_eq!(
+            game_state.laser_tiles(Position::new(0, 1), Direction::Right),
+            vec![]
+        );
+        assert_eq!(
+            game_state.laser_tiles(Position::new(0, 0), Direction::Right),
+            vec![
+                Position::new(1, 0),
+                Position::new(2, 0),
+                Position::new(3, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ticks_to_hit() {
+        let game_state = game_state(&["1...2", "3.#.4", "5.67."]);
+
+        assert_eq!(game_state.ticks_to_hit("1", "2", 1.0), Some(4));
+        assert_eq!(game_state.ticks_to_hit("1", "2", 2.0), Some(2));
+        // A wall is in the way
+        assert_eq!(game_state.ticks_to_hit("3", "4", 1.0), None);
+        // The turret faces away from the target
+        assert_eq!(game_state.ticks_to_hit("2", "1", 1.0), None);
+        // The bullet hits the closer tank first
+        assert_eq!(game_state.ticks_to_hit("5", "6", 1.0), Some(2));
+        assert_eq!(game_state.ticks_to_hit("5", "7", 1.0), None);
+        // Bullets that never arrive
+        assert_eq!(game_state.ticks_to_hit("1", "2", 0.0), None);
+        assert_eq!(game_state.ticks_to_hit("1", "2", -1.0), None);
+        assert_eq!(game_state.ticks_to_hit("1", "2", f64::NAN), None);
+    }
+
+    #[test]
+    fn test_enemies_in_line_of_sight() {
+        let game_state = game_state(&["1.2", "#..", "3.4"]);
+
+        let enemies: Vec<(Position, Direction)> = game_state
+            .enemies_in_line_of_sight("1")
+            .into_iter()
+            .map(|(position, _, direction)| (position, direction))
+            .collect();
+        assert_eq!(enemies, vec![(Position::new(2, 0), Direction::Right)]);
+
+        let enemies = game_state.enemies_in_line_of_sight("4");
+        assert_eq!(enemies.len(), 2);
+        assert!(game_state.enemies_in_line_of_sight("9").is_empty());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod danger_map;
+pub mod entity_tracker;
+pub mod line_of_fire;
+pub mod world_memory;
+pub mod zone_control;
@@ -0,0 +1,291 @@
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+use std::collections::HashMap;
+
+/// What is remembered about a single tile.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TileMemory {
+    /// Whether a wall has ever been seen on the tile. Walls never move,
+    /// so this is known for sure once seen.
+    pub wall: bool,
+
+    /// Items and mines seen on the tile when it was last visible.
+    ///
+    /// Tanks are tracked separately, and bullets and lasers are not remembered,
+    /// as they are gone shortly after leaving vision.
+    pub entities: Vec<TileEntity>,
+
+    /// The tick in which the tile was last visible, or `None` if it never was.
+    pub last_seen: Option<u64>,
+}
+
+/// The last known position of a tank.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TankMemory {
+    pub position: Position,
+
+    /// The tank as it was last seen.
+    pub tank: Tank,
+
+    /// The tick in which the tank was last seen.
+    pub last_seen: u64,
+
+    /// Whether the tile the tank was last seen on has been visible without it since,
+    /// so the tank is known to have moved somewhere out of sight.
+    pub moved_away: bool,
+}
+
+/// Something believed to be on a tile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Belief {
+    pub entity: TileEntity,
+
+    /// The tick in which the entity was last seen there.
+    pub last_seen: u64,
+}
+
+/// Knowledge about the map gathered from all game states received so far.
+///
+/// Each game state only describes the tiles visible in that tick.
+/// Feed every game state to [`WorldMemory::update`] to keep what was seen
+/// earlier, together with how old that knowledge is.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct WorldMemory {
+    /// Indexed by `[y][x]`.
+    tiles: Vec<Vec<TileMemory>>,
+
+    /// Last known tanks, by the id of their owner.
+    tanks: HashMap<String, TankMemory>,
+
+    /// The tick of the latest game state.
+    tick: u64,
+}
+
+impl WorldMemory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges a new game state into the memory.
+    ///
+    /// The memory is cleared when the tick goes backwards, as the game state
+    /// then belongs to a new game.
+    pub fn update(&mut self, game_state: &GameState) {
+        if game_state.tick < self.tick {
+            *self = WorldMemory::new();
+        }
+
+        if self.tiles.len() != game_state.map.len() {
+            self.tiles = game_state
+                .map
+                .iter()
+                .map(|row| vec![TileMemory::default(); row.len()])
+                .collect();
+        }
+
+        self.tick = game_state.tick;
+
+        for (position, tile) in game_state.tiles() {
+            let Some(memory) = self
+                .tiles
+                .get_mut(position.y)
+                .and_then(|row| row.get_mut(position.x))
+            else {
+                continue;
+            };
+
+            memory.wall |= tile.entities.iter().any(TileEntity::is_wall);
+
+            if tile.visible {
+                memory.last_seen = Some(game_state.tick);
+                memory.entities = tile
+                    .entities
+                    .iter()
+                    .filter(|entity| entity.is_item() || entity.is_mine())
+                    .cloned()
+                    .collect();
+            }
+        }
+
+        for (position, tank) in game_state.tanks() {
+            self.tanks.insert(
+                tank.owner_id.clone(),
+                TankMemory {
+                    position,
+                    tank: tank.clone(),
+                    last_seen: game_state.tick,
+                    moved_away: false,
+                },
+            );
+        }
+
+        for memory in self.tanks.values_mut() {
+            memory.moved_away |= memory.last_seen != game_state.tick
+                && game_state
+                    .tile_at(memory.position)
+                    .is_some_and(|tile| tile.visible);
+        }
+    }
+
+    /// Returns the tick of the latest game state.
+    pub fn tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns what is remembered about the tile at the given position,
+    /// or `None` if it is outside of the map.
+    pub fn tile(&self, position: Position) -> Option<&TileMemory> {
+        self.tiles.get(position.y)?.get(position.x)
+    }
+
+    /// Returns true if a wall has been seen at the given position.
+    pub fn is_wall(&self, position: Position) -> bool {
+        self.tile(position).is_some_and(|tile| tile.wall)
+    }
+
+    /// Returns the number of ticks since the tile at the given position was last visible,
+    /// or `None` if it has never been seen.
+    pub fn age(&self, position: Position) -> Option<u64> {
+        let last_seen = self.tile(position)?.last_seen?;
+        Some(self.tick - last_seen)
+    }
+
+    /// Returns the last known position of the tank of the given player.
+    pub fn tank(&self, owner_id: &str) -> Option<&TankMemory> {
+        self.tanks.get(owner_id)
+    }
+
+    /// Iterates over the last known positions of all tanks,
+    /// including the ones that have moved away from them.
+    pub fn tanks(&self) -> impl Iterator<Item = &TankMemory> {
+        self.tanks.values()
+    }
+
+    /// Returns everything believed to be on the tile at the given position.
+    ///
+    /// Walls are reported as seen in the current tick, as they never move.
+    /// Tanks known to have moved away from the tile are left out.
+    pub fn beliefs_at(&self, position: Position) -> Vec<Belief> {
+        let Some(tile) = self.tile(position) else {
+            return vec![];
+        };
+
+        let mut beliefs = vec![];
+
+        if tile.wall {
+            beliefs.push(Belief {
+                entity: TileEntity::Wall,
+                last_seen: self.tick,
+            });
+        }
+
+        if let Some(last_seen) = tile.last_seen {
+            beliefs.extend(tile.entities.iter().map(|entity| Belief {
+                entity: entity.clone(),
+                last_seen,
+            }));
+        }
+
+        beliefs.extend(
+            self.tanks
+                .values()
+                .filter(|memory| memory.position == position && !memory.moved_away)
+                .map(|memory| Belief {
+                    entity: TileEntity::Tank(memory.tank.clone()),
+                    last_seen: memory.last_seen,
+                }),
+        );
+
+        beliefs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ascii_map::game_state_from_rows;
+    use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+    use crate::ws_client::packet::packets::game_state::tile::item::{Item, ItemType};
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+
+    /// Builds a game state from rows of `#` (wall), `I` (item), `T` (tank) and `.` (empty).
+    /// Only the tiles in the given columns are visible.
+    fn game_state(tick: u64, rows: &[&str], visible_columns: &[usize]) -> GameState {
+        let legend = |symbol: char| match symbol {
+            'I' => vec![TileEntity::Item(Item::new(ItemType::Laser))],
+            'T' => vec![TileEntity::Tank(Tank::new(
+                Direction::Up,
+                None,
+                "enemy".into(),
+                Turret::new(None, None, Direction::Up),
+                None,
+            ))],
+            _ => vec![],
+        };
+        game_state_from_rows(rows, tick, legend, |position| {
+            visible_columns.contains(&position.x)
+        })
+    }
+
+    #[test]
+    fn test_remembers_tiles_out_of_sight() {
+        let mut memory = WorldMemory::new();
+        memory.update(&game_state(1, &["#I.", "..."], &[0, 1, 2]));
+        memory.update(&game_state(5, &["#..", "..."], &[2]));
+
+        assert!(memory.is_wall(Position::new(0, 0)));
+        assert_eq!(memory.age(Position::new(1, 0)), Some(4));
+        assert_eq!(memory.age(Position::new(2, 0)), Some(0));
+
+        let beliefs = memory.beliefs_at(Position::new(1, 0));
+        assert_eq!(beliefs.len(), 1);
+        assert!(beliefs[0].entity.is_item());
+        assert_eq!(beliefs[0].last_seen, 1);
+
+        // The item is forgotten once the tile is seen without it
+        memory.update(&game_state(6, &["#..", "..."], &[1]));
+        assert!(memory.beliefs_at(Position::new(1, 0)).is_empty());
+    }
+
+    #[test]
+    fn test_tracks_last_seen_tanks() {
+        let mut memory = WorldMemory::new();
+        memory.update(&game_state(1, &["T..", "..."], &[0, 1, 2]));
+        memory.update(&game_state(2, &["...", "..."], &[1, 2]));
+
+        let tank = memory.tank("enemy").unwrap();
+        assert_eq!(tank.position, Position::new(0, 0));
+        assert_eq!(tank.last_seen, 1);
+        assert!(memory.beliefs_at(Position::new(0, 0))[0].entity.is_tank());
+
+        // The tank is not where it was last seen anymore
+        memory.update(&game_state(3, &["...", "..."], &[0]));
+        let tank = memory.tank("enemy").unwrap();
+        assert_eq!(tank.position, Position::new(0, 0));
+        assert_eq!(tank.last_seen, 1);
+        assert!(tank.moved_away);
+        assert!(memory.beliefs_at(Position::new(0, 0)).is_empty());
+
+        memory.update(&game_state(4, &["...", "..T"], &[2]));
+        let tank = memory.tank("enemy").unwrap();
+        assert_eq!(tank.position, Position::new(2, 1));
+        assert!(!tank.moved_away);
+        assert_eq!(memory.tanks().count(), 1);
+        assert_eq!(memory.age(Position::new(0, 0)), Some(1));
+    }
+
+    #[test]
+    fn test_forgets_previous_game() {
+        let mut memory = WorldMemory::new();
+        memory.update(&game_state(10, &["#T.", "..."], &[0, 1, 2]));
+        memory.update(&game_state(2, &["...", "..."], &[2]));
+
+        assert_eq!(memory.tick(), 2);
+        assert!(!memory.is_wall(Position::new(0, 0)));
+        assert_eq!(memory.age(Position::new(1, 0)), None);
+        assert_eq!(memory.age(Position::new(2, 0)), Some(0));
+        assert!(memory.tank("enemy").is_none());
+    }
+}
@@ -0,0 +1,245 @@
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::zone::ZoneStatus;
+use std::collections::HashMap;
+
+/// Who controls a zone and who is inside of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneControl {
+    /// The index of the zone.
+    pub index: u8,
+
+    pub status: ZoneStatus,
+
+    /// The player who holds the zone and receives points for it.
+    pub owner: Option<String>,
+
+    /// The player who is capturing or retaking the zone.
+    pub capturer: Option<String>,
+
+    /// The remaining ticks until the capturer takes over the zone.
+    pub remaining_ticks: Option<u64>,
+
+    /// Positions of the tiles that belong to the zone, according to `Tile::zone_index`.
+    pub tiles: Vec<Position>,
+
+    /// Whether our tank stands inside the zone.
+    pub contains_us: bool,
+
+    /// The number of visible enemy tanks inside the zone.
+    pub enemies: usize,
+}
+
+impl ZoneControl {
+    /// Returns true if the given player receives points for the zone.
+    pub fn is_ours(&self, player_id: &str) -> bool {
+        self.owner.as_deref() == Some(player_id)
+    }
+}
+
+/// Zone control of the whole map from the point of view of a single player.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZoneAnalysis {
+    pub zones: Vec<ZoneControl>,
+
+    /// The current score of each player, by their ID.
+    pub scores: HashMap<String, u64>,
+
+    /// The points each player currently receives every tick for the zones they hold, by their ID.
+    pub points_per_tick: HashMap<String, u64>,
+
+    /// The points received every tick for holding a single zone.
+    pub zone_points_per_tick: u64,
+}
+
+impl ZoneAnalysis {
+    /// Analyzes the zones in the game state from the point of view of the given player,
+    /// who receives `zone_points_per_tick` points every tick for each zone they hold.
+    pub fn new(game_state: &GameState, player_id: &str, zone_points_per_tick: u64) -> ZoneAnalysis {
+        let our_position = game_state.my_tank(player_id).map(|(position, _)| position);
+
+        let zones: Vec<ZoneControl> = game_state
+            .zones
+            .iter()
+            .map(|zone| {
+                let tiles: Vec<Position> = game_state
+                    .tiles()
+                    .filter(|(_, tile)| tile.zone_index == Some(zone.index))
+                    .map(|(position, _)| position)
+                    .collect();
+
+                let enemies = game_state
+                    .tanks()
+                    .filter(|(position, tank)| {
+                        tank.owner_id != player_id && tiles.contains(position)
+                    })
+                    .count();
+
+                ZoneControl {
+                    index: zone.index,
+                    status: zone.status.clone(),
+                    owner: zone.status.owner().map(str::to_string),
+                    capturer: zone.status.capturer().map(str::to_string),
+                    remaining_ticks: zone.status.remaining_ticks(),
+                    contains_us: our_position.is_some_and(|position| tiles.contains(&position)),
+                    tiles,
+                    enemies,
+                }
+            })
+            .collect();
+
+        let scores: HashMap<String, u64> = game_state
+            .players
+            .iter()
+            .map(|player| (player.id.clone(), player.score.unwrap_or(0)))
+            .collect();
+
+        let mut points_per_tick: HashMap<String, u64> =
+            scores.keys().map(|id| (id.clone(), 0)).collect();
+        for owner in zones.iter().filter_map(|zone| zone.owner.as_ref()) {
+            *points_per_tick.entry(owner.clone()).or_default() += zone_points_per_tick;
+        }
+
+        ZoneAnalysis {
+            zones,
+            scores,
+            points_per_tick,
+            zone_points_per_tick,
+        }
+    }
+
+    /// Returns the zone with the given index.
+    pub fn zone(&self, index: u8) -> Option<&ZoneControl> {
+        self.zones.iter().find(|zone| zone.index == index)
+    }
+
+    /// Estimates the score of each player at the end of the game, by their ID,
+    /// if nobody enters or leaves any zone during the remaining ticks.
+    ///
+    /// Zones being captured or retaken are assumed to change hands once
+    /// their remaining ticks run out. The number of remaining ticks can be computed
+    /// from `ServerSettings::tick` received in the lobby data.
+    pub fn projected_scores(&self, remaining_game_ticks: u64) -> HashMap<String, u64> {
+        let mut scores = self.scores.clone();
+
+        for zone in &self.zones {
+            let takeover = zone
+                .remaining_ticks
+                .unwrap_or(remaining_game_ticks)
+                .min(remaining_game_ticks);
+
+            if let Some(owner) = &zone.owner {
+                *scores.entry(owner.clone()).or_default() += takeover * self.zone_points_per_tick;
+            }
+
+            if let Some(capturer) = &zone.capturer {
+                // The capturer receives points once the zone changes hands
+                let ticks = remaining_game_ticks.saturating_sub(takeover);
+                *scores.entry(capturer.clone()).or_default() += ticks * self.zone_points_per_tick;
+            }
+        }
+
+        scores
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::packet::packets::game_state::player::Player;
+    use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+    use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+    use crate::ws_client::packet::packets::game_state::tile::tile::{Tile, TileEntity};
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+    use crate::ws_client::packet::packets::game_state::zone::{
+        BeingRetakenStatus, CapturedStatus, Zone,
+    };
+
+    fn game_state() -> GameState {
+        let mut map = vec![vec![Tile::new(true, None, vec![]); 6]; 6];
+        let zones = vec![
+            Zone::new(
+                b'A',
+                0,
+                0,
+                2,
+                2,
+                ZoneStatus::Captured(CapturedStatus {
+                    player_id: "us".into(),
+                }),
+            ),
+            Zone::new(
+                b'B',
+                3,
+                3,
+                2,
+                2,
+                ZoneStatus::BeingRetaken(BeingRetakenStatus {
+                    remaining_ticks: 10,
+                    captured_by_id: "us".into(),
+                    retaken_by_id: "enemy".into(),
+                }),
+            ),
+        ];
+        for zone in &zones {
+            for y in zone.y..zone.y + zone.height {
+                for x in zone.x..zone.x + zone.width {
+                    map[y as usize][x as usize].zone_index = Some(zone.index);
+                }
+            }
+        }
+
+        let tank = |owner_id: &str| {
+            TileEntity::Tank(Tank::new(
+                Direction::Up,
+                None,
+                owner_id.into(),
+                Turret::new(None, None, Direction::Up),
+                None,
+            ))
+        };
+        map[1][1].entities.push(tank("us"));
+        map[4][4].entities.push(tank("enemy"));
+
+        let players = vec![
+            Player::new("us".into(), "Us".into(), 0, 0, Some(100), None, None),
+            Player::new("enemy".into(), "Enemy".into(), 0, 0, Some(50), None, None),
+        ];
+
+        GameState::new("test".into(), map, players, 0, zones)
+    }
+
+    #[test]
+    fn test_zone_control() {
+        let analysis = ZoneAnalysis::new(&game_state(), "us", 1);
+
+        let a = analysis.zone(b'A').unwrap();
+        assert!(a.is_ours("us"));
+        assert!(a.contains_us);
+        assert_eq!(a.enemies, 0);
+        assert_eq!(a.tiles.len(), 4);
+
+        let b = analysis.zone(b'B').unwrap();
+        assert!(b.is_ours("us"));
+        assert_eq!(b.capturer.as_deref(), Some("enemy"));
+        assert_eq!(b.remaining_ticks, Some(10));
+        assert!(!b.contains_us);
+        assert_eq!(b.enemies, 1);
+
+        assert_eq!(analysis.points_per_tick["us"], 2);
+        assert_eq!(analysis.points_per_tick["enemy"], 0);
+    }
+
+    #[test]
+    fn test_projected_scores() {
+        let analysis = ZoneAnalysis::new(&game_state(), "us", 1);
+
+        let scores = analysis.projected_scores(100);
+        assert_eq!(scores["us"], 100 + 100 + 10);
+        assert_eq!(scores["enemy"], 50 + 90);
+
+        let scores = analysis.projected_scores(5);
+        assert_eq!(scores["us"], 100 + 5 + 5);
+        assert_eq!(scores["enemy"], 50);
+    }
+}
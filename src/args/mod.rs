@@ -1,4 +1,13 @@
+pub mod replay_args;
+pub mod server_args;
+pub mod spectator_args;
+pub mod tournament_args;
+
+use crate::logging::logging::LogFormat;
+use crate::ws_client::packet::enum_serialization_format::EnumSerializationFormat;
+use crate::ws_client::response_config::FallbackResponse;
 use clap::Parser;
+use std::path::PathBuf;
 
 /// Command-line arguments for configuring the bot and connecting to a server.
 ///
@@ -40,4 +49,79 @@ pub struct Args {
     /// If no code is required, this can be left empty (default is an empty string).
     #[clap(short, long, default_value = "")]
     pub code: String,
+
+    /// Connect using a secure WebSocket connection (`wss://`).
+    ///
+    /// Use this flag when the server is hosted behind TLS.
+    #[clap(long)]
+    pub secure: bool,
+
+    /// Full base url of the server, e.g. `wss://example.com:5000`.
+    ///
+    /// Overrides `--host`, `--port` and `--secure` when provided.
+    #[clap(long, conflicts_with_all = ["host", "port", "secure"])]
+    pub url: Option<String>,
+
+    /// Path to a PEM certificate to trust for secure connections.
+    ///
+    /// Useful for local testing against a server with a self-signed certificate.
+    #[clap(long)]
+    pub certificate: Option<PathBuf>,
+
+    /// How enums are encoded in the packets exchanged with the server.
+    ///
+    /// `int` sends the index of each variant instead of its name,
+    /// which makes the game states of large maps noticeably smaller.
+    #[clap(long, value_enum, default_value_t = EnumSerializationFormat::String)]
+    pub enum_format: EnumSerializationFormat,
+
+    /// Maximum number of reconnection attempts after the connection drops.
+    ///
+    /// The bot keeps its state between reconnections. Set to 0 to disable reconnecting.
+    #[clap(long, default_value = "5")]
+    pub reconnect_attempts: u32,
+
+    /// Delay before the first reconnection attempt, in milliseconds.
+    ///
+    /// The delay doubles with every failed attempt.
+    #[clap(long, default_value = "500")]
+    pub reconnect_delay: u64,
+
+    /// Upper bound of the delay between reconnection attempts, in milliseconds.
+    #[clap(long, default_value = "10000")]
+    pub reconnect_max_delay: u64,
+
+    /// Maximum time the bot may think about its next move, in milliseconds.
+    ///
+    /// If not provided, the deadline derived from the broadcast interval and ping is used.
+    #[clap(long)]
+    pub move_timeout: Option<u64>,
+
+    /// The response sent when the bot does not make its move in time.
+    #[clap(long, value_enum, default_value = "pass")]
+    pub fallback: FallbackResponse,
+
+    /// Append every packet received from and sent to the server to this file.
+    ///
+    /// Each line is a JSON object with a timestamp, the direction and the packet.
+    #[clap(long)]
+    pub record: Option<PathBuf>,
+
+    /// Show the game in a full-screen terminal UI instead of printing the map every tick.
+    ///
+    /// The side panel shows the players, zones, the last response and recent warnings.
+    /// Press `q`, `Esc` or `Ctrl+C` to quit.
+    #[clap(long)]
+    pub tui: bool,
+
+    /// Which log events are printed, e.g. `debug` or `warn,HackArena2_0_MonoTanks_Rust::bot=debug`.
+    ///
+    /// Accepts a level (`error`, `warn`, `info`, `debug` or `trace`), optionally followed
+    /// by levels of single targets, which are the module paths of the code that logs.
+    #[clap(long, default_value = "info")]
+    pub log_level: String,
+
+    /// The format of the printed log events.
+    #[clap(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
 }
@@ -0,0 +1,18 @@
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Command-line arguments for replaying a recorded game.
+///
+/// The recorded lobby data and game states are fed to the bot tick by tick,
+/// and every response that differs from the recorded one is reported.
+#[derive(Parser, Debug)]
+#[clap(
+    name = "replay",
+    version = env!("CARGO_PKG_VERSION"),
+    author = env!("CARGO_PKG_AUTHORS"),
+    about = "Replay a game recorded with `--record` through the bot, without the server"
+)]
+pub struct ReplayArgs {
+    /// Path to the file recorded with the `--record` argument of the bot.
+    pub recording: PathBuf,
+}
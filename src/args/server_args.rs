@@ -0,0 +1,90 @@
+use crate::logging::logging::LogFormat;
+use crate::simulator::rules::MIN_GRID_DIMENSION;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Command-line arguments for the local game server.
+///
+/// The server hosts a single match with the given settings and exits once
+/// the match is over. Bots connect to it exactly like to the official server.
+#[derive(Parser, Debug)]
+#[clap(
+    name = "server",
+    version = env!("CARGO_PKG_VERSION"),
+    author = env!("CARGO_PKG_AUTHORS"),
+    about = "Local MonoTanks game server for testing bots without the official server"
+)]
+pub struct ServerArgs {
+    /// The IP address or domain name the server listens on.
+    #[clap(long, default_value = "localhost")]
+    pub host: String,
+
+    /// The port on which the server listens.
+    #[clap(short, long, default_value = "5000")]
+    pub port: u16,
+
+    /// Optional access code bots have to provide to join.
+    ///
+    /// If empty (the default), bots can join without a code.
+    #[clap(short, long, default_value = "")]
+    pub code: String,
+
+    /// The number of players needed to start the game. Minimum is 2. Maximum is 4.
+    #[clap(short, long, default_value = "2", value_parser = clap::value_parser!(u32).range(2..=4))]
+    pub number_of_players: u32,
+
+    /// The dimension of the square grid. Minimum is 2.
+    #[clap(
+        long,
+        default_value = "24",
+        value_parser = clap::value_parser!(u32).range(MIN_GRID_DIMENSION as i64..)
+    )]
+    pub grid_dimension: u32,
+
+    /// The seed used to generate the map.
+    ///
+    /// If not provided, a random seed is used.
+    #[clap(long)]
+    pub seed: Option<u32>,
+
+    /// The number of ticks the game lasts.
+    #[clap(long, default_value = "3000")]
+    pub ticks: u32,
+
+    /// The interval between game state broadcasts, in milliseconds.
+    #[clap(long, default_value = "100")]
+    pub broadcast_interval: u32,
+
+    /// Broadcast the next game state as soon as all players have made their action.
+    #[clap(long)]
+    pub eager_broadcast: bool,
+
+    /// Run the game in sandbox mode, without a tick limit.
+    #[clap(long)]
+    pub sandbox: bool,
+
+    /// Optional name of the match sent to the bots.
+    #[clap(long)]
+    pub match_name: Option<String>,
+
+    /// Path to a PEM certificate chain, makes the server accept `wss://` connections only.
+    ///
+    /// A self-signed certificate is enough for local testing, the bot has to
+    /// trust it with its `--certificate` argument.
+    #[clap(long, requires = "tls_key")]
+    pub tls_certificate: Option<PathBuf>,
+
+    /// Path to the PEM private key of the `--tls-certificate`.
+    #[clap(long, requires = "tls_certificate")]
+    pub tls_key: Option<PathBuf>,
+
+    /// Which log events are printed, e.g. `debug`.
+    ///
+    /// Accepts the same filters as the `--log-level` option of the bot.
+    #[clap(long, default_value = "info")]
+    pub log_level: String,
+
+    /// The format of the printed log events.
+    #[clap(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
@@ -0,0 +1,65 @@
+use crate::logging::logging::LogFormat;
+use crate::ws_client::packet::enum_serialization_format::EnumSerializationFormat;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Command-line arguments for watching a match as a spectator.
+///
+/// The spectator sees the whole map and does not take part in the game,
+/// every game state is passed to the observer instead of a bot.
+#[derive(Parser, Debug)]
+#[clap(
+    name = "spectator",
+    version = env!("CARGO_PKG_VERSION"),
+    author = env!("CARGO_PKG_AUTHORS"),
+    about = "Watch a MonoTanks match as a spectator and pass it to an observer"
+)]
+pub struct SpectatorArgs {
+    /// The IP address or domain name of the server to connect to.
+    #[clap(long, default_value = "localhost")]
+    pub host: String,
+
+    /// The port on which the server is listening.
+    #[clap(short, long, default_value = "5000")]
+    pub port: u16,
+
+    /// Optional access code required to join the server.
+    #[clap(short, long, default_value = "")]
+    pub code: String,
+
+    /// Connect using a secure WebSocket connection (`wss://`).
+    #[clap(long)]
+    pub secure: bool,
+
+    /// Full base url of the server, e.g. `wss://example.com:5000`.
+    ///
+    /// Overrides `--host`, `--port` and `--secure` when provided.
+    #[clap(long, conflicts_with_all = ["host", "port", "secure"])]
+    pub url: Option<String>,
+
+    /// Path to a PEM certificate to trust for secure connections.
+    #[clap(long)]
+    pub certificate: Option<PathBuf>,
+
+    /// How enums are encoded in the packets exchanged with the server.
+    #[clap(long, value_enum, default_value_t = EnumSerializationFormat::String)]
+    pub enum_format: EnumSerializationFormat,
+
+    /// Append every packet received from and sent to the server to this file.
+    #[clap(long)]
+    pub record: Option<PathBuf>,
+
+    /// Show the match in a full-screen terminal UI.
+    ///
+    /// Press `q`, `Esc` or `Ctrl+C` to quit.
+    #[clap(long)]
+    pub tui: bool,
+
+    /// Which log events are printed, e.g. `debug` or `warn,HackArena2_0_MonoTanks_Rust::observer=info`.
+    #[clap(long, default_value = "info")]
+    pub log_level: String,
+
+    /// The format of the printed log events.
+    #[clap(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
@@ -0,0 +1,197 @@
+use crate::logging::logging::LogFormat;
+use crate::simulator::rules::MIN_GRID_DIMENSION;
+use crate::tournament::pairing::Pairing;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Command-line arguments for the local tournament runner.
+///
+/// Bots are played against each other over many seeds, and rated
+/// by their results, without the official server.
+#[derive(Parser, Debug)]
+#[clap(
+    name = "tournament",
+    version = env!("CARGO_PKG_VERSION"),
+    author = env!("CARGO_PKG_AUTHORS"),
+    about = "Play bots against each other locally and rate them"
+)]
+pub struct TournamentArgs {
+    /// Name of a bot implementation from this crate to enter, can be repeated.
+    ///
+    /// The available bots are listed in `src/bin/tournament.rs`.
+    #[clap(long = "bot")]
+    pub bots: Vec<String>,
+
+    /// Path to a bot executable to enter, optionally followed by arguments,
+    /// e.g. `"./old-bot --log-level warn"`. Can be repeated.
+    ///
+    /// The command is split into words like a shell does, so a path or argument
+    /// containing spaces can be quoted, e.g. `"'./my bot' --log-level warn"`.
+    /// The executable is started for every match with these arguments and `--host`,
+    /// `--port` and `--nickname` ones, and has to connect to the local server with them.
+    #[clap(long = "executable", value_parser = parse_executable)]
+    pub executables: Vec<ExecutableCommand>,
+
+    /// How entrants are matched against each other.
+    #[clap(long, value_enum, default_value_t = Pairing::RoundRobin)]
+    pub pairing: Pairing,
+
+    /// The number of rounds of a Swiss tournament.
+    #[clap(long, default_value = "5")]
+    pub rounds: usize,
+
+    /// The number of players in every match. Minimum is 2. Maximum is 4.
+    #[clap(short, long, default_value = "2", value_parser = clap::value_parser!(u32).range(2..=4))]
+    pub number_of_players: u32,
+
+    /// The number of seeds every pairing is played on.
+    #[clap(long, default_value = "10")]
+    pub seeds: u32,
+
+    /// The first seed, the following seeds are consecutive numbers.
+    #[clap(long, default_value = "0")]
+    pub first_seed: u32,
+
+    /// The maximum number of matches played at the same time.
+    ///
+    /// Defaults to the number of available CPU cores.
+    #[clap(long)]
+    pub parallel: Option<usize>,
+
+    /// The dimension of the square grid. Minimum is 2.
+    #[clap(
+        long,
+        default_value = "24",
+        value_parser = clap::value_parser!(u32).range(MIN_GRID_DIMENSION as i64..)
+    )]
+    pub grid_dimension: u32,
+
+    /// The number of ticks every match lasts.
+    #[clap(long, default_value = "3000")]
+    pub ticks: u32,
+
+    /// The longest time the server waits for responses of executables, in milliseconds.
+    #[clap(long, default_value = "100")]
+    pub broadcast_interval: u32,
+
+    /// Abort matches with executables that take longer, in seconds.
+    #[clap(long, default_value = "600")]
+    pub match_timeout: u64,
+
+    /// Which log events are printed, e.g. `info` to include the bots played in-process.
+    ///
+    /// Accepts the same filters as the `--log-level` option of the bot. By default
+    /// only the progress of the tournament and the warnings of the bots are printed.
+    #[clap(
+        long,
+        default_value = "warn,tournament=info,HackArena2_0_MonoTanks_Rust::tournament=info"
+    )]
+    pub log_level: String,
+
+    /// The format of the printed log events.
+    #[clap(long, value_enum, default_value_t = LogFormat::Text)]
+    pub log_format: LogFormat,
+}
+
+/// A bot executable entered with `--executable` and the arguments it is started with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutableCommand {
+    pub path: PathBuf,
+    pub args: Vec<String>,
+}
+
+/// Splits the command into words like a POSIX shell does. Single quotes keep
+/// everything literally, double quotes and backslashes escape the next character.
+fn parse_executable(command: &str) -> Result<ExecutableCommand, String> {
+    let mut words: Vec<String> = vec![];
+    // None between words, so quoted empty words are kept.
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\\' => {
+                let escaped = chars.next().ok_or("Trailing backslash in the command")?;
+                word.get_or_insert_with(String::new).push(escaped);
+            }
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated single quote in the command".into()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err("Unterminated double quote in the command".into()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("Unterminated double quote in the command".into()),
+                    }
+                }
+            }
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+
+    let mut words = words.into_iter();
+    let path = words.next().ok_or("Empty executable command")?;
+
+    Ok(ExecutableCommand {
+        path: PathBuf::from(path),
+        args: words.collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(path: &str, args: &[&str]) -> ExecutableCommand {
+        ExecutableCommand {
+            path: PathBuf::from(path),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_parse_executable_splits_words() {
+        assert_eq!(
+            parse_executable("  ./old-bot --log-level   warn "),
+            Ok(command("./old-bot", &["--log-level", "warn"]))
+        );
+    }
+
+    #[test]
+    fn test_parse_executable_keeps_quoted_spaces() {
+        assert_eq!(
+            parse_executable(r#"'./my bot' --nickname "Old \"bot\"" a\ b ''"#),
+            Ok(command(
+                "./my bot",
+                &["--nickname", "Old \"bot\"", "a b", ""]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_executable_rejects_invalid_commands() {
+        assert!(parse_executable("   ").is_err());
+        assert!(parse_executable("'./my bot").is_err());
+        assert!(parse_executable("./bot \"warn").is_err());
+        assert!(parse_executable("./bot \\").is_err());
+    }
+}
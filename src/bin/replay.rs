@@ -0,0 +1,53 @@
+#![allow(non_snake_case)]
+
+use clap::Parser;
+use HackArena2_0_MonoTanks_Rust::args::replay_args::ReplayArgs;
+use HackArena2_0_MonoTanks_Rust::bot::Bot;
+use HackArena2_0_MonoTanks_Rust::replay::replay::replay;
+use HackArena2_0_MonoTanks_Rust::ws_client::recorder::read_recording;
+
+fn main() {
+    let args = ReplayArgs::parse();
+
+    let recording = match read_recording(&args.recording) {
+        Ok(recording) => recording,
+        Err(e) => {
+            eprintln!(
+                "[Replay] 🌋 Error reading {} -> {}",
+                args.recording.display(),
+                e
+            );
+            std::process::exit(2);
+        }
+    };
+
+    // The bot implementation to replay is chosen here, any `BotTrait` implementation can be used
+    let report = match replay::<Bot>(&recording) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("[Replay] 🌋 Error replaying the game -> {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    for difference in &report.differences {
+        let original = match &difference.original {
+            Some(original) => format!("{:?}", original),
+            None => "nothing".to_string(),
+        };
+        println!(
+            "[Replay] ❗ Tick {}: originally sent {}, now {:?}",
+            difference.tick, original, difference.replayed
+        );
+    }
+
+    println!(
+        "[Replay] 📊 {} of {} ticks differ",
+        report.differences.len(),
+        report.ticks
+    );
+
+    if !report.is_identical() {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,92 @@
+#![allow(non_snake_case)]
+
+use clap::Parser;
+use std::io::IsTerminal;
+use tokio::signal;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+use HackArena2_0_MonoTanks_Rust::args::server_args::ServerArgs;
+use HackArena2_0_MonoTanks_Rust::logging::logging::init_logging;
+use HackArena2_0_MonoTanks_Rust::server::server::{GameServer, ServerConfig};
+use HackArena2_0_MonoTanks_Rust::ws_client::packet::packets::lobby_data::server_settings::{
+    ServerSettings, SUPPORTED_SERVER_VERSION,
+};
+
+#[tokio::main]
+async fn main() {
+    let args = ServerArgs::parse();
+
+    let ansi = std::io::stdout().is_terminal();
+    if let Err(e) = init_logging(&args.log_level, args.log_format, ansi) {
+        eprintln!("[Server] 🌋 Error setting up logging -> {}", e);
+        return;
+    }
+
+    let settings = ServerSettings::new(
+        args.grid_dimension,
+        args.number_of_players,
+        args.seed.unwrap_or_else(rand::random),
+        args.broadcast_interval,
+        args.eager_broadcast,
+        args.sandbox,
+        (!args.sandbox).then_some(args.ticks),
+        args.match_name,
+        SUPPORTED_SERVER_VERSION.to_string(),
+    );
+    let config = ServerConfig {
+        join_code: (!args.code.is_empty()).then_some(args.code),
+        settings,
+    };
+
+    let mut server = match GameServer::bind((args.host.as_str(), args.port), config).await {
+        Ok(server) => server,
+        Err(e) => {
+            error!("🌋 Error binding to {}:{} -> {}", args.host, args.port, e);
+            return;
+        }
+    };
+
+    let mut scheme = "ws";
+    if let (Some(certificate), Some(key)) = (&args.tls_certificate, &args.tls_key) {
+        server = match server.with_tls(certificate, key) {
+            Ok(server) => server,
+            Err(e) => {
+                error!("🌋 Error enabling TLS -> {}", e);
+                return;
+            }
+        };
+        scheme = "wss";
+    }
+
+    info!(
+        "🚀 Listening on {}://{}:{}, waiting for {} players...",
+        scheme, args.host, args.port, args.number_of_players
+    );
+
+    let cancel_token = CancellationToken::new();
+    let ctrl_c_handler = tokio::spawn(handle_ctrl_c(cancel_token.clone()));
+
+    let game_end = server.run(cancel_token.clone()).await;
+
+    cancel_token.cancel();
+    let _ = ctrl_c_handler.await;
+
+    if let Some(game_end) = game_end {
+        for player in &game_end.players {
+            info!(
+                "🏆 {} - Score: {} - Kills: {}",
+                player.nickname, player.score, player.kills
+            );
+        }
+    }
+}
+
+async fn handle_ctrl_c(cancel_token: CancellationToken) {
+    tokio::select! {
+        _ = signal::ctrl_c() => {
+            info!("🛑 Received CTRL+C, shutting down...");
+            cancel_token.cancel();
+        }
+        _ = cancel_token.cancelled() => {}
+    }
+}
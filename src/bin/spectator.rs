@@ -0,0 +1,133 @@
+#![allow(non_snake_case)]
+
+use clap::Parser;
+use std::io::IsTerminal;
+#[cfg(feature = "tui")]
+use std::sync::Arc;
+use tokio::signal;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+#[cfg(feature = "tui")]
+use HackArena2_0_MonoTanks_Rust::tui::{tui::run_tui, viewer::Viewer};
+use HackArena2_0_MonoTanks_Rust::{
+    args::spectator_args::SpectatorArgs,
+    logging::logging::init_logging,
+    observer::Observer,
+    ws_client::{
+        recorder::Recorder, server_url::server_url, spectator_client::SpectatorClient,
+        tls_config::TlsConfig,
+    },
+};
+
+#[tokio::main]
+async fn main() {
+    let SpectatorArgs {
+        host,
+        port,
+        code,
+        secure,
+        url,
+        certificate,
+        enum_format,
+        record,
+        tui,
+        log_level,
+        log_format,
+    } = SpectatorArgs::parse();
+
+    // Colors would be shown as escape codes in the log panel of the terminal UI
+    let ansi = !tui && std::io::stdout().is_terminal();
+    if let Err(e) = init_logging(&log_level, log_format, ansi) {
+        eprintln!("[System] 🌋 Error setting up logging -> {}", e);
+        return;
+    }
+
+    #[cfg(not(feature = "tui"))]
+    if tui {
+        error!("🌋 The terminal UI requires the `tui` feature");
+        return;
+    }
+
+    info!("🚀 Starting spectator...");
+
+    let server_url = url.unwrap_or_else(|| server_url(&host, port, secure));
+    let tls_config = match certificate {
+        Some(path) => match TlsConfig::with_certificate(&path) {
+            Ok(tls_config) => tls_config,
+            Err(e) => {
+                error!("🌋 Error loading the certificate -> {}", e);
+                return;
+            }
+        },
+        None => TlsConfig::default(),
+    };
+
+    let recorder = match record.as_deref().map(Recorder::create).transpose() {
+        Ok(recorder) => recorder,
+        Err(e) => {
+            error!("🌋 Error opening the record file -> {}", e);
+            return;
+        }
+    };
+
+    let cancel_token = CancellationToken::new();
+    let ctrl_c_handler = tokio::spawn(handle_ctrl_c(cancel_token.clone()));
+
+    // The observer implementation to run is chosen here, any `ObserverTrait` implementation can be used
+    let spectator_client = match SpectatorClient::connect_to(
+        &server_url,
+        tls_config,
+        enum_format,
+        &code,
+        Observer::default(),
+        cancel_token.clone(),
+    )
+    .await
+    {
+        Ok(client) => client,
+        Err(e) => {
+            error!("🌋 Error connecting to the server -> {}", e);
+            return;
+        }
+    };
+
+    let spectator_client = match recorder {
+        Some(recorder) => spectator_client.with_recorder(recorder),
+        None => spectator_client,
+    };
+
+    #[cfg(feature = "tui")]
+    let (spectator_client, tui_handler) = if tui {
+        let viewer = Arc::new(Viewer::new());
+        let tui_handler = tokio::spawn(run_tui(viewer.clone(), cancel_token.clone()));
+        (spectator_client.with_viewer(viewer), Some(tui_handler))
+    } else {
+        (spectator_client, None)
+    };
+
+    let client_result = spectator_client.run().await;
+
+    cancel_token.cancel();
+    let _ = ctrl_c_handler.await;
+    #[cfg(feature = "tui")]
+    if let Some(tui_handler) = tui_handler {
+        if let Ok(Err(e)) = tui_handler.await {
+            error!("🌋 Error running the terminal UI -> {}", e);
+        }
+    }
+
+    if let Err(e) = client_result {
+        error!("🌋 Error running spectator client: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn handle_ctrl_c(cancel_token: CancellationToken) {
+    tokio::select! {
+        _ = signal::ctrl_c() => {
+            info!("🛑 Received CTRL+C, initiating shutdown...");
+            cancel_token.cancel();
+        }
+        _ = cancel_token.cancelled() => {}
+    }
+}
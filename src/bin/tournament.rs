@@ -0,0 +1,111 @@
+#![allow(non_snake_case)]
+
+use clap::Parser;
+use std::io::IsTerminal;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use HackArena2_0_MonoTanks_Rust::args::tournament_args::TournamentArgs;
+use HackArena2_0_MonoTanks_Rust::bot::Bot;
+use HackArena2_0_MonoTanks_Rust::logging::logging::init_logging;
+use HackArena2_0_MonoTanks_Rust::tournament::tournament::{
+    run_tournament, Entrant, TournamentConfig,
+};
+
+/// Returns an entrant named `name` running the bot implementation from this crate called `bot`.
+///
+/// Register other `BotTrait` implementations here to let them enter tournaments.
+fn in_process_bot(bot: &str, name: String) -> Option<Entrant> {
+    match bot {
+        "bot" => Some(Entrant::in_process::<Bot>(name)),
+        _ => None,
+    }
+}
+
+/// Appends a number to names entered more than once, so every entrant has a unique nickname.
+fn unique_name(name: &str, entrants: &[Entrant]) -> String {
+    let mut unique = name.to_string();
+    let mut count = 1;
+    while entrants.iter().any(|entrant| entrant.name() == unique) {
+        count += 1;
+        unique = format!("{}-{}", name, count);
+    }
+    unique
+}
+
+#[tokio::main]
+async fn main() {
+    let args = TournamentArgs::parse();
+
+    let ansi = std::io::stdout().is_terminal();
+    if let Err(e) = init_logging(&args.log_level, args.log_format, ansi) {
+        eprintln!("[Tournament] 🌋 Error setting up logging -> {}", e);
+        std::process::exit(2);
+    }
+
+    let mut entrants: Vec<Entrant> = vec![];
+    for bot in &args.bots {
+        let name = unique_name(bot, &entrants);
+        let Some(entrant) = in_process_bot(bot, name) else {
+            error!("🌋 Unknown bot {}", bot);
+            std::process::exit(2);
+        };
+        entrants.push(entrant);
+    }
+    for command in args.executables {
+        let stem = command
+            .path
+            .file_stem()
+            .map_or("executable".into(), |stem| stem.to_string_lossy());
+        let name = unique_name(&stem, &entrants);
+        entrants.push(Entrant::executable(name, command.path, command.args));
+    }
+
+    let config = TournamentConfig {
+        pairing: args.pairing,
+        players_per_match: args.number_of_players as usize,
+        seeds: (0..args.seeds)
+            .map(|offset| args.first_seed.wrapping_add(offset))
+            .collect(),
+        rounds: args.rounds,
+        parallel: args.parallel.unwrap_or_else(|| {
+            std::thread::available_parallelism().map_or(1, |parallelism| parallelism.get())
+        }),
+        grid_dimension: args.grid_dimension,
+        ticks: args.ticks,
+        broadcast_interval: args.broadcast_interval,
+        match_timeout: Duration::from_secs(args.match_timeout),
+    };
+
+    info!(
+        "🚀 Starting a {:?} tournament between {} entrants...",
+        config.pairing,
+        entrants.len()
+    );
+
+    let report = match run_tournament(&entrants, &config).await {
+        Ok(report) => report,
+        Err(e) => {
+            error!("🌋 Error running the tournament -> {}", e);
+            std::process::exit(2);
+        }
+    };
+
+    info!("📊 Results of {} matches:", report.matches.len());
+    for (rank, standing) in report.standings.iter().enumerate() {
+        info!(
+            "🏆 {}. {} - Matches: {} - Wins: {} - Avg score: {:.1} - Kills: {} - Elo: {:.0}",
+            rank + 1,
+            standing.name,
+            standing.matches,
+            standing.wins,
+            standing.average_score(),
+            standing.kills,
+            standing.rating
+        );
+    }
+
+    if !report.failures.is_empty() {
+        warn!("❗ {} matches could not be finished", report.failures.len());
+        std::process::exit(1);
+    }
+}
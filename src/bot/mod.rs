@@ -1,3 +1,4 @@
+use crate::bot_trait::tick_context::TickContext;
 use crate::bot_trait::BotTrait;
 use crate::ws_client::packet::packets::bot_response::ability_type::AbilityType;
 use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
@@ -5,13 +6,9 @@ use crate::ws_client::packet::packets::bot_response::move_direction::MoveDirecti
 use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
 use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
 use crate::ws_client::packet::packets::game_state::game_state::GameState;
-use crate::ws_client::packet::packets::game_state::tile::bullet::BulletType;
-use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
-use crate::ws_client::packet::packets::game_state::tile::item::ItemType;
-use crate::ws_client::packet::packets::game_state::tile::laser::LaserOrientation;
-use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
 use crate::ws_client::packet::packets::lobby_data::LobbyData;
 use crate::ws_client::packet::warning::Warning;
+use tracing::{debug, info, warn};
 
 pub struct Bot {
     my_id: String,
@@ -64,91 +61,18 @@ impl BotTrait for Bot {
     /// - `game_state`: The current state of the game, which includes all
     ///   necessary information for the bot to decide its next action,
     ///   such as the entire map with walls, tanks, bullets, zones, etc.
+    /// - `context`: Timing information about the current tick, such as
+    ///   the time remaining before the response has to be sent.
     ///
     /// # Returns
     /// - `BotResponse`: The action or decision made by the bot, which will
     ///   be communicated back to the game server.
-    fn next_move(&mut self, game_state: GameState) -> BotResponse {
-        // Print map
-        println!("Map:");
-        for row in &game_state.map {
-            for col in row {
-                let symbol = {
-                    if col.entities.iter().any(|entity| entity.is_wall()) {
-                        '#'
-                    } else {
-                        let entity_symbol = col.entities.iter().find_map(|entity| match entity {
-                            TileEntity::Tank(tank) if tank.owner_id == self.my_id => {
-                                Some(match tank.direction {
-                                    Direction::Left => '<',
-                                    Direction::Right => '>',
-                                    Direction::Up => '^',
-                                    Direction::Down => 'v',
-                                })
-
-                                // There is also turrent direction.
-                                // tank.turret.direction
-                            }
-                            TileEntity::Tank(_) => Some('T'),
-                            TileEntity::Bullet(bullet) => Some(match bullet.bullet_type {
-                                BulletType::Basic => match bullet.direction {
-                                    Direction::Left => '←',
-                                    Direction::Right => '→',
-                                    Direction::Up => '↑',
-                                    Direction::Down => '↓',
-                                },
-                                BulletType::Double => match bullet.direction {
-                                    Direction::Left => '⇇',
-                                    Direction::Right => '⇉',
-                                    Direction::Up => '⇈',
-                                    Direction::Down => '⇊',
-                                },
-                            }),
-                            TileEntity::Laser(laser) => Some(match laser.orientation {
-                                LaserOrientation::Horizontal => '-',
-                                LaserOrientation::Vertical => '|',
-                            }),
-                            TileEntity::Mine(_) => Some('X'),
-                            TileEntity::Item(item) => Some(match item.item_type {
-                                ItemType::Unknown => '?',
-                                ItemType::Laser => 'L',
-                                ItemType::DoubleBullet => 'D',
-                                ItemType::Radar => 'R',
-                                ItemType::Mine => 'M',
-                            }),
-                            _ => None,
-                        });
-
-                        entity_symbol.unwrap_or_else(|| {
-                            if let Some(zone_index) = col.zone_index {
-                                if col.visible {
-                                    (zone_index as u8) as char
-                                } else {
-                                    (zone_index as u8 + 32) as char
-                                }
-                            } else if col.visible {
-                                '.'
-                            } else {
-                                ' '
-                            }
-                        })
-                    }
-                };
-                print!(" {}", symbol);
-            }
-            println!();
-        }
+    fn next_move(&mut self, game_state: GameState, context: &TickContext) -> BotResponse {
+        // Time left before the response has to be sent
+        debug!("Time remaining: {} ms", context.remaining().as_millis());
 
         // Find my tank
-        let my_tank = game_state.map.iter().flatten().find_map(|tile| {
-            tile.entities.iter().find(|obj| {
-                if let TileEntity::Tank(tank) = obj {
-                    tank.owner_id == self.my_id
-                } else {
-                    false
-                }
-            })
-        });
+        let my_tank = game_state.my_tank(&self.my_id);
 
         // If our tank is not found, it is dead, and we should pass
         if my_tank.is_none() {
@@ -208,14 +132,14 @@ impl BotTrait for Bot {
     fn on_warning_received(&mut self, warning: Warning) {
         match warning {
             Warning::PlayerAlreadyMadeActionWarning => {
-                println!("⚠️ Player already made action warning")
+                warn!("⚠️ Player already made action warning")
             }
-            Warning::MissingGameStateIdWarning => println!("⚠️ Missing game state id warning"),
-            Warning::SlowResponseWarning => println!("⚠️ Slow response warning"),
+            Warning::MissingGameStateIdWarning => warn!("⚠️ Missing game state id warning"),
+            Warning::SlowResponseWarning => warn!("⚠️ Slow response warning"),
             Warning::ActionIgnoredDueToDeadWarning => {
-                println!("⚠️ Action ignored due to dead warning")
+                warn!("⚠️ Action ignored due to dead warning")
             }
-            Warning::CustomWarning { message } => println!("⚠️ Custom warning: {}", message),
+            Warning::CustomWarning { message } => warn!("⚠️ Custom warning: {}", message),
         }
     }
 
@@ -242,11 +166,11 @@ impl BotTrait for Bot {
             .unwrap();
 
         if winner.id == self.my_id {
-            println!("I won!");
+            info!("I won!");
         }
 
         for player in &game_end.players {
-            println!("Player: {} - Score: {}", player.nickname, player.score);
+            info!("Player: {} - Score: {}", player.nickname, player.score);
         }
     }
 }
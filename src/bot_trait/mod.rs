@@ -1,8 +1,11 @@
+pub mod tick_context;
+
 use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
 use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
 use crate::ws_client::packet::packets::game_state::game_state::GameState;
 use crate::ws_client::packet::packets::lobby_data::LobbyData;
 use crate::ws_client::packet::warning::Warning;
+use tick_context::TickContext;
 
 /// A trait that defines the behavior of an AI bot interacting with the game
 /// by responding to game state updates and making decisions based on the current state.
@@ -48,11 +51,14 @@ pub trait BotTrait: Send + Sync {
     /// - `game_state`: The current state of the game, which includes all
     ///   necessary information for the bot to decide its next action,
     ///   such as the entire map with walls, tanks, bullets, zones, etc.
+    /// - `context`: Timing information about the current tick. Use
+    ///   `context.remaining()` to find out how much time is left before
+    ///   the response has to be sent.
     ///
     /// # Returns
     /// - `BotResponse`: The action or decision made by the bot, which will
     ///   be communicated back to the game server.
-    fn next_move(&mut self, game_state: GameState) -> BotResponse;
+    fn next_move(&mut self, game_state: GameState, context: &TickContext) -> BotResponse;
 
     /// Called when a warning is received from the server.
     /// Please, do remember that if your bot is stuck on processing warning,
@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+/// Time reserved for sending the response, so it reaches the server before the deadline.
+pub const SAFETY_MARGIN: Duration = Duration::from_millis(5);
+
+/// Timing information about the tick the bot is responding to.
+///
+/// The deadline is derived from the broadcast interval of the server
+/// and the current ping of the player. Bots running anytime algorithms
+/// can check [`TickContext::remaining`] and stop in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickContext {
+    /// The moment the game state was received.
+    received_at: Instant,
+
+    /// The moment the response should be ready to be sent.
+    deadline: Instant,
+}
+
+impl TickContext {
+    pub fn new(received_at: Instant, deadline: Instant) -> Self {
+        TickContext {
+            received_at,
+            deadline: deadline.max(received_at),
+        }
+    }
+
+    /// Creates a context for a game state received at `received_at`.
+    ///
+    /// The time budget is the broadcast interval reduced by the ping
+    /// (in milliseconds) and the [`SAFETY_MARGIN`].
+    pub fn from_broadcast_interval(
+        received_at: Instant,
+        broadcast_interval: u64,
+        ping: u64,
+    ) -> Self {
+        let budget = Duration::from_millis(broadcast_interval.saturating_sub(ping))
+            .saturating_sub(SAFETY_MARGIN);
+
+        TickContext::new(received_at, received_at + budget)
+    }
+
+    /// Creates a context without a meaningful deadline,
+    /// e.g. when driving the bot offline with the simulator.
+    pub fn unlimited() -> Self {
+        let now = Instant::now();
+        TickContext::new(now, now + Duration::from_secs(60 * 60 * 24))
+    }
+
+    /// The moment the game state was received.
+    pub fn received_at(&self) -> Instant {
+        self.received_at
+    }
+
+    /// The moment the response should be ready to be sent.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// The total time available for this tick.
+    pub fn budget(&self) -> Duration {
+        self.deadline - self.received_at
+    }
+
+    /// Time passed since the game state was received.
+    pub fn elapsed(&self) -> Duration {
+        self.received_at.elapsed()
+    }
+
+    /// Time left until the deadline, zero if it has already passed.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns true if the deadline has already passed.
+    pub fn is_expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_broadcast_interval() {
+        let now = Instant::now();
+
+        let context = TickContext::from_broadcast_interval(now, 100, 20);
+        assert_eq!(context.budget(), Duration::from_millis(75));
+
+        // Ping higher than the interval leaves no time at all
+        let context = TickContext::from_broadcast_interval(now, 100, 150);
+        assert_eq!(context.budget(), Duration::ZERO);
+        assert!(context.is_expired());
+    }
+}
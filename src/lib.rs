@@ -1,7 +1,22 @@
 // Needed for some reason in tests where very large jsons are hardcoded
 #![recursion_limit = "256"]
+// Crate name and `module/module.rs` layout are part of the public API of this wrapper
+#![allow(non_snake_case, clippy::module_inception)]
 
+pub mod analysis;
 pub mod args;
 pub mod bot;
 pub mod bot_trait;
+pub mod logging;
+pub mod observer;
+pub mod observer_trait;
+pub mod pathfinding;
+pub mod replay;
+pub mod server;
+pub mod simulator;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod tournament;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod ws_client;
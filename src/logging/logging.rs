@@ -0,0 +1,50 @@
+use derive_more::derive::IsVariant;
+use tracing_subscriber::EnvFilter;
+
+/// How log events are written to the standard output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, IsVariant)]
+pub enum LogFormat {
+    /// Human readable lines, one per event.
+    #[default]
+    Text,
+
+    /// One JSON object per line, with the fields of the event and of its spans,
+    /// e.g. `game_state_id` and `tick`.
+    Json,
+}
+
+/// Installs the global subscriber, which writes the events enabled by `filter`
+/// to the standard output.
+///
+/// The filter uses the `RUST_LOG` syntax, a level like `debug`, optionally followed by
+/// levels of single targets, e.g. `warn,HackArena2_0_MonoTanks_Rust::bot=debug`.
+/// Bots log through the same subscriber with the macros of the `tracing` crate.
+pub fn init_logging(filter: &str, format: LogFormat, ansi: bool) -> Result<(), String> {
+    let filter = EnvFilter::try_new(filter).map_err(|e| format!("invalid filter: {}", e))?;
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stdout)
+        .with_ansi(ansi);
+
+    match format {
+        LogFormat::Text => subscriber.try_init(),
+        LogFormat::Json => subscriber
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .try_init(),
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rejects_invalid_filter() {
+        let error = init_logging("info,[tick{", LogFormat::Json, false).unwrap_err();
+        assert!(error.starts_with("invalid filter"));
+    }
+}
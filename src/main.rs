@@ -1,7 +1,24 @@
+#![allow(non_snake_case)]
+
 use clap::Parser;
+use std::io::IsTerminal;
+#[cfg(feature = "tui")]
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::signal;
 use tokio_util::sync::CancellationToken;
-use HackArena2_0_MonoTanks_Rust::{args::Args, ws_client::ws_client::WebSocketClient};
+use tracing::{error, info};
+#[cfg(feature = "tui")]
+use HackArena2_0_MonoTanks_Rust::tui::{tui::run_tui, viewer::Viewer};
+use HackArena2_0_MonoTanks_Rust::{
+    args::Args,
+    bot::Bot,
+    logging::logging::init_logging,
+    ws_client::{
+        reconnect_config::ReconnectConfig, recorder::Recorder, response_config::ResponseConfig,
+        server_url::server_url, tls_config::TlsConfig, ws_client::WebSocketClient,
+    },
+};
 
 #[tokio::main]
 async fn main() {
@@ -10,9 +27,55 @@ async fn main() {
         port,
         code,
         nickname,
+        secure,
+        url,
+        certificate,
+        enum_format,
+        reconnect_attempts,
+        reconnect_delay,
+        reconnect_max_delay,
+        move_timeout,
+        fallback,
+        record,
+        tui,
+        log_level,
+        log_format,
     } = Args::parse();
 
-    println!("[System] 🚀 Starting bot...");
+    // Colors would be shown as escape codes in the log panel of the terminal UI
+    let ansi = !tui && std::io::stdout().is_terminal();
+    if let Err(e) = init_logging(&log_level, log_format, ansi) {
+        eprintln!("[System] 🌋 Error setting up logging -> {}", e);
+        return;
+    }
+
+    #[cfg(not(feature = "tui"))]
+    if tui {
+        error!("🌋 The terminal UI requires the `tui` feature");
+        return;
+    }
+
+    info!("🚀 Starting bot...");
+
+    let server_url = url.unwrap_or_else(|| server_url(&host, port, secure));
+    let tls_config = match certificate {
+        Some(path) => match TlsConfig::with_certificate(&path) {
+            Ok(tls_config) => tls_config,
+            Err(e) => {
+                error!("🌋 Error loading the certificate -> {}", e);
+                return;
+            }
+        },
+        None => TlsConfig::default(),
+    };
+
+    let recorder = match record.as_deref().map(Recorder::create).transpose() {
+        Ok(recorder) => recorder,
+        Err(e) => {
+            error!("🌋 Error opening the record file -> {}", e);
+            return;
+        }
+    };
 
     // Create a single cancellation token for both CTRL+C and connection loss
     let cancel_token = CancellationToken::new();
@@ -20,32 +83,71 @@ async fn main() {
     // Spawn a task to handle CTRL+C
     let ctrl_c_handler = tokio::spawn(handle_ctrl_c(cancel_token.clone()));
 
-    let websocket_client =
-        match WebSocketClient::connect(&host, port, &code, &nickname, cancel_token.clone()).await {
-            Ok(client) => client,
-            Err(e) => {
-                eprintln!("[System] 🌋 Error connecting to the server -> {}", e);
-                return;
-            }
-        };
+    // The bot implementation to run is chosen here, any `BotTrait` implementation can be used
+    let websocket_client = match WebSocketClient::<Bot>::connect_to(
+        &server_url,
+        tls_config,
+        enum_format,
+        &code,
+        &nickname,
+        cancel_token.clone(),
+    )
+    .await
+    {
+        Ok(client) => client
+            .with_reconnect_config(ReconnectConfig::new(
+                reconnect_attempts,
+                Duration::from_millis(reconnect_delay),
+                Duration::from_millis(reconnect_max_delay),
+            ))
+            .with_response_config(ResponseConfig::new(
+                move_timeout.map(Duration::from_millis),
+                fallback,
+            )),
+        Err(e) => {
+            error!("🌋 Error connecting to the server -> {}", e);
+            return;
+        }
+    };
+
+    let websocket_client = match recorder {
+        Some(recorder) => websocket_client.with_recorder(recorder),
+        None => websocket_client,
+    };
+
+    // The terminal UI is drawn from a viewer the client keeps up to date
+    #[cfg(feature = "tui")]
+    let (websocket_client, tui_handler) = if tui {
+        let viewer = Arc::new(Viewer::new());
+        let tui_handler = tokio::spawn(run_tui(viewer.clone(), cancel_token.clone()));
+        (websocket_client.with_viewer(viewer), Some(tui_handler))
+    } else {
+        (websocket_client, None)
+    };
 
     // Run the WebSocket client
     let client_result = websocket_client.run().await;
 
-    // Cancel the CTRL+C handler and wait for it to complete
+    // Cancel the CTRL+C handler and the terminal UI, and wait for them to complete
     cancel_token.cancel();
     let _ = ctrl_c_handler.await;
+    #[cfg(feature = "tui")]
+    if let Some(tui_handler) = tui_handler {
+        if let Ok(Err(e)) = tui_handler.await {
+            error!("🌋 Error running the terminal UI -> {}", e);
+        }
+    }
 
-    match client_result {
-        Ok(_) => {}
-        Err(e) => eprintln!("[System] 🌋 Error running WebSocket client: {}", e),
+    if let Err(e) = client_result {
+        error!("🌋 Error running WebSocket client: {}", e);
+        std::process::exit(1);
     }
 }
 
 async fn handle_ctrl_c(cancel_token: CancellationToken) {
     tokio::select! {
         _ = signal::ctrl_c() => {
-            println!("[System] 🛑 Received CTRL+C, initiating shutdown...");
+            info!("🛑 Received CTRL+C, initiating shutdown...");
             cancel_token.cancel();
         }
         _ = cancel_token.cancelled() => {}
@@ -0,0 +1,90 @@
+use crate::observer_trait::ObserverTrait;
+use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+use std::collections::{HashMap, HashSet};
+use tracing::info;
+
+/// Comments on a match as it is played: zones changing hands, destroyed tanks
+/// and the final standings.
+///
+/// Replace it with your own `ObserverTrait` implementation in `src/bin/spectator.rs`
+/// to record statistics or analyze the strategies of other teams.
+#[derive(Debug, Default)]
+pub struct Observer {
+    /// Nicknames of the players, by their ID.
+    nicknames: HashMap<String, String>,
+
+    /// The owner of each zone in the previous tick, by its index.
+    zone_owners: HashMap<u8, Option<String>>,
+
+    /// Players whose tank was destroyed in the previous tick.
+    destroyed: HashSet<String>,
+}
+
+impl Observer {
+    fn nickname<'a>(&'a self, player_id: &'a str) -> &'a str {
+        self.nicknames
+            .get(player_id)
+            .map(String::as_str)
+            .unwrap_or(player_id)
+    }
+}
+
+impl ObserverTrait for Observer {
+    fn on_lobby_data(&mut self, lobby_data: LobbyData) {
+        info!("🎳 Players in the lobby: {}", lobby_data.players.len());
+
+        for player in lobby_data.players {
+            self.nicknames.insert(player.id, player.nickname);
+        }
+    }
+
+    fn on_game_state(&mut self, game_state: GameState) {
+        for zone in &game_state.zones {
+            let owner = zone.status.owner().map(str::to_string);
+            let previous = self.zone_owners.insert(zone.index, owner.clone());
+
+            match (previous, owner) {
+                (Some(previous), Some(owner)) if previous.as_ref() != Some(&owner) => {
+                    let nickname = self.nickname(&owner);
+                    info!("🚩 Zone {} captured by {}", zone.index as char, nickname);
+                }
+                (Some(Some(previous)), None) => {
+                    let nickname = self.nickname(&previous);
+                    info!("🏳️ Zone {} lost by {}", zone.index as char, nickname);
+                }
+                _ => {}
+            }
+        }
+
+        for player in &game_state.players {
+            let is_destroyed = player.ticks_to_regen.is_some();
+            if is_destroyed && !self.destroyed.contains(&player.id) {
+                info!("💥 Tank of {} destroyed", self.nickname(&player.id));
+            }
+        }
+
+        self.destroyed = game_state
+            .players
+            .iter()
+            .filter(|player| player.ticks_to_regen.is_some())
+            .map(|player| player.id.clone())
+            .collect();
+    }
+
+    fn on_game_ended(&mut self, game_end: GameEnd) {
+        let mut players = game_end.players;
+        players.sort_by_key(|player| std::cmp::Reverse(player.score));
+
+        for (place, player) in players.iter().enumerate() {
+            info!(
+                "🏆 {}. {} with {} points and {} kills",
+                place + 1,
+                player.nickname,
+                player.score,
+                player.kills
+            );
+        }
+    }
+}
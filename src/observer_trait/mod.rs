@@ -0,0 +1,43 @@
+use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+
+/// A trait that defines the behavior of a spectator watching a match,
+/// e.g. to render it, record it or compute statistics about it.
+///
+/// Unlike a [`crate::bot_trait::BotTrait`], an observer does not take part in the game
+/// and never responds to the server. It is driven by
+/// [`crate::ws_client::spectator_client::SpectatorClient`], which calls its methods
+/// on a blocking thread and reads the next packet once they return.
+pub trait ObserverTrait: Send {
+    /// Called whenever lobby data is received, both when joining the lobby
+    /// and after every change, e.g. when a player joins or leaves.
+    ///
+    /// # Parameters
+    /// - `lobby_data`: The current state of the lobby, with the players and
+    ///   the server settings. Its `player_id` is empty, as spectators are not players.
+    ///
+    /// # Default Behavior
+    /// By default, this method performs no action.
+    fn on_lobby_data(&mut self, lobby_data: LobbyData) {
+        let _ = lobby_data;
+    }
+
+    /// Called after each game tick, when new game state data is received from the server.
+    ///
+    /// # Parameters
+    /// - `game_state`: The current state of the game. Spectators see the whole map,
+    ///   so every tile is visible and every tank includes its details.
+    fn on_game_state(&mut self, game_state: GameState);
+
+    /// Called when the game has concluded, providing the final game results.
+    ///
+    /// # Parameters
+    /// - `game_end`: The final state of the game, containing players' scores.
+    ///
+    /// # Default Behavior
+    /// By default, this method performs no action.
+    fn on_game_ended(&mut self, game_end: GameEnd) {
+        let _ = game_end;
+    }
+}
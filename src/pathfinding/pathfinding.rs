@@ -0,0 +1,315 @@
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::bot_response::move_direction::MoveDirection;
+use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+use derive_more::derive::Constructor;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+/// Position and facing of a tank's body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Constructor)]
+pub struct Pose {
+    pub position: Position,
+    pub direction: Direction,
+}
+
+impl Pose {
+    /// Returns the pose after the tank performs the given response,
+    /// assuming nothing blocks its movement.
+    ///
+    /// Returns `None` if the tank would leave the map on the top or left side.
+    pub fn after(self, response: &BotResponse) -> Option<Pose> {
+        match response {
+            BotResponse::Movement { direction } => {
+                let direction = match direction {
+                    MoveDirection::Forward => self.direction,
+                    MoveDirection::Backward => self.direction.opposite(),
+                };
+                let position = self.position.neighbour(direction)?;
+                Some(Pose::new(position, self.direction))
+            }
+            BotResponse::Rotation {
+                tank_rotation: Some(rotation),
+                ..
+            } => Some(Pose::new(self.position, self.direction.rotate(*rotation))),
+            _ => Some(self),
+        }
+    }
+}
+
+/// Obstacles taken into account when searching for a path.
+///
+/// Walls and tiles outside of the map are always avoided.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Constructor)]
+pub struct PathOptions {
+    /// Never step on tiles with a mine.
+    pub avoid_mines: bool,
+
+    /// Never step on tiles currently occupied by a tank.
+    pub avoid_tanks: bool,
+}
+
+impl Default for PathOptions {
+    fn default() -> Self {
+        PathOptions {
+            avoid_mines: true,
+            avoid_tanks: false,
+        }
+    }
+}
+
+/// Finds the fastest way for a tank at `start` to reach `target`.
+///
+/// Every movement and every rotation takes a single tick, so the returned
+/// responses are exactly what the bot has to send, one per tick.
+/// Returns `None` if the target cannot be reached.
+pub fn path_to(
+    game_state: &GameState,
+    start: Pose,
+    target: Position,
+    options: PathOptions,
+) -> Option<Vec<BotResponse>> {
+    find_path(
+        game_state,
+        start,
+        options,
+        |position| position == target,
+        |position| position.manhattan_distance(target),
+    )
+}
+
+/// Finds the fastest way for a tank at `start` to enter the zone with the given index.
+///
+/// Returns `None` if there is no such zone or it cannot be reached.
+pub fn path_to_zone(
+    game_state: &GameState,
+    start: Pose,
+    zone_index: u8,
+    options: PathOptions,
+) -> Option<Vec<BotResponse>> {
+    let zone = game_state
+        .zones
+        .iter()
+        .find(|zone| zone.index == zone_index)?;
+
+    find_path(
+        game_state,
+        start,
+        options,
+        |position| zone.contains(position),
+        |position| zone.distance(position),
+    )
+}
+
+/// A* search over the poses of a tank.
+///
+/// `is_goal` decides which positions end the search and `heuristic` has to
+/// estimate the number of ticks needed to reach one of them without overestimating.
+pub fn find_path(
+    game_state: &GameState,
+    start: Pose,
+    options: PathOptions,
+    is_goal: impl Fn(Position) -> bool,
+    heuristic: impl Fn(Position) -> usize,
+) -> Option<Vec<BotResponse>> {
+    let mut costs: HashMap<Pose, usize> = HashMap::from([(start, 0)]);
+    let mut came_from: HashMap<Pose, (Pose, BotResponse)> = HashMap::new();
+    let mut queue = BinaryHeap::from([(Reverse(heuristic(start.position)), Reverse(0), start)]);
+
+    while let Some((_, Reverse(cost), pose)) = queue.pop() {
+        if is_goal(pose.position) {
+            return Some(reconstruct(&came_from, pose));
+        }
+
+        // A cheaper way to this pose has already been processed
+        if costs.get(&pose).is_some_and(|&best| best < cost) {
+            continue;
+        }
+
+        for response in MOVES {
+            let Some(next) = pose.after(&response) else {
+                continue;
+            };
+
+            if next.position != pose.position && !is_passable(game_state, next.position, options) {
+                continue;
+            }
+
+            let next_cost = cost + 1;
+            if costs.get(&next).map_or(true, |&best| next_cost < best) {
+                costs.insert(next, next_cost);
+                came_from.insert(next, (pose, response));
+                queue.push((
+                    Reverse(next_cost + heuristic(next.position)),
+                    Reverse(next_cost),
+                    next,
+                ));
+            }
+        }
+    }
+
+    None
+}
+
+/// Responses that change the pose of a tank.
+const MOVES: [BotResponse; 4] = [
+    BotResponse::Movement {
+        direction: MoveDirection::Forward,
+    },
+    BotResponse::Movement {
+        direction: MoveDirection::Backward,
+    },
+    BotResponse::Rotation {
+        tank_rotation: Some(Rotation::Left),
+        turret_rotation: None,
+    },
+    BotResponse::Rotation {
+        tank_rotation: Some(Rotation::Right),
+        turret_rotation: None,
+    },
+];
+
+fn is_passable(game_state: &GameState, position: Position, options: PathOptions) -> bool {
+    let Some(tile) = game_state.tile_at(position) else {
+        return false;
+    };
+
+    tile.entities.iter().all(|entity| match entity {
+        TileEntity::Wall => false,
+        TileEntity::Mine(_) => !options.avoid_mines,
+        TileEntity::Tank(_) => !options.avoid_tanks,
+        _ => true,
+    })
+}
+
+fn reconstruct(came_from: &HashMap<Pose, (Pose, BotResponse)>, mut pose: Pose) -> Vec<BotResponse> {
+    let mut responses = vec![];
+
+    while let Some((previous, response)) = came_from.get(&pose) {
+        responses.push(response.clone());
+        pose = *previous;
+    }
+
+    responses.reverse();
+    responses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ascii_map::game_state_from_rows;
+    use crate::ws_client::packet::packets::game_state::tile::mine::Mine;
+    use crate::ws_client::packet::packets::game_state::zone::{Zone, ZoneStatus};
+
+    /// Builds a game state from rows of `#` (wall), `M` (mine) and `.` (empty).
+    fn game_state(rows: &[&str]) -> GameState {
+        let legend = |symbol: char| match symbol {
+            'M' => vec![TileEntity::Mine(Mine::new(0, None))],
+            _ => vec![],
+        };
+        game_state_from_rows(rows, 0, legend, |_| true)
+    }
+
+    fn follow(start: Pose, responses: &[BotResponse]) -> Pose {
+        responses
+            .iter()
+            .fold(start, |pose, response| pose.after(response).unwrap())
+    }
+
+    #[test]
+    fn test_moves_straight_ahead() {
+        let game_state = game_state(&["....", "....", "....", "...."]);
+        let start = Pose::new(Position::new(0, 1), Direction::Right);
+
+        let path = path_to(
+            &game_state,
+            start,
+            Position::new(3, 1),
+            PathOptions::default(),
+        );
+        assert_eq!(
+            path,
+            Some(vec![
+                BotResponse::Movement {
+                    direction: MoveDirection::Forward
+                };
+                3
+            ])
+        );
+    }
+
+    #[test]
+    fn test_prefers_reversing_over_turning_around() {
+        let game_state = game_state(&["....", "....", "....", "...."]);
+        let start = Pose::new(Position::new(3, 1), Direction::Right);
+
+        let path = path_to(
+            &game_state,
+            start,
+            Position::new(1, 1),
+            PathOptions::default(),
+        );
+        assert_eq!(
+            path,
+            Some(vec![
+                BotResponse::Movement {
+                    direction: MoveDirection::Backward
+                };
+                2
+            ])
+        );
+    }
+
+    #[test]
+    fn test_goes_around_walls_and_mines() {
+        let game_state = game_state(&["....", ".#M.", "....", "...."]);
+        let start = Pose::new(Position::new(0, 1), Direction::Right);
+        let target = Position::new(3, 1);
+
+        let path = path_to(&game_state, start, target, PathOptions::default()).unwrap();
+        assert_eq!(follow(start, &path).position, target);
+        assert!(path.len() > 3);
+
+        // Stepping on the mine is allowed when mines are not avoided
+        let options = PathOptions::new(false, false);
+        let game_state = self::game_state(&["....", "..M.", "....", "...."]);
+        let path = path_to(&game_state, start, target, options).unwrap();
+        assert_eq!(path.len(), 3);
+    }
+
+    #[test]
+    fn test_unreachable_target() {
+        let game_state = game_state(&["..#.", "..#.", "..#.", "..#."]);
+        let start = Pose::new(Position::new(0, 0), Direction::Down);
+
+        assert_eq!(
+            path_to(
+                &game_state,
+                start,
+                Position::new(3, 3),
+                PathOptions::default()
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_path_to_zone() {
+        let mut game_state = game_state(&["......", "......", "......", "......"]);
+        game_state
+            .zones
+            .push(Zone::new(b'A', 4, 2, 2, 2, ZoneStatus::Neutral));
+        let start = Pose::new(Position::new(0, 0), Direction::Up);
+
+        let path = path_to_zone(&game_state, start, b'A', PathOptions::default()).unwrap();
+        assert_eq!(path.len(), 7);
+        assert!(game_state.zones[0].contains(follow(start, &path).position));
+        assert_eq!(
+            path_to_zone(&game_state, start, b'B', PathOptions::default()),
+            None
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use crate::bot_trait::tick_context::TickContext;
+use crate::bot_trait::BotTrait;
+use crate::ws_client::packet::packet::Packet;
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+use crate::ws_client::recorder::RecordedPacket;
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// A tick in which the replayed bot responded differently than in the recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayDifference {
+    pub tick: u64,
+
+    pub game_state_id: String,
+
+    /// The response sent during the recorded game,
+    /// `None` if nothing was sent for this game state.
+    pub original: Option<BotResponse>,
+
+    /// The response of the replayed bot.
+    pub replayed: BotResponse,
+}
+
+/// Result of replaying a recorded game through a bot.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ReplayReport {
+    /// The number of game states fed to the bot.
+    pub ticks: usize,
+
+    pub differences: Vec<ReplayDifference>,
+}
+
+impl ReplayReport {
+    /// Returns true if the bot responded exactly as in the recording.
+    pub fn is_identical(&self) -> bool {
+        self.differences.is_empty()
+    }
+}
+
+/// Feeds the lobby data and game states from a recording to a new bot of type `B`,
+/// tick by tick, and compares its responses with the ones originally sent.
+///
+/// Each tick gets the same time budget it had during the recorded game.
+pub fn replay<B: BotTrait>(recording: &[RecordedPacket]) -> Result<ReplayReport, String> {
+    // Responses sent during the recorded game, by game state id
+    let original_responses: HashMap<String, BotResponse> = recording
+        .iter()
+        .filter(|recorded| recorded.direction.is_sent())
+        .filter_map(|recorded| BotResponse::from_packet(recorded.packet.clone()?))
+        .collect();
+
+    let mut bot: Option<B> = None;
+    let mut lobby_data: Option<LobbyData> = None;
+    let mut report = ReplayReport::default();
+
+    for recorded in recording
+        .iter()
+        .filter(|recorded| recorded.direction.is_received())
+    {
+        // Messages that could not be parsed are skipped, like the client did
+        let Some(packet) = &recorded.packet else {
+            continue;
+        };
+
+        match packet {
+            Packet::LobbyData(new_lobby_data) => {
+                match bot.as_mut() {
+                    Some(bot) => bot.on_lobby_data_changed(new_lobby_data.clone()),
+                    None => bot = Some(B::on_joining_lobby(new_lobby_data.clone())),
+                }
+                lobby_data = Some(new_lobby_data.clone());
+            }
+            Packet::GameState(raw_game_state) => {
+                let (Some(bot), Some(lobby_data)) = (bot.as_mut(), lobby_data.as_ref()) else {
+                    return Err(format!(
+                        "Game state {} recorded before lobby data",
+                        raw_game_state.id
+                    ));
+                };
+
+                let ping = raw_game_state
+                    .players
+                    .iter()
+                    .find(|player| player.id == lobby_data.player_id)
+                    .map_or(0, |player| player.ping);
+                let context = TickContext::from_broadcast_interval(
+                    Instant::now(),
+                    lobby_data.server_settings.broadcast_interval as u64,
+                    ping,
+                );
+
+                let replayed = bot.next_move(raw_game_state.clone().into(), &context);
+                let original = original_responses.get(&raw_game_state.id).cloned();
+                report.ticks += 1;
+
+                if original.as_ref() != Some(&replayed) {
+                    report.differences.push(ReplayDifference {
+                        tick: raw_game_state.tick,
+                        game_state_id: raw_game_state.id.clone(),
+                        original,
+                        replayed,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::simulator::Simulator;
+    use crate::testing::pass_bot::PassBot;
+    use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
+    use crate::ws_client::packet::packets::game_state::game_state::GameState;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+    use crate::ws_client::recorder::PacketDirection;
+
+    struct RotatingBot;
+
+    impl BotTrait for RotatingBot {
+        fn on_joining_lobby(_: LobbyData) -> Self {
+            RotatingBot
+        }
+
+        fn next_move(&mut self, game_state: GameState, _: &TickContext) -> BotResponse {
+            match game_state.tick {
+                0 => BotResponse::Pass,
+                _ => BotResponse::Rotation {
+                    tank_rotation: Some(Rotation::Right),
+                    turret_rotation: None,
+                },
+            }
+        }
+    }
+
+    /// A recording of a short game in which the player always passed.
+    fn recording() -> Vec<RecordedPacket> {
+        let settings =
+            ServerSettings::new(16, 2, 5, 100, false, false, Some(3), None, "test".into());
+        let mut simulator = Simulator::new(5, settings).unwrap();
+        let recorded = |direction, packet| RecordedPacket {
+            timestamp: 0,
+            direction,
+            packet: Some(packet),
+            text: None,
+        };
+
+        let mut recording = vec![recorded(
+            PacketDirection::Received,
+            Packet::LobbyData(simulator.lobby_data("player-1")),
+        )];
+
+        while !simulator.is_finished() {
+            let raw_game_state = simulator.raw_game_state("player-1");
+            let game_state_id = raw_game_state.id.clone();
+
+            recording.push(recorded(
+                PacketDirection::Received,
+                Packet::GameState(raw_game_state),
+            ));
+            recording.push(recorded(
+                PacketDirection::Sent,
+                BotResponse::Pass.to_packet(game_state_id),
+            ));
+
+            simulator.tick(HashMap::new());
+        }
+
+        recording
+    }
+
+    #[test]
+    fn test_same_bot_is_identical() {
+        let report = replay::<PassBot>(&recording()).unwrap();
+
+        assert_eq!(report.ticks, 3);
+        assert!(report.is_identical());
+    }
+
+    #[test]
+    fn test_reports_differences() {
+        let report = replay::<RotatingBot>(&recording()).unwrap();
+
+        assert_eq!(report.ticks, 3);
+        assert_eq!(report.differences.len(), 2);
+        assert_eq!(report.differences[0].tick, 1);
+        assert_eq!(report.differences[0].original, Some(BotResponse::Pass));
+    }
+
+    #[test]
+    fn test_requires_lobby_data() {
+        let recording = recording().split_off(1);
+        assert!(replay::<PassBot>(&recording).is_err());
+    }
+}
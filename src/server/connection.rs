@@ -0,0 +1,143 @@
+use crate::ws_client::packet::packet::Packet;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio_tungstenite::accept_hdr_async;
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::error;
+
+/// Query parameters sent by a client in the connection URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct ConnectionQuery {
+    pub nickname: Option<String>,
+    pub join_code: Option<String>,
+    pub player_type: Option<String>,
+    pub enum_serialization_format: Option<String>,
+}
+
+impl ConnectionQuery {
+    fn parse(query: &str) -> Self {
+        let mut params: HashMap<String, String> = url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect();
+
+        ConnectionQuery {
+            nickname: params.remove("nickname"),
+            join_code: params.remove("joinCode"),
+            player_type: params.remove("playerType"),
+            enum_serialization_format: params.remove("enumSerializationFormat"),
+        }
+    }
+}
+
+/// Events sent from connection tasks to the match loop.
+#[derive(Debug)]
+pub(super) enum ServerEvent {
+    /// A client finished the WebSocket handshake.
+    Connected {
+        connection_id: u64,
+        query: ConnectionQuery,
+        sender: UnboundedSender<Message>,
+    },
+
+    /// A client sent a text message. Contains the error message if it is not a valid packet.
+    Received {
+        connection_id: u64,
+        packet: Result<Packet, String>,
+    },
+
+    /// A client closed the connection.
+    Disconnected { connection_id: u64 },
+}
+
+/// Performs the WebSocket handshake and forwards everything the client sends to the match loop.
+pub(super) async fn handle_connection<S: AsyncRead + AsyncWrite + Unpin + Send + 'static>(
+    stream: S,
+    connection_id: u64,
+    events: UnboundedSender<ServerEvent>,
+    cancel_token: CancellationToken,
+) {
+    let mut query = String::new();
+
+    // The error type is dictated by tungstenite's `Callback` trait
+    #[allow(clippy::result_large_err)]
+    let callback = |request: &Request, response: Response| {
+        query = request.uri().query().unwrap_or_default().to_string();
+        Ok(response)
+    };
+
+    let websocket_stream = match accept_hdr_async(stream, callback).await {
+        Ok(stream) => stream,
+        Err(e) => {
+            error!("🌋 WebSocket handshake error -> {}", e);
+            return;
+        }
+    };
+
+    let (mut write, mut read) = websocket_stream.split();
+    let (sender, mut receiver) = unbounded_channel();
+
+    let connected = ServerEvent::Connected {
+        connection_id,
+        query: ConnectionQuery::parse(&query),
+        sender: sender.clone(),
+    };
+    if events.send(connected).is_err() {
+        return;
+    }
+
+    let writer_task = tokio::spawn(async move {
+        while let Some(message) = receiver.recv().await {
+            let is_close = matches!(message, Message::Close(_));
+            if write.send(message).await.is_err() || is_close {
+                break;
+            }
+        }
+    });
+
+    loop {
+        tokio::select! {
+            message = read.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let packet = serde_json::from_str(&text).map_err(|e| e.to_string());
+                    let _ = events.send(ServerEvent::Received { connection_id, packet });
+                }
+                Some(Ok(Message::Ping(payload))) => {
+                    let _ = sender.send(Message::Pong(payload));
+                }
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+            _ = cancel_token.cancelled() => break,
+        }
+    }
+
+    let _ = events.send(ServerEvent::Disconnected { connection_id });
+    drop(sender);
+    let _ = writer_task.await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        let query = ConnectionQuery::parse(
+            "nickname=Bot%201&enumSerializationFormat=string&playerType=hackathonBot&joinCode=abc",
+        );
+
+        assert_eq!(
+            query,
+            ConnectionQuery {
+                nickname: Some("Bot 1".to_string()),
+                join_code: Some("abc".to_string()),
+                player_type: Some("hackathonBot".to_string()),
+                enum_serialization_format: Some("string".to_string()),
+            }
+        );
+    }
+}
@@ -0,0 +1,2 @@
+mod connection;
+pub mod server;
@@ -0,0 +1,834 @@
+use super::connection::{handle_connection, ConnectionQuery, ServerEvent};
+use crate::simulator::rules::PLAYER_COLORS;
+use crate::simulator::simulator::Simulator;
+use crate::ws_client::packet::enum_serialization_format::EnumSerializationFormat;
+use crate::ws_client::packet::packet::Packet;
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
+use crate::ws_client::packet::packets::lobby_data::lobby_player::LobbyPlayer;
+use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+use tokio::net::{TcpListener, ToSocketAddrs};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::Instant;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Configuration of a [`GameServer`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerConfig {
+    /// Code that clients have to provide in order to join, if any.
+    pub join_code: Option<String>,
+
+    /// Settings of the hosted match, sent to the clients in the lobby data.
+    pub settings: ServerSettings,
+}
+
+/// A local stand-in for the official MonoTanks server.
+///
+/// The server accepts WebSocket connections on the same URL the
+/// [`WebSocketClient`](crate::ws_client::ws_client::WebSocketClient) connects to,
+/// runs the regular lobby handshake and plays a single match using the
+/// [`Simulator`]. This allows running bots against each other without
+/// network access or the official server.
+pub struct GameServer {
+    listener: TcpListener,
+    config: ServerConfig,
+    #[cfg(feature = "rustls")]
+    tls_acceptor: Option<tokio_rustls::TlsAcceptor>,
+}
+
+impl GameServer {
+    /// Binds the server to the given address. Use port `0` to pick a free port.
+    ///
+    /// Fails with [`io::ErrorKind::InvalidInput`] if the settings cannot be simulated.
+    pub async fn bind(address: impl ToSocketAddrs, config: ServerConfig) -> io::Result<Self> {
+        Simulator::check_settings(&config.settings)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        let listener = TcpListener::bind(address).await?;
+        Ok(GameServer {
+            listener,
+            config,
+            #[cfg(feature = "rustls")]
+            tls_acceptor: None,
+        })
+    }
+
+    /// Makes the server accept secure (`wss://`) connections only,
+    /// using the PEM encoded certificate chain and private key from the given files.
+    #[cfg(feature = "rustls")]
+    pub fn with_tls(mut self, certificate: &Path, private_key: &Path) -> Result<Self, String> {
+        use rustls::pki_types::pem::PemObject;
+        use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+
+        let certificates = CertificateDer::pem_file_iter(certificate)
+            .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Invalid certificate {} -> {}", certificate.display(), e))?;
+        let private_key = PrivateKeyDer::from_pem_file(private_key)
+            .map_err(|e| format!("Invalid private key {} -> {}", private_key.display(), e))?;
+
+        let tls_config = rustls::ServerConfig::builder()
+            .with_no_client_auth()
+            .with_single_cert(certificates, private_key)
+            .map_err(|e| format!("Invalid TLS configuration -> {}", e))?;
+
+        self.tls_acceptor = Some(tokio_rustls::TlsAcceptor::from(std::sync::Arc::new(
+            tls_config,
+        )));
+        Ok(self)
+    }
+
+    /// Makes the server accept secure (`wss://`) connections only,
+    /// using the PEM encoded certificate chain and private key from the given files.
+    #[cfg(not(feature = "rustls"))]
+    pub fn with_tls(self, _certificate: &Path, _private_key: &Path) -> Result<Self, String> {
+        Err("TLS support requires the `rustls` feature".to_string())
+    }
+
+    /// Returns the address the server is listening on.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Hosts a single match and returns its results.
+    ///
+    /// Returns `None` if the server was cancelled before the match ended.
+    pub async fn run(self, cancel_token: CancellationToken) -> Option<GameEnd> {
+        let (events_tx, events_rx) = unbounded_channel();
+
+        let accept_task = tokio::spawn(accept_connections(
+            self.listener,
+            #[cfg(feature = "rustls")]
+            self.tls_acceptor,
+            events_tx,
+            cancel_token.clone(),
+        ));
+
+        let game_end = Match::new(self.config)
+            .run(events_rx, cancel_token.clone())
+            .await;
+
+        accept_task.abort();
+        game_end
+    }
+}
+
+async fn accept_connections(
+    listener: TcpListener,
+    #[cfg(feature = "rustls")] tls_acceptor: Option<tokio_rustls::TlsAcceptor>,
+    events: UnboundedSender<ServerEvent>,
+    cancel_token: CancellationToken,
+) {
+    let mut next_connection_id = 0;
+
+    loop {
+        tokio::select! {
+            connection = listener.accept() => match connection {
+                Ok((stream, _)) => {
+                    next_connection_id += 1;
+                    let connection_id = next_connection_id;
+                    let events = events.clone();
+                    let cancel_token = cancel_token.clone();
+
+                    #[cfg(feature = "rustls")]
+                    if let Some(tls_acceptor) = tls_acceptor.clone() {
+                        tokio::spawn(async move {
+                            match tls_acceptor.accept(stream).await {
+                                Ok(stream) => {
+                                    handle_connection(stream, connection_id, events, cancel_token)
+                                        .await
+                                }
+                                Err(e) => error!("🌋 TLS handshake error -> {}", e),
+                            }
+                        });
+                        continue;
+                    }
+
+                    tokio::spawn(handle_connection(stream, connection_id, events, cancel_token));
+                }
+                Err(e) => error!("🌋 Error accepting connection -> {}", e),
+            },
+            _ = cancel_token.cancelled() => break,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    Lobby,
+    Starting,
+    InProgress,
+}
+
+struct Connection {
+    sender: UnboundedSender<Message>,
+    /// Empty for spectators.
+    player_id: String,
+    enum_format: EnumSerializationFormat,
+    ready: bool,
+    /// Spectators watch the whole map and take no part in the game.
+    spectator: bool,
+}
+
+/// State of the single match hosted by the server.
+struct Match {
+    config: ServerConfig,
+    phase: Phase,
+    connections: HashMap<u64, Connection>,
+    players: Vec<LobbyPlayer>,
+    next_player_id: u64,
+    simulator: Option<Simulator>,
+    game_state_id: String,
+    previous_game_state_id: Option<String>,
+    actions: HashMap<String, BotResponse>,
+}
+
+impl Match {
+    fn new(config: ServerConfig) -> Self {
+        Match {
+            config,
+            phase: Phase::Lobby,
+            connections: HashMap::new(),
+            players: vec![],
+            next_player_id: 0,
+            simulator: None,
+            game_state_id: String::new(),
+            previous_game_state_id: None,
+            actions: HashMap::new(),
+        }
+    }
+
+    async fn run(
+        mut self,
+        mut events: UnboundedReceiver<ServerEvent>,
+        cancel_token: CancellationToken,
+    ) -> Option<GameEnd> {
+        let interval = Duration::from_millis(self.config.settings.broadcast_interval as u64);
+        let mut next_tick = Instant::now();
+
+        loop {
+            tokio::select! {
+                event = events.recv() => {
+                    let event = event?;
+                    let was_in_progress = self.phase == Phase::InProgress;
+                    self.handle_event(event);
+
+                    let started = !was_in_progress && self.phase == Phase::InProgress;
+                    let everyone_acted = self.config.settings.eager_broadcast
+                        && self.phase == Phase::InProgress
+                        && self.everyone_acted();
+
+                    if started {
+                        next_tick = Instant::now() + interval;
+                    } else if everyone_acted {
+                        next_tick = Instant::now();
+                    }
+                }
+                _ = tokio::time::sleep_until(next_tick), if self.phase == Phase::InProgress => {
+                    if let Some(game_end) = self.advance() {
+                        return Some(game_end);
+                    }
+                    next_tick = Instant::now() + interval;
+                }
+                _ = cancel_token.cancelled() => return None,
+            }
+        }
+    }
+
+    fn send(&self, connection_id: u64, packet: Packet) {
+        if let Some(connection) = self.connections.get(&connection_id) {
+            let message = Message::Text(packet.to_json(connection.enum_format));
+            let _ = connection.sender.send(message);
+        }
+    }
+
+    fn broadcast(&self, packet: Packet) {
+        for connection_id in self.connections.keys() {
+            self.send(*connection_id, packet.clone());
+        }
+    }
+
+    fn lobby_data(&self, player_id: &str) -> LobbyData {
+        LobbyData::new(
+            player_id.to_string(),
+            self.players.clone(),
+            self.config.settings.clone(),
+        )
+    }
+
+    fn broadcast_lobby_data(&self) {
+        for (connection_id, connection) in &self.connections {
+            let lobby_data = self.lobby_data(&connection.player_id);
+            self.send(*connection_id, Packet::LobbyData(lobby_data));
+        }
+    }
+
+    fn broadcast_game_state(&mut self) {
+        let Some(simulator) = self.simulator.as_ref() else {
+            return;
+        };
+
+        for (connection_id, connection) in &self.connections {
+            let raw_game_state = match connection.spectator {
+                true => simulator.spectator_raw_game_state(),
+                false => simulator.raw_game_state(&connection.player_id),
+            };
+            self.game_state_id = raw_game_state.id.clone();
+            self.send(*connection_id, Packet::GameState(raw_game_state));
+        }
+    }
+
+    fn everyone_acted(&self) -> bool {
+        let Some(simulator) = self.simulator.as_ref() else {
+            return false;
+        };
+
+        self.connections.values().all(|connection| {
+            connection.spectator
+                || self.actions.contains_key(&connection.player_id)
+                || !simulator.is_alive(&connection.player_id)
+        })
+    }
+
+    fn handle_event(&mut self, event: ServerEvent) {
+        match event {
+            ServerEvent::Connected {
+                connection_id,
+                query,
+                sender,
+            } => self.handle_connected(connection_id, query, sender),
+            ServerEvent::Received {
+                connection_id,
+                packet: Ok(packet),
+            } => self.handle_packet(connection_id, packet),
+            ServerEvent::Received {
+                connection_id,
+                packet: Err(message),
+            } => self.send(connection_id, Packet::InvalidPayloadError { message }),
+            ServerEvent::Disconnected { connection_id } => {
+                let Some(connection) = self.connections.remove(&connection_id) else {
+                    return;
+                };
+                if connection.spectator {
+                    info!("🔌 Spectator disconnected");
+                    return;
+                }
+                info!("🔌 Player {} disconnected", connection.player_id);
+
+                if self.phase == Phase::Lobby {
+                    self.players
+                        .retain(|player| player.id != connection.player_id);
+                    self.broadcast_lobby_data();
+                }
+            }
+        }
+    }
+
+    fn handle_connected(
+        &mut self,
+        connection_id: u64,
+        query: ConnectionQuery,
+        sender: UnboundedSender<Message>,
+    ) {
+        let enum_format = query
+            .enum_serialization_format
+            .as_deref()
+            .and_then(EnumSerializationFormat::from_query);
+
+        if let Some(player_id) = self.rejoining_player_id(&query) {
+            let enum_format = enum_format.unwrap_or_default();
+            self.handle_rejoined(connection_id, player_id, enum_format, sender);
+            return;
+        }
+
+        if query.player_type.as_deref() == Some("spectator") {
+            self.handle_spectator_connected(connection_id, query, enum_format, sender);
+            return;
+        }
+
+        let rejection = match &query {
+            _ if self.phase != Phase::Lobby => Some("GameInProgress"),
+            _ if self.players.len() >= self.config.settings.number_of_players as usize => {
+                Some("LobbyFull")
+            }
+            ConnectionQuery { nickname: None, .. } => Some("MissingNickname"),
+            ConnectionQuery {
+                nickname: Some(nickname),
+                ..
+            } if self.players.iter().any(|p| &p.nickname == nickname) => {
+                Some("NicknameAlreadyExists")
+            }
+            ConnectionQuery { join_code, .. }
+                if self.config.join_code.is_some() && *join_code != self.config.join_code =>
+            {
+                Some("InvalidJoinCode")
+            }
+            ConnectionQuery { player_type, .. }
+                if player_type.as_deref() != Some("hackathonBot") =>
+            {
+                Some("InvalidPlayerType")
+            }
+            _ if enum_format.is_none() => Some("InvalidEnumSerializationFormat"),
+            _ => None,
+        };
+
+        if let Some(reason) = rejection {
+            reject(&sender, reason);
+            return;
+        }
+
+        self.next_player_id += 1;
+        let player = LobbyPlayer::new(
+            format!("player-{}", self.next_player_id),
+            query.nickname.unwrap_or_default(),
+            PLAYER_COLORS[self.players.len() % PLAYER_COLORS.len()],
+        );
+        info!("🎉 {} joined as {}", player.nickname, player.id);
+
+        self.connections.insert(
+            connection_id,
+            Connection {
+                sender,
+                player_id: player.id.clone(),
+                enum_format: enum_format.unwrap_or_default(),
+                ready: false,
+                spectator: false,
+            },
+        );
+        self.players.push(player);
+        self.send(connection_id, Packet::ConnectionAccepted);
+
+        // Let everyone else know about the new player
+        for (other_id, connection) in &self.connections {
+            if *other_id != connection_id {
+                let lobby_data = self.lobby_data(&connection.player_id);
+                self.send(*other_id, Packet::LobbyData(lobby_data));
+            }
+        }
+
+        if self.players.len() == self.config.settings.number_of_players as usize {
+            info!("🎲 Lobby is full, game starting");
+            self.phase = Phase::Starting;
+            self.broadcast(Packet::GameStarting);
+            self.start_if_ready();
+        }
+    }
+
+    /// Lets a spectator watch the match, in any phase and regardless of the number of players.
+    fn handle_spectator_connected(
+        &mut self,
+        connection_id: u64,
+        query: ConnectionQuery,
+        enum_format: Option<EnumSerializationFormat>,
+        sender: UnboundedSender<Message>,
+    ) {
+        let rejection = match enum_format {
+            _ if self.config.join_code.is_some() && query.join_code != self.config.join_code => {
+                Some("InvalidJoinCode")
+            }
+            None => Some("InvalidEnumSerializationFormat"),
+            Some(_) => None,
+        };
+
+        if let Some(reason) = rejection {
+            reject(&sender, reason);
+            return;
+        }
+
+        info!("👀 Spectator joined");
+        self.connections.insert(
+            connection_id,
+            Connection {
+                sender,
+                player_id: String::new(),
+                enum_format: enum_format.unwrap_or_default(),
+                ready: true,
+                spectator: true,
+            },
+        );
+        self.send(connection_id, Packet::ConnectionAccepted);
+    }
+
+    /// Returns the id of the player a connection belongs to, if it is a player
+    /// coming back after losing its connection once the game has started.
+    fn rejoining_player_id(&self, query: &ConnectionQuery) -> Option<String> {
+        if self.phase == Phase::Lobby
+            || (self.config.join_code.is_some() && query.join_code != self.config.join_code)
+        {
+            return None;
+        }
+
+        let nickname = query.nickname.as_ref()?;
+        let player = self.players.iter().find(|p| &p.nickname == nickname)?;
+        let connected = self
+            .connections
+            .values()
+            .any(|connection| connection.player_id == player.id);
+
+        (!connected).then(|| player.id.clone())
+    }
+
+    fn handle_rejoined(
+        &mut self,
+        connection_id: u64,
+        player_id: String,
+        enum_format: EnumSerializationFormat,
+        sender: UnboundedSender<Message>,
+    ) {
+        info!("🔁 Player {} reconnected", player_id);
+
+        self.connections.insert(
+            connection_id,
+            Connection {
+                sender,
+                player_id,
+                enum_format,
+                ready: self.phase == Phase::InProgress,
+                spectator: false,
+            },
+        );
+        self.send(connection_id, Packet::ConnectionAccepted);
+
+        if self.phase == Phase::Starting {
+            self.send(connection_id, Packet::GameStarting);
+        }
+    }
+
+    fn handle_packet(&mut self, connection_id: u64, packet: Packet) {
+        let Some(connection) = self.connections.get_mut(&connection_id) else {
+            return;
+        };
+        let player_id = connection.player_id.clone();
+        let spectator = connection.spectator;
+
+        match packet {
+            Packet::Ping => self.send(connection_id, Packet::Pong),
+            Packet::Pong => {}
+            Packet::LobbyDataRequest => {
+                let lobby_data = self.lobby_data(&player_id);
+                self.send(connection_id, Packet::LobbyData(lobby_data));
+            }
+            Packet::GameStatusRequest => {
+                let status = match self.phase {
+                    Phase::InProgress => Packet::GameInProgress,
+                    Phase::Lobby | Phase::Starting => Packet::GameNotStarted,
+                };
+                self.send(connection_id, status);
+            }
+            Packet::ReadyToReceiveGameState => {
+                connection.ready = true;
+                self.start_if_ready();
+            }
+            Packet::Movement { .. }
+            | Packet::Rotation { .. }
+            | Packet::AbilityUse { .. }
+            | Packet::Pass { .. }
+                if !spectator =>
+            {
+                self.handle_action(connection_id, player_id, packet)
+            }
+            _ => self.send(connection_id, Packet::InvalidPacketUsageError),
+        }
+    }
+
+    fn handle_action(&mut self, connection_id: u64, player_id: String, packet: Packet) {
+        let Some((game_state_id, response)) = BotResponse::from_packet(packet) else {
+            return;
+        };
+
+        let warning = match self.simulator.as_ref() {
+            None => Some(Packet::InvalidPacketUsageError),
+            Some(_) if game_state_id.is_empty() => Some(Packet::MissingGameStateIdWarning),
+            Some(_) if game_state_id != self.game_state_id => {
+                let is_previous = self.previous_game_state_id.as_ref() == Some(&game_state_id);
+                Some(if is_previous {
+                    Packet::SlowResponseWarning
+                } else {
+                    Packet::CustomWarning {
+                        message: format!("Unknown game state id {}", game_state_id),
+                    }
+                })
+            }
+            Some(_) if self.actions.contains_key(&player_id) => {
+                Some(Packet::PlayerAlreadyMadeActionWarning)
+            }
+            Some(simulator) if !simulator.is_alive(&player_id) => {
+                Some(Packet::ActionIgnoredDueToDeadWarning)
+            }
+            Some(_) => None,
+        };
+
+        match warning {
+            Some(warning) => self.send(connection_id, warning),
+            None => {
+                self.actions.insert(player_id, response);
+            }
+        }
+    }
+
+    fn start_if_ready(&mut self) {
+        let everyone_ready = self.connections.values().all(|connection| connection.ready);
+        if self.phase != Phase::Starting || !everyone_ready {
+            return;
+        }
+
+        let settings = &self.config.settings;
+        let simulator =
+            Simulator::with_players(settings.seed, settings.clone(), self.players.clone())
+                .expect("the settings are checked when binding the server");
+        self.simulator = Some(simulator);
+        self.phase = Phase::InProgress;
+
+        info!("🏃 Game started");
+        self.broadcast(Packet::GameStarted);
+        self.broadcast_game_state();
+    }
+
+    /// Plays a single tick and broadcasts the results.
+    /// Returns the final results once the game has ended.
+    fn advance(&mut self) -> Option<GameEnd> {
+        let simulator = self.simulator.as_mut()?;
+        simulator.tick(std::mem::take(&mut self.actions));
+
+        if simulator.is_finished() {
+            let game_end = simulator.game_end();
+            info!("🏁 Game ended");
+
+            self.broadcast(Packet::GameEnded(game_end.clone()));
+            for connection in self.connections.values() {
+                let _ = connection.sender.send(Message::Close(None));
+            }
+
+            return Some(game_end);
+        }
+
+        self.previous_game_state_id = Some(self.game_state_id.clone());
+        self.broadcast_game_state();
+        None
+    }
+}
+
+/// Tells the client why it cannot join and closes the connection.
+fn reject(sender: &UnboundedSender<Message>, reason: &str) {
+    warn!("🚨 Connection rejected -> {}", reason);
+    let rejected = Packet::ConnectionRejected {
+        reason: reason.to_string(),
+    };
+    let _ = sender.send(Message::Text(rejected.into()));
+    let _ = sender.send(Message::Close(None));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::observer_trait::ObserverTrait;
+    use crate::testing::pass_bot::PassBot;
+    use crate::ws_client::packet::packets::game_state::game_state::GameState;
+    use crate::ws_client::server_url::server_url;
+    use crate::ws_client::spectator_client::SpectatorClient;
+    use crate::ws_client::tls_config::TlsConfig;
+    use crate::ws_client::ws_client::WebSocketClient;
+    use futures_util::future::join_all;
+    use futures_util::StreamExt;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        game_states: Vec<GameState>,
+        game_end: Option<GameEnd>,
+    }
+
+    impl ObserverTrait for RecordingObserver {
+        fn on_game_state(&mut self, game_state: GameState) {
+            self.game_states.push(game_state);
+        }
+
+        fn on_game_ended(&mut self, game_end: GameEnd) {
+            self.game_end = Some(game_end);
+        }
+    }
+
+    fn config(join_code: Option<&str>) -> ServerConfig {
+        ServerConfig {
+            join_code: join_code.map(str::to_string),
+            settings: ServerSettings::new(16, 2, 5, 10, true, false, Some(20), None, "test".into()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plays_full_match() {
+        let server = GameServer::bind("127.0.0.1:0", config(None)).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        let server_task = tokio::spawn(server.run(CancellationToken::new()));
+
+        // Each client chooses how the enums in its packets are encoded
+        let mut clients = vec![];
+        for (nickname, enum_format) in [
+            ("first", EnumSerializationFormat::String),
+            ("second", EnumSerializationFormat::Int),
+        ] {
+            let server_url = server_url("127.0.0.1", port, false);
+            let client = WebSocketClient::<PassBot>::connect_to(
+                &server_url,
+                TlsConfig::default(),
+                enum_format,
+                "",
+                nickname,
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+            clients.push(client.run());
+        }
+
+        let (game_end, results) = tokio::join!(server_task, join_all(clients));
+        assert_eq!(game_end.unwrap().unwrap().players.len(), 2);
+        assert!(results.iter().all(Result::is_ok));
+    }
+
+    #[tokio::test]
+    async fn test_spectator_watches_match() {
+        let server = GameServer::bind("127.0.0.1:0", config(None)).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        let server_task = tokio::spawn(server.run(CancellationToken::new()));
+        let server_url = server_url("127.0.0.1", port, false);
+
+        // The spectator joins first, the game still starts once both players are in
+        let spectator = SpectatorClient::connect_to(
+            &server_url,
+            TlsConfig::default(),
+            EnumSerializationFormat::Int,
+            "",
+            RecordingObserver::default(),
+            CancellationToken::new(),
+        )
+        .await
+        .unwrap();
+
+        let mut clients = vec![];
+        for nickname in ["first", "second"] {
+            let client = WebSocketClient::<PassBot>::connect_to(
+                &server_url,
+                TlsConfig::default(),
+                EnumSerializationFormat::String,
+                "",
+                nickname,
+                CancellationToken::new(),
+            )
+            .await
+            .unwrap();
+            clients.push(client.run());
+        }
+
+        let (game_end, observer, results) =
+            tokio::join!(server_task, spectator.run(), join_all(clients));
+        let observer = observer.unwrap();
+        assert!(results.iter().all(Result::is_ok));
+        assert_eq!(observer.game_end, game_end.unwrap());
+
+        // Spectators see the whole map and the details of every tank
+        assert!(!observer.game_states.is_empty());
+        for game_state in &observer.game_states {
+            assert!(game_state.tiles().all(|(_, tile)| tile.visible));
+            assert!(game_state.tanks().all(|(_, tank)| tank.health.is_some()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_join_code() {
+        let server = GameServer::bind("127.0.0.1:0", config(Some("secret")))
+            .await
+            .unwrap();
+        let port = server.local_addr().unwrap().port();
+        let cancel_token = CancellationToken::new();
+        tokio::spawn(server.run(cancel_token.clone()));
+
+        let url = WebSocketClient::<PassBot>::construct_url("127.0.0.1", port, "wrong", "bot");
+        let (mut stream, _) = tokio_tungstenite::connect_async(&url).await.unwrap();
+
+        let message = stream.next().await.unwrap().unwrap();
+        let packet: Packet = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(
+            packet,
+            Packet::ConnectionRejected {
+                reason: "InvalidJoinCode".to_string()
+            }
+        );
+
+        cancel_token.cancel();
+    }
+
+    #[tokio::test]
+    async fn test_accepts_rejoining_player() {
+        let server = GameServer::bind("127.0.0.1:0", config(None)).await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        let cancel_token = CancellationToken::new();
+        tokio::spawn(server.run(cancel_token.clone()));
+
+        let connect = |nickname: &str| {
+            let url = WebSocketClient::<PassBot>::construct_url("127.0.0.1", port, "", nickname);
+            async move { tokio_tungstenite::connect_async(&url).await.unwrap().0 }
+        };
+        let mut first = connect("first").await;
+        let _second = connect("second").await;
+        first.close(None).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let mut rejoined = connect("first").await;
+        let message = rejoined.next().await.unwrap().unwrap();
+        let packet: Packet = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(packet, Packet::ConnectionAccepted);
+
+        cancel_token.cancel();
+    }
+
+    #[cfg(feature = "rustls")]
+    #[tokio::test]
+    async fn test_plays_match_over_tls() {
+        let certified_key = rcgen::generate_simple_self_signed(vec!["localhost".into()]).unwrap();
+        let directory = std::env::temp_dir().join(format!("monotanks-tls-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let certificate = directory.join("cert.pem");
+        let private_key = directory.join("key.pem");
+        std::fs::write(&certificate, certified_key.cert.pem()).unwrap();
+        std::fs::write(&private_key, certified_key.key_pair.serialize_pem()).unwrap();
+
+        let server = GameServer::bind("127.0.0.1:0", config(None))
+            .await
+            .unwrap()
+            .with_tls(&certificate, &private_key)
+            .unwrap();
+        let port = server.local_addr().unwrap().port();
+        let server_task = tokio::spawn(server.run(CancellationToken::new()));
+
+        let server_url = format!("wss://localhost:{}", port);
+        let tls_config = TlsConfig::with_certificate(&certificate).unwrap();
+
+        let mut clients = vec![];
+        for nickname in ["first", "second"] {
+            let cancel_token = CancellationToken::new();
+            let client = WebSocketClient::<PassBot>::connect_to(
+                &server_url,
+                tls_config.clone(),
+                EnumSerializationFormat::String,
+                "",
+                nickname,
+                cancel_token,
+            )
+            .await
+            .unwrap();
+            clients.push(client.run());
+        }
+
+        let (game_end, results) = tokio::join!(server_task, join_all(clients));
+        let _ = std::fs::remove_dir_all(&directory);
+        assert_eq!(game_end.unwrap().unwrap().players.len(), 2);
+        assert!(results.iter().all(Result::is_ok));
+    }
+}
@@ -0,0 +1,69 @@
+use crate::ws_client::packet::packets::game_state::tile::bullet::BulletType;
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::item::ItemType;
+use crate::ws_client::packet::packets::game_state::tile::laser::LaserOrientation;
+
+/// A player taking part in the simulated game.
+#[derive(Debug, Clone)]
+pub(super) struct SimPlayer {
+    pub id: String,
+    pub nickname: String,
+    pub color: u64,
+    pub score: u64,
+    pub kills: u64,
+    pub ticks_to_regen: Option<u64>,
+    pub is_using_radar: bool,
+    /// The tank of the player, `None` while the player is dead.
+    pub tank: Option<SimTank>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SimTank {
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+    pub turret_direction: Direction,
+    pub health: i64,
+    pub bullet_count: i64,
+    /// `None` when the turret holds the maximum number of bullets.
+    pub ticks_to_regen_bullet: Option<i64>,
+    pub secondary_item: Option<ItemType>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SimBullet {
+    pub id: i64,
+    pub owner_id: String,
+    pub x: usize,
+    pub y: usize,
+    pub direction: Direction,
+    pub speed: f64,
+    pub bullet_type: BulletType,
+    /// Fraction of a tile travelled but not yet applied.
+    pub progress: f64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SimLaser {
+    pub id: i64,
+    pub owner_id: String,
+    pub orientation: LaserOrientation,
+    pub tiles: Vec<(usize, usize)>,
+    pub remaining_ticks: u64,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SimMine {
+    pub id: i64,
+    pub owner_id: String,
+    pub x: usize,
+    pub y: usize,
+    pub explosion_remaining_ticks: Option<i64>,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct SimItem {
+    pub x: usize,
+    pub y: usize,
+    pub item_type: ItemType,
+}
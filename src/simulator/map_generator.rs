@@ -0,0 +1,165 @@
+use super::rules::{WALL_DENSITY, ZONE_SIZE};
+use crate::ws_client::packet::packets::game_state::zone::{Zone, ZoneStatus};
+use rand::rngs::StdRng;
+use rand::Rng;
+use std::collections::VecDeque;
+
+/// Walls and zones of a freshly generated map.
+pub(super) struct GeneratedMap {
+    /// `walls[y][x]` is true if the tile contains a wall.
+    pub walls: Vec<Vec<bool>>,
+    pub zones: Vec<Zone>,
+}
+
+/// Generates a map of the given dimension.
+///
+/// Every tile that is not a wall is reachable from every other one,
+/// and zones never contain walls.
+pub(super) fn generate_map(rng: &mut StdRng, dimension: usize) -> GeneratedMap {
+    let zones = generate_zones(rng, dimension);
+    let in_zone = |x: usize, y: usize| {
+        zones.iter().any(|zone| {
+            (zone.x..zone.x + zone.width).contains(&(x as u64))
+                && (zone.y..zone.y + zone.height).contains(&(y as u64))
+        })
+    };
+
+    let mut walls = vec![vec![false; dimension]; dimension];
+    for (y, row) in walls.iter_mut().enumerate() {
+        for (x, wall) in row.iter_mut().enumerate() {
+            *wall = !in_zone(x, y) && rng.gen_bool(WALL_DENSITY);
+        }
+    }
+
+    // Make sure all zones are connected with each other
+    for pair in zones.windows(2) {
+        let (from_x, from_y) = zone_center(&pair[0]);
+        let (to_x, to_y) = zone_center(&pair[1]);
+
+        walls[from_y][from_x.min(to_x)..=from_x.max(to_x)].fill(false);
+        for row in &mut walls[from_y.min(to_y)..=from_y.max(to_y)] {
+            row[to_x] = false;
+        }
+    }
+
+    // Fill every region that is not reachable from the main one
+    let start = zones
+        .first()
+        .map(zone_center)
+        .unwrap_or((dimension / 2, dimension / 2));
+    walls[start.1][start.0] = false;
+
+    let reachable = flood_fill(&walls, start);
+    for (y, row) in walls.iter_mut().enumerate() {
+        for (x, wall) in row.iter_mut().enumerate() {
+            if !reachable[y][x] {
+                *wall = true;
+            }
+        }
+    }
+
+    GeneratedMap { walls, zones }
+}
+
+/// Finds the free open tile closest to the given one by grid distance,
+/// which may be the given tile itself. Walls do not block the search.
+pub(super) fn nearest_open_tile(
+    walls: &[Vec<bool>],
+    (x, y): (usize, usize),
+    is_free: impl Fn(usize, usize) -> bool,
+) -> Option<(usize, usize)> {
+    let dimension = walls.len();
+    let mut visited = vec![vec![false; dimension]; dimension];
+    let mut queue = VecDeque::from([(x, y)]);
+    visited[y][x] = true;
+
+    while let Some((x, y)) = queue.pop_front() {
+        if !walls[y][x] && is_free(x, y) {
+            return Some((x, y));
+        }
+
+        for (nx, ny) in neighbours(x, y, dimension) {
+            if !visited[ny][nx] {
+                visited[ny][nx] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    None
+}
+
+fn generate_zones(rng: &mut StdRng, dimension: usize) -> Vec<Zone> {
+    let size = ZONE_SIZE as usize;
+    if dimension < size + 2 {
+        return vec![];
+    }
+
+    // Small maps only have a single zone in the middle
+    let anchors = if dimension < 3 * size {
+        vec![(dimension / 2, dimension / 2)]
+    } else {
+        vec![
+            (dimension / 4, dimension / 4),
+            (dimension - dimension / 4 - 1, dimension - dimension / 4 - 1),
+        ]
+    };
+
+    anchors
+        .into_iter()
+        .enumerate()
+        .map(|(index, (x, y))| {
+            let jitter = (dimension / 8).max(1) as i64;
+            let mut clamp = |value: usize| {
+                let value = value as i64 - size as i64 / 2 + rng.gen_range(-jitter..=jitter);
+                value.clamp(1, (dimension - size - 1) as i64) as u64
+            };
+
+            Zone::new(
+                b'A' + index as u8,
+                clamp(x),
+                clamp(y),
+                ZONE_SIZE,
+                ZONE_SIZE,
+                ZoneStatus::Neutral,
+            )
+        })
+        .collect()
+}
+
+fn zone_center(zone: &Zone) -> (usize, usize) {
+    (
+        (zone.x + zone.width / 2) as usize,
+        (zone.y + zone.height / 2) as usize,
+    )
+}
+
+fn flood_fill(walls: &[Vec<bool>], start: (usize, usize)) -> Vec<Vec<bool>> {
+    let dimension = walls.len();
+    let mut reachable = vec![vec![false; dimension]; dimension];
+    let mut queue = VecDeque::from([start]);
+    reachable[start.1][start.0] = true;
+
+    while let Some((x, y)) = queue.pop_front() {
+        for (nx, ny) in neighbours(x, y, dimension) {
+            if !walls[ny][nx] && !reachable[ny][nx] {
+                reachable[ny][nx] = true;
+                queue.push_back((nx, ny));
+            }
+        }
+    }
+
+    reachable
+}
+
+fn neighbours(x: usize, y: usize, dimension: usize) -> impl Iterator<Item = (usize, usize)> {
+    [(0, -1), (1, 0), (0, 1), (-1, 0)]
+        .into_iter()
+        .filter_map(move |(dx, dy)| {
+            let nx = x as i64 + dx;
+            let ny = y as i64 + dy;
+            let in_bounds =
+                (0..dimension as i64).contains(&nx) && (0..dimension as i64).contains(&ny);
+            in_bounds.then_some((nx as usize, ny as usize))
+        })
+}
@@ -0,0 +1,4 @@
+mod entities;
+mod map_generator;
+pub mod rules;
+pub mod simulator;
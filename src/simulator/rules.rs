@@ -0,0 +1,73 @@
+//! Constants describing the game rules used by the [`Simulator`](super::simulator::Simulator).
+//!
+//! The values mirror the ones from the official MonoTanks server as closely as
+//! possible. Speeds are expressed in tiles per tick.
+
+/// Number of ticks played when the server settings do not define it.
+pub const DEFAULT_GAME_TICKS: u64 = 3000;
+
+/// Smallest dimension of the square grid a simulation can be played on.
+pub const MIN_GRID_DIMENSION: u32 = 2;
+
+/// Colors assigned to players, in joining order.
+pub const PLAYER_COLORS: [u64; 4] = [0xFFFF6464, 0xFF64C8FF, 0xFF64FF64, 0xFFFFDC64];
+
+/// Health of a freshly spawned tank.
+pub const TANK_HEALTH: i64 = 100;
+
+/// Number of ticks a dead tank waits before respawning.
+pub const TANK_REGEN_TICKS: u64 = 50;
+
+/// Maximum number of bullets a turret can hold.
+pub const MAX_BULLETS: i64 = 3;
+
+/// Number of ticks needed to regenerate a single bullet.
+pub const BULLET_REGEN_TICKS: i64 = 10;
+
+/// Speed of a basic bullet.
+pub const BASIC_BULLET_SPEED: f64 = 1.0;
+
+/// Speed of a double bullet.
+pub const DOUBLE_BULLET_SPEED: f64 = 2.0;
+
+/// Damage dealt by a basic bullet.
+pub const BASIC_BULLET_DAMAGE: i64 = 20;
+
+/// Damage dealt by a double bullet.
+pub const DOUBLE_BULLET_DAMAGE: i64 = 40;
+
+/// Number of ticks a laser stays active after being fired.
+pub const LASER_TICKS: u64 = 3;
+
+/// Damage dealt by a laser to every tank it covers, each tick it is active.
+pub const LASER_DAMAGE: i64 = 40;
+
+/// Damage dealt by an exploding mine to tanks standing on it.
+pub const MINE_DAMAGE: i64 = 50;
+
+/// Number of ticks an exploding mine stays on the map.
+pub const MINE_EXPLOSION_TICKS: i64 = 10;
+
+/// Number of ticks needed to capture or retake a zone.
+pub const ZONE_CAPTURE_TICKS: u64 = 30;
+
+/// Points received each tick by the player holding a zone.
+pub const ZONE_POINTS_PER_TICK: u64 = 1;
+
+/// Points received for destroying an enemy tank.
+pub const KILL_POINTS: u64 = 25;
+
+/// Width and height of a generated zone.
+pub const ZONE_SIZE: u64 = 4;
+
+/// Probability of a non-zone tile becoming a wall during map generation.
+pub const WALL_DENSITY: f64 = 0.12;
+
+/// Probability of an item spawning on the map in a given tick.
+pub const ITEM_SPAWN_CHANCE: f64 = 0.05;
+
+/// Maximum number of items lying on the map at the same time.
+pub const MAX_ITEMS: usize = 6;
+
+/// How far in front of the turret a tank can see.
+pub const VISION_RANGE: i64 = 10;
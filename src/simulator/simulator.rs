@@ -0,0 +1,1261 @@
+use super::entities::{SimBullet, SimItem, SimLaser, SimMine, SimPlayer, SimTank};
+use super::map_generator::{generate_map, nearest_open_tile};
+use super::rules::*;
+use crate::ws_client::packet::packets::bot_response::ability_type::AbilityType;
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::bot_response::move_direction::MoveDirection;
+use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
+use crate::ws_client::packet::packets::game_end::game_end_player::GameEndPlayer;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::player::Player;
+use crate::ws_client::packet::packets::game_state::raw_game_state::map::RawMap;
+use crate::ws_client::packet::packets::game_state::raw_game_state::RawGameState;
+use crate::ws_client::packet::packets::game_state::tile::bullet::{Bullet, BulletType};
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::item::{Item, ItemType};
+use crate::ws_client::packet::packets::game_state::tile::laser::{Laser, LaserOrientation};
+use crate::ws_client::packet::packets::game_state::tile::mine::Mine;
+use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+use crate::ws_client::packet::packets::game_state::zone::{
+    BeingCapturedStatus, BeingContestedStatus, BeingRetakenStatus, CapturedStatus, Zone, ZoneStatus,
+};
+use crate::ws_client::packet::packets::lobby_data::lobby_player::LobbyPlayer;
+use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+
+/// An in-process simulation of a MonoTanks game.
+///
+/// The simulator generates a map from a seed and advances the world one tick
+/// at a time, applying the actions chosen by each player. The produced game
+/// states use the same types as the ones received from the server, so bots can
+/// be tested without connecting to it.
+///
+/// ```
+/// use std::collections::HashMap;
+/// use HackArena2_0_MonoTanks_Rust::simulator::simulator::Simulator;
+/// use HackArena2_0_MonoTanks_Rust::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+/// use HackArena2_0_MonoTanks_Rust::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+///
+/// let settings = ServerSettings::new(24, 2, 7, 100, true, false, Some(10), None, "1.0.0".into());
+/// let mut simulator = Simulator::new(7, settings).unwrap();
+///
+/// while !simulator.is_finished() {
+///     let actions = simulator
+///         .players()
+///         .into_iter()
+///         .map(|player| (player.id, BotResponse::Pass))
+///         .collect::<HashMap<_, _>>();
+///     simulator.tick(actions);
+/// }
+///
+/// assert_eq!(simulator.current_tick(), 10);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulator {
+    seed: u32,
+    settings: ServerSettings,
+    rng: StdRng,
+    tick: u64,
+    /// `walls[y][x]` is true if the tile contains a wall.
+    walls: Vec<Vec<bool>>,
+    zones: Vec<Zone>,
+    players: Vec<SimPlayer>,
+    bullets: Vec<SimBullet>,
+    lasers: Vec<SimLaser>,
+    mines: Vec<SimMine>,
+    items: Vec<SimItem>,
+    next_entity_id: i64,
+}
+
+impl Simulator {
+    /// Creates a new simulation with `settings.number_of_players` generated players.
+    ///
+    /// Returns an error if the settings cannot be simulated, see [`Simulator::check_settings`].
+    pub fn new(seed: u32, settings: ServerSettings) -> Result<Self, String> {
+        let players = (0..settings.number_of_players as usize)
+            .map(|index| {
+                LobbyPlayer::new(
+                    format!("player-{}", index + 1),
+                    format!("Player {}", index + 1),
+                    PLAYER_COLORS[index % PLAYER_COLORS.len()],
+                )
+            })
+            .collect();
+
+        Self::with_players(seed, settings, players)
+    }
+
+    /// Creates a new simulation with the given players.
+    ///
+    /// Returns an error if the settings cannot be simulated, see [`Simulator::check_settings`].
+    pub fn with_players(
+        seed: u32,
+        settings: ServerSettings,
+        players: Vec<LobbyPlayer>,
+    ) -> Result<Self, String> {
+        Self::check_settings(&settings)?;
+
+        let mut rng = StdRng::seed_from_u64(seed as u64);
+        let map = generate_map(&mut rng, settings.grid_dimension as usize);
+
+        let mut simulator = Simulator {
+            seed,
+            settings,
+            rng,
+            tick: 0,
+            walls: map.walls,
+            zones: map.zones,
+            players: vec![],
+            bullets: vec![],
+            lasers: vec![],
+            mines: vec![],
+            items: vec![],
+            next_entity_id: 0,
+        };
+
+        for (index, player) in players.into_iter().enumerate() {
+            let tank = simulator.spawn_tank(index);
+            simulator.players.push(SimPlayer {
+                id: player.id,
+                nickname: player.nickname,
+                color: player.color,
+                score: 0,
+                kills: 0,
+                ticks_to_regen: None,
+                is_using_radar: false,
+                tank,
+            });
+        }
+
+        Ok(simulator)
+    }
+
+    /// Returns an error if a game with the given settings cannot be simulated,
+    /// i.e. if `settings.grid_dimension` is smaller than [`MIN_GRID_DIMENSION`].
+    pub fn check_settings(settings: &ServerSettings) -> Result<(), String> {
+        if settings.grid_dimension < MIN_GRID_DIMENSION {
+            return Err(format!(
+                "Grid dimension {} is smaller than {}",
+                settings.grid_dimension, MIN_GRID_DIMENSION
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Returns the seed used to generate the map.
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Returns the settings the simulation was created with.
+    pub fn settings(&self) -> &ServerSettings {
+        &self.settings
+    }
+
+    /// Returns the number of ticks simulated so far.
+    pub fn current_tick(&self) -> u64 {
+        self.tick
+    }
+
+    /// Returns the total number of ticks of the game, `None` in sandbox mode.
+    pub fn game_length(&self) -> Option<u64> {
+        if self.settings.sandbox_mode {
+            None
+        } else {
+            Some(self.settings.tick.map_or(DEFAULT_GAME_TICKS, u64::from))
+        }
+    }
+
+    /// Returns true once the configured number of ticks has been played.
+    pub fn is_finished(&self) -> bool {
+        self.game_length()
+            .is_some_and(|game_length| self.tick >= game_length)
+    }
+
+    /// Returns the players taking part in the game.
+    pub fn players(&self) -> Vec<LobbyPlayer> {
+        self.players
+            .iter()
+            .map(|player| {
+                LobbyPlayer::new(player.id.clone(), player.nickname.clone(), player.color)
+            })
+            .collect()
+    }
+
+    /// Returns true if the given player currently has a tank on the map.
+    pub fn is_alive(&self, player_id: &str) -> bool {
+        self.players
+            .iter()
+            .any(|player| player.id == player_id && player.tank.is_some())
+    }
+
+    /// Returns the lobby data as seen by the given player.
+    pub fn lobby_data(&self, player_id: &str) -> LobbyData {
+        LobbyData::new(player_id.to_string(), self.players(), self.settings.clone())
+    }
+
+    /// Returns the final results of the game.
+    pub fn game_end(&self) -> GameEnd {
+        let mut players: Vec<GameEndPlayer> = self
+            .players
+            .iter()
+            .map(|player| {
+                GameEndPlayer::new(
+                    player.id.clone(),
+                    player.nickname.clone(),
+                    player.color,
+                    player.score,
+                    player.kills,
+                )
+            })
+            .collect();
+        players.sort_by_key(|player| std::cmp::Reverse(player.score));
+
+        GameEnd::new(players)
+    }
+
+    /// Returns the game state as the given player would receive it from the server.
+    pub fn game_state(&self, player_id: &str) -> GameState {
+        self.raw_game_state(player_id).into()
+    }
+
+    /// Returns the raw game state as the given player would receive it from the server.
+    ///
+    /// Tiles outside of the player's vision only contain walls, and details
+    /// like health or bullet count are only included for the player's own tank.
+    pub fn raw_game_state(&self, player_id: &str) -> RawGameState {
+        let visibility = self.visibility(player_id);
+        self.build_raw_game_state(Some(player_id), visibility)
+    }
+
+    /// Returns the raw game state with the whole map visible, as a spectator sees it.
+    pub fn spectator_raw_game_state(&self) -> RawGameState {
+        let dimension = self.dimension();
+        self.build_raw_game_state(None, vec![vec![true; dimension]; dimension])
+    }
+
+    /// Advances the game by a single tick.
+    ///
+    /// `actions` maps player IDs to the response of their bot.
+    /// Players without an entry pass their turn.
+    pub fn tick(&mut self, mut actions: HashMap<String, BotResponse>) {
+        if self.is_finished() {
+            return;
+        }
+
+        for player in self.players.iter_mut() {
+            player.is_using_radar = false;
+        }
+
+        for index in 0..self.players.len() {
+            if let Some(action) = actions.remove(&self.players[index].id) {
+                self.apply_action(index, action);
+            }
+        }
+
+        self.move_bullets();
+        self.update_lasers();
+        self.update_mines();
+        self.pick_up_items();
+        self.update_zones();
+        self.regenerate();
+        self.spawn_items();
+
+        self.tick += 1;
+    }
+
+    fn dimension(&self) -> usize {
+        self.walls.len()
+    }
+
+    fn next_id(&mut self) -> i64 {
+        self.next_entity_id += 1;
+        self.next_entity_id
+    }
+
+    fn step(&self, (x, y): (usize, usize), direction: Direction) -> Option<(usize, usize)> {
+        let (dx, dy) = direction.offset();
+        let nx = x as i64 + dx;
+        let ny = y as i64 + dy;
+        let dimension = self.dimension() as i64;
+
+        if (0..dimension).contains(&nx) && (0..dimension).contains(&ny) {
+            Some((nx as usize, ny as usize))
+        } else {
+            None
+        }
+    }
+
+    fn is_wall(&self, (x, y): (usize, usize)) -> bool {
+        self.walls[y][x]
+    }
+
+    fn tank_at(&self, (x, y): (usize, usize)) -> Option<usize> {
+        self.players.iter().position(|player| {
+            player
+                .tank
+                .as_ref()
+                .is_some_and(|tank| tank.x == x && tank.y == y)
+        })
+    }
+
+    fn zone_at(&self, (x, y): (usize, usize)) -> Option<usize> {
+        self.zones.iter().position(|zone| {
+            (zone.x..zone.x + zone.width).contains(&(x as u64))
+                && (zone.y..zone.y + zone.height).contains(&(y as u64))
+        })
+    }
+
+    /// Returns true if a tank can move onto the tile or a mine can be dropped on it.
+    fn is_passable(&self, position: (usize, usize)) -> bool {
+        !self.is_wall(position) && self.tank_at(position).is_none()
+    }
+
+    fn is_free(&self, position: (usize, usize)) -> bool {
+        !self.is_wall(position)
+            && self.tank_at(position).is_none()
+            && self.zone_at(position).is_none()
+            && !self.mines.iter().any(|mine| (mine.x, mine.y) == position)
+            && !self.items.iter().any(|item| (item.x, item.y) == position)
+    }
+
+    /// Finds a free tile for the tank of the player with the given index,
+    /// starting the search from the player's corner of the map.
+    fn spawn_tank(&mut self, index: usize) -> Option<SimTank> {
+        let max = self.dimension() - 1;
+        let corners = [(1, 1), (max - 1, max - 1), (max - 1, 1), (1, max - 1)];
+        let corner = if index < corners.len() {
+            corners[index]
+        } else {
+            (self.rng.gen_range(0..=max), self.rng.gen_range(0..=max))
+        };
+
+        let (x, y) = nearest_open_tile(&self.walls, corner, |x, y| self.is_free((x, y)))?;
+        let direction = Direction::ALL[self.rng.gen_range(0..Direction::ALL.len())];
+
+        Some(SimTank {
+            x,
+            y,
+            direction,
+            turret_direction: direction,
+            health: TANK_HEALTH,
+            bullet_count: MAX_BULLETS,
+            ticks_to_regen_bullet: None,
+            secondary_item: None,
+        })
+    }
+
+    fn apply_action(&mut self, index: usize, action: BotResponse) {
+        let Some(tank) = self.players[index].tank.clone() else {
+            return;
+        };
+        let position = (tank.x, tank.y);
+
+        match action {
+            BotResponse::Movement { direction } => {
+                let direction = match direction {
+                    MoveDirection::Forward => tank.direction,
+                    MoveDirection::Backward => tank.direction.opposite(),
+                };
+
+                if let Some(target) = self.step(position, direction) {
+                    if self.is_passable(target) {
+                        let tank = self.players[index].tank.as_mut().unwrap();
+                        (tank.x, tank.y) = target;
+
+                        // Bullets lying on the target tile hit the tank moving onto it
+                        let (hits, bullets) = std::mem::take(&mut self.bullets)
+                            .into_iter()
+                            .partition(|bullet| (bullet.x, bullet.y) == target);
+                        self.bullets = bullets;
+                        for bullet in hits {
+                            let damage = bullet_damage(&bullet.bullet_type);
+                            self.damage_tank(index, damage, &bullet.owner_id);
+                        }
+                    }
+                }
+            }
+            BotResponse::Rotation {
+                tank_rotation,
+                turret_rotation,
+            } => {
+                let tank = self.players[index].tank.as_mut().unwrap();
+                if let Some(rotation) = tank_rotation {
+                    tank.direction = tank.direction.rotate(rotation);
+                }
+                if let Some(rotation) = turret_rotation {
+                    tank.turret_direction = tank.turret_direction.rotate(rotation);
+                }
+            }
+            BotResponse::AbilityUse { ability_type } => self.use_ability(index, ability_type),
+            BotResponse::Pass => {}
+        }
+    }
+
+    fn use_ability(&mut self, index: usize, ability_type: AbilityType) {
+        let owner_id = self.players[index].id.clone();
+        let tank = self.players[index].tank.clone().unwrap();
+        let position = (tank.x, tank.y);
+
+        let required_item = match ability_type {
+            AbilityType::FireBullet => None,
+            AbilityType::FireDoubleBullet => Some(ItemType::DoubleBullet),
+            AbilityType::UseLaser => Some(ItemType::Laser),
+            AbilityType::UseRadar => Some(ItemType::Radar),
+            AbilityType::DropMine => Some(ItemType::Mine),
+        };
+
+        if required_item.is_some() && tank.secondary_item != required_item {
+            return;
+        }
+
+        match ability_type {
+            AbilityType::FireBullet | AbilityType::FireDoubleBullet => {
+                let (bullet_type, speed) = if ability_type == AbilityType::FireBullet {
+                    if tank.bullet_count == 0 {
+                        return;
+                    }
+                    (BulletType::Basic, BASIC_BULLET_SPEED)
+                } else {
+                    (BulletType::Double, DOUBLE_BULLET_SPEED)
+                };
+
+                let id = self.next_id();
+                self.bullets.push(SimBullet {
+                    id,
+                    owner_id,
+                    x: tank.x,
+                    y: tank.y,
+                    direction: tank.turret_direction,
+                    speed,
+                    bullet_type,
+                    progress: 0.0,
+                });
+            }
+            AbilityType::UseLaser => {
+                let mut tiles = vec![];
+                let mut current = position;
+                while let Some(next) = self.step(current, tank.turret_direction) {
+                    if self.is_wall(next) {
+                        break;
+                    }
+                    tiles.push(next);
+                    current = next;
+                }
+
+                let orientation = match tank.turret_direction {
+                    Direction::Left | Direction::Right => LaserOrientation::Horizontal,
+                    Direction::Up | Direction::Down => LaserOrientation::Vertical,
+                };
+
+                let id = self.next_id();
+                self.lasers.push(SimLaser {
+                    id,
+                    owner_id,
+                    orientation,
+                    tiles,
+                    remaining_ticks: LASER_TICKS,
+                });
+            }
+            AbilityType::UseRadar => {
+                self.players[index].is_using_radar = true;
+            }
+            AbilityType::DropMine => {
+                // Mines are not dropped onto tanks or other mines, the item is kept then
+                let behind = self.step(position, tank.direction.opposite());
+                let Some((x, y)) = behind.filter(|&target| {
+                    self.is_passable(target)
+                        && !self.mines.iter().any(|mine| (mine.x, mine.y) == target)
+                }) else {
+                    return;
+                };
+
+                let id = self.next_id();
+                self.mines.push(SimMine {
+                    id,
+                    owner_id,
+                    x,
+                    y,
+                    explosion_remaining_ticks: None,
+                });
+            }
+        }
+
+        let tank = self.players[index].tank.as_mut().unwrap();
+        if ability_type == AbilityType::FireBullet {
+            tank.bullet_count -= 1;
+            if tank.ticks_to_regen_bullet.is_none() {
+                tank.ticks_to_regen_bullet = Some(BULLET_REGEN_TICKS);
+            }
+        } else {
+            tank.secondary_item = None;
+        }
+    }
+
+    fn damage_tank(&mut self, victim: usize, damage: i64, attacker_id: &str) {
+        let Some(tank) = self.players[victim].tank.as_mut() else {
+            return;
+        };
+
+        tank.health -= damage;
+        if tank.health > 0 {
+            return;
+        }
+
+        self.players[victim].tank = None;
+        self.players[victim].ticks_to_regen = Some(TANK_REGEN_TICKS);
+
+        if self.players[victim].id != attacker_id {
+            if let Some(attacker) = self.players.iter_mut().find(|p| p.id == attacker_id) {
+                attacker.score += KILL_POINTS;
+                attacker.kills += 1;
+            }
+        }
+    }
+
+    /// Moves all bullets one tile at a time in lockstep, so bullets flying
+    /// towards each other collide even when they swap tiles.
+    fn move_bullets(&mut self) {
+        let mut bullets = std::mem::take(&mut self.bullets);
+        let mut destroyed = vec![false; bullets.len()];
+
+        // The number of tiles each bullet travels in this tick
+        let steps: Vec<u32> = bullets
+            .iter_mut()
+            .map(|bullet| {
+                bullet.progress += bullet.speed;
+                let steps = bullet.progress.floor();
+                bullet.progress -= steps;
+                steps as u32
+            })
+            .collect();
+        let rounds = steps.iter().copied().max().unwrap_or(0);
+
+        for round in 0..rounds {
+            let moving: Vec<bool> = (0..bullets.len())
+                .map(|index| !destroyed[index] && steps[index] > round)
+                .collect();
+            let previous: Vec<(usize, usize)> =
+                bullets.iter().map(|bullet| (bullet.x, bullet.y)).collect();
+
+            for index in (0..bullets.len()).filter(|&index| moving[index]) {
+                let bullet = &bullets[index];
+                let next = self
+                    .step((bullet.x, bullet.y), bullet.direction)
+                    .filter(|&next| !self.is_wall(next));
+                match next {
+                    Some(next) => (bullets[index].x, bullets[index].y) = next,
+                    None => destroyed[index] = true,
+                }
+            }
+
+            for index in 0..bullets.len() {
+                if !moving[index] || destroyed[index] {
+                    continue;
+                }
+
+                if let Some(victim) = self.tank_at((bullets[index].x, bullets[index].y)) {
+                    let damage = bullet_damage(&bullets[index].bullet_type);
+                    let owner_id = bullets[index].owner_id.clone();
+                    self.damage_tank(victim, damage, &owner_id);
+                    destroyed[index] = true;
+                }
+            }
+
+            // Bullets meeting on a tile or passing each other destroy each other
+            for index in 0..bullets.len() {
+                if !moving[index] || destroyed[index] {
+                    continue;
+                }
+
+                let position = (bullets[index].x, bullets[index].y);
+                let collision = (0..bullets.len()).find(|&other| {
+                    let other_position = (bullets[other].x, bullets[other].y);
+                    let swapped = moving[other]
+                        && previous[other] == position
+                        && previous[index] == other_position;
+
+                    other != index && !destroyed[other] && (other_position == position || swapped)
+                });
+                if let Some(other) = collision {
+                    destroyed[index] = true;
+                    destroyed[other] = true;
+                }
+            }
+        }
+
+        self.bullets = bullets
+            .into_iter()
+            .zip(destroyed)
+            .filter_map(|(bullet, destroyed)| (!destroyed).then_some(bullet))
+            .collect();
+    }
+
+    fn update_lasers(&mut self) {
+        for laser in std::mem::take(&mut self.lasers) {
+            for &tile in &laser.tiles {
+                if let Some(victim) = self.tank_at(tile) {
+                    self.damage_tank(victim, LASER_DAMAGE, &laser.owner_id);
+                }
+            }
+
+            if laser.remaining_ticks > 1 {
+                self.lasers.push(SimLaser {
+                    remaining_ticks: laser.remaining_ticks - 1,
+                    ..laser
+                });
+            }
+        }
+    }
+
+    fn update_mines(&mut self) {
+        for mine in std::mem::take(&mut self.mines) {
+            let mine = match mine.explosion_remaining_ticks {
+                Some(ticks) if ticks <= 1 => continue,
+                Some(ticks) => SimMine {
+                    explosion_remaining_ticks: Some(ticks - 1),
+                    ..mine
+                },
+                None => match self.tank_at((mine.x, mine.y)) {
+                    Some(victim) => {
+                        self.damage_tank(victim, MINE_DAMAGE, &mine.owner_id);
+                        SimMine {
+                            explosion_remaining_ticks: Some(MINE_EXPLOSION_TICKS),
+                            ..mine
+                        }
+                    }
+                    None => mine,
+                },
+            };
+
+            self.mines.push(mine);
+        }
+    }
+
+    fn pick_up_items(&mut self) {
+        let players = &mut self.players;
+        self.items.retain(|item| {
+            let tank = players
+                .iter_mut()
+                .filter_map(|player| player.tank.as_mut())
+                .find(|tank| (tank.x, tank.y) == (item.x, item.y));
+
+            match tank {
+                Some(tank) if tank.secondary_item.is_none() => {
+                    tank.secondary_item = Some(item.item_type);
+                    false
+                }
+                _ => true,
+            }
+        });
+    }
+
+    fn update_zones(&mut self) {
+        for index in 0..self.zones.len() {
+            let present: Vec<&str> = self
+                .players
+                .iter()
+                .filter(|player| {
+                    player
+                        .tank
+                        .as_ref()
+                        .is_some_and(|tank| self.zone_at((tank.x, tank.y)) == Some(index))
+                })
+                .map(|player| player.id.as_str())
+                .collect();
+
+            let status = next_zone_status(&self.zones[index].status, &present);
+
+            if let Some(holder_id) = status.owner() {
+                if let Some(holder) = self.players.iter_mut().find(|p| p.id == holder_id) {
+                    holder.score += ZONE_POINTS_PER_TICK;
+                }
+            }
+
+            self.zones[index].status = status;
+        }
+    }
+
+    fn regenerate(&mut self) {
+        for index in 0..self.players.len() {
+            if let Some(tank) = self.players[index].tank.as_mut() {
+                if let Some(ticks) = tank.ticks_to_regen_bullet {
+                    if ticks > 1 {
+                        tank.ticks_to_regen_bullet = Some(ticks - 1);
+                    } else {
+                        tank.bullet_count += 1;
+                        tank.ticks_to_regen_bullet =
+                            (tank.bullet_count < MAX_BULLETS).then_some(BULLET_REGEN_TICKS);
+                    }
+                }
+                continue;
+            }
+
+            match self.players[index].ticks_to_regen {
+                Some(ticks) if ticks > 1 => {
+                    self.players[index].ticks_to_regen = Some(ticks - 1);
+                }
+                _ => {
+                    let tank = self.spawn_tank(index);
+                    let player = &mut self.players[index];
+                    player.ticks_to_regen = None;
+                    player.tank = tank;
+                }
+            }
+        }
+    }
+
+    fn spawn_items(&mut self) {
+        if self.items.len() >= MAX_ITEMS || !self.rng.gen_bool(ITEM_SPAWN_CHANCE) {
+            return;
+        }
+
+        let dimension = self.dimension();
+        let position = (
+            self.rng.gen_range(0..dimension),
+            self.rng.gen_range(0..dimension),
+        );
+        if !self.is_free(position) {
+            return;
+        }
+
+        let item_types = [
+            ItemType::Laser,
+            ItemType::DoubleBullet,
+            ItemType::Radar,
+            ItemType::Mine,
+        ];
+        let item_type = item_types[self.rng.gen_range(0..item_types.len())];
+
+        self.items.push(SimItem {
+            x: position.0,
+            y: position.1,
+            item_type,
+        });
+    }
+
+    /// Computes which tiles the given player can see, as `visibility[y][x]`.
+    fn visibility(&self, player_id: &str) -> Vec<Vec<bool>> {
+        let dimension = self.dimension();
+        let Some(player) = self.players.iter().find(|player| player.id == player_id) else {
+            return vec![vec![false; dimension]; dimension];
+        };
+
+        if player.is_using_radar {
+            return vec![vec![true; dimension]; dimension];
+        }
+
+        let mut visibility = vec![vec![false; dimension]; dimension];
+        let Some(tank) = player.tank.as_ref() else {
+            return visibility;
+        };
+
+        let (fx, fy) = tank.turret_direction.offset();
+        for (y, row) in visibility.iter_mut().enumerate() {
+            for (x, visible) in row.iter_mut().enumerate() {
+                let dx = x as i64 - tank.x as i64;
+                let dy = y as i64 - tank.y as i64;
+
+                // Distance along and across the turret direction
+                let forward = dx * fx + dy * fy;
+                let side = (dx * fy - dy * fx).abs();
+
+                let nearby = dx.abs() <= 1 && dy.abs() <= 1;
+                let in_cone = (0..=VISION_RANGE).contains(&forward) && side <= forward;
+                *visible = nearby || in_cone;
+            }
+        }
+
+        visibility
+    }
+
+    fn build_raw_game_state(
+        &self,
+        player_id: Option<&str>,
+        visibility: Vec<Vec<bool>>,
+    ) -> RawGameState {
+        let dimension = self.dimension();
+
+        // Raw tiles are indexed as `tiles[x][y]`, just like the server sends them
+        let mut tiles: Vec<Vec<Vec<TileEntity>>> = vec![vec![vec![]; dimension]; dimension];
+        let mut push_if_visible = |(x, y): (usize, usize), entity: TileEntity, always: bool| {
+            if always || visibility[y][x] {
+                tiles[x][y].push(entity);
+            }
+        };
+
+        for player in &self.players {
+            let Some(tank) = player.tank.as_ref() else {
+                continue;
+            };
+            let is_own = player_id == Some(player.id.as_str());
+            let is_detailed = is_own || player_id.is_none();
+
+            let turret = Turret::new(
+                is_detailed.then_some(tank.bullet_count),
+                if is_detailed {
+                    tank.ticks_to_regen_bullet
+                } else {
+                    None
+                },
+                tank.turret_direction,
+            );
+            let entity = TileEntity::Tank(Tank::new(
+                tank.direction,
+                is_detailed.then_some(tank.health),
+                player.id.clone(),
+                turret,
+                if is_detailed {
+                    tank.secondary_item
+                } else {
+                    None
+                },
+            ));
+
+            // Your own tank is always visible to you
+            push_if_visible((tank.x, tank.y), entity, is_own);
+        }
+
+        for bullet in &self.bullets {
+            let entity = TileEntity::Bullet(Bullet::new(
+                bullet.direction,
+                bullet.id,
+                bullet.speed,
+                bullet.bullet_type.clone(),
+            ));
+            push_if_visible((bullet.x, bullet.y), entity, false);
+        }
+
+        for laser in &self.lasers {
+            for &tile in &laser.tiles {
+                let entity = TileEntity::Laser(Laser::new(laser.id, laser.orientation));
+                push_if_visible(tile, entity, false);
+            }
+        }
+
+        for mine in &self.mines {
+            let entity = TileEntity::Mine(Mine::new(mine.id, mine.explosion_remaining_ticks));
+            push_if_visible((mine.x, mine.y), entity, false);
+        }
+
+        for item in &self.items {
+            let entity = TileEntity::Item(Item::new(item.item_type));
+            push_if_visible((item.x, item.y), entity, false);
+        }
+
+        // Walls are always known, even outside of vision
+        for (y, row) in self.walls.iter().enumerate() {
+            for (x, &wall) in row.iter().enumerate() {
+                if wall {
+                    tiles[x][y].insert(0, TileEntity::Wall);
+                }
+            }
+        }
+
+        let visibility = visibility
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|&visible| if visible { '1' } else { '0' })
+                    .collect()
+            })
+            .collect();
+
+        let players = self
+            .players
+            .iter()
+            .map(|player| {
+                let is_detailed = player_id.map_or(true, |id| id == player.id);
+
+                Player::new(
+                    player.id.clone(),
+                    player.nickname.clone(),
+                    player.color,
+                    0,
+                    Some(player.score),
+                    player.ticks_to_regen,
+                    is_detailed.then_some(player.is_using_radar),
+                )
+            })
+            .collect();
+
+        RawGameState::new(
+            format!("sim-{}-{}", self.seed, self.tick),
+            self.tick,
+            players,
+            RawMap::new(tiles, self.zones.clone(), visibility),
+        )
+    }
+}
+
+/// Returns the damage dealt by a bullet of the given type.
+fn bullet_damage(bullet_type: &BulletType) -> i64 {
+    match bullet_type {
+        BulletType::Basic => BASIC_BULLET_DAMAGE,
+        BulletType::Double => DOUBLE_BULLET_DAMAGE,
+    }
+}
+
+/// Computes the status of a zone after a tick, given the IDs of players
+/// whose tanks are standing inside of it.
+fn next_zone_status(status: &ZoneStatus, present: &[&str]) -> ZoneStatus {
+    let being_captured = |player_id: &str| {
+        ZoneStatus::BeingCaptured(BeingCapturedStatus {
+            remaining_ticks: ZONE_CAPTURE_TICKS,
+            player_id: player_id.to_string(),
+        })
+    };
+    let captured = |player_id: &str| {
+        ZoneStatus::Captured(CapturedStatus {
+            player_id: player_id.to_string(),
+        })
+    };
+    let being_retaken = |captured_by_id: &str, retaken_by_id: &str| {
+        ZoneStatus::BeingRetaken(BeingRetakenStatus {
+            remaining_ticks: ZONE_CAPTURE_TICKS,
+            captured_by_id: captured_by_id.to_string(),
+            retaken_by_id: retaken_by_id.to_string(),
+        })
+    };
+    let contested = |captured_by_id: Option<&str>| {
+        ZoneStatus::BeingContested(BeingContestedStatus {
+            captured_by_id: captured_by_id.map(str::to_string),
+        })
+    };
+
+    if present.len() > 1 {
+        return contested(status.owner());
+    }
+
+    match (status, present.first().copied()) {
+        (ZoneStatus::Neutral, None) => ZoneStatus::Neutral,
+        (ZoneStatus::Neutral, Some(player_id)) => being_captured(player_id),
+
+        (ZoneStatus::BeingCaptured(_), None) => ZoneStatus::Neutral,
+        (ZoneStatus::BeingCaptured(capturing), Some(player_id))
+            if capturing.player_id == player_id =>
+        {
+            if capturing.remaining_ticks <= 1 {
+                captured(player_id)
+            } else {
+                ZoneStatus::BeingCaptured(BeingCapturedStatus {
+                    remaining_ticks: capturing.remaining_ticks - 1,
+                    player_id: player_id.to_string(),
+                })
+            }
+        }
+        (ZoneStatus::BeingCaptured(_), Some(player_id)) => being_captured(player_id),
+
+        (ZoneStatus::Captured(captured_status), player) => match player {
+            Some(player_id) if player_id != captured_status.player_id => {
+                being_retaken(&captured_status.player_id, player_id)
+            }
+            _ => status.clone(),
+        },
+
+        (ZoneStatus::BeingContested(contested_status), player) => {
+            match (contested_status.captured_by_id.as_deref(), player) {
+                (None, None) => ZoneStatus::Neutral,
+                (None, Some(player_id)) => being_captured(player_id),
+                (Some(holder_id), None) => captured(holder_id),
+                (Some(holder_id), Some(player_id)) if holder_id == player_id => captured(holder_id),
+                (Some(holder_id), Some(player_id)) => being_retaken(holder_id, player_id),
+            }
+        }
+
+        (ZoneStatus::BeingRetaken(retaking), player) => match player {
+            None => captured(&retaking.captured_by_id),
+            Some(player_id) if player_id == retaking.captured_by_id => captured(player_id),
+            Some(player_id) if player_id == retaking.retaken_by_id => {
+                if retaking.remaining_ticks <= 1 {
+                    captured(player_id)
+                } else {
+                    ZoneStatus::BeingRetaken(BeingRetakenStatus {
+                        remaining_ticks: retaking.remaining_ticks - 1,
+                        ..retaking.clone()
+                    })
+                }
+            }
+            Some(player_id) => being_retaken(&retaking.captured_by_id, player_id),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
+
+    fn settings(seed: u32) -> ServerSettings {
+        ServerSettings::new(
+            24,
+            2,
+            seed,
+            100,
+            true,
+            false,
+            Some(200),
+            None,
+            "test".into(),
+        )
+    }
+
+    /// Creates a simulator with no walls and no items, with tanks at known positions.
+    fn empty_simulator() -> Simulator {
+        let mut simulator = Simulator::new(1, settings(1)).unwrap();
+        for row in simulator.walls.iter_mut() {
+            row.fill(false);
+        }
+
+        let place = |player: &mut SimPlayer, x, y| {
+            let tank = player.tank.as_mut().unwrap();
+            (tank.x, tank.y) = (x, y);
+            tank.direction = Direction::Right;
+            tank.turret_direction = Direction::Right;
+        };
+        place(&mut simulator.players[0], 1, 1);
+        place(&mut simulator.players[1], 5, 1);
+
+        simulator
+    }
+
+    fn action(player_id: &str, response: BotResponse) -> HashMap<String, BotResponse> {
+        HashMap::from([(player_id.to_string(), response)])
+    }
+
+    #[test]
+    fn test_same_seed_generates_same_map() {
+        let first = Simulator::new(42, settings(42)).unwrap();
+        let second = Simulator::new(42, settings(42)).unwrap();
+        let other = Simulator::new(43, settings(43)).unwrap();
+
+        assert_eq!(first.walls, second.walls);
+        assert_eq!(
+            first.spectator_raw_game_state(),
+            second.spectator_raw_game_state()
+        );
+        assert_ne!(first.walls, other.walls);
+    }
+
+    #[test]
+    fn test_map_has_zones_and_spawned_tanks() {
+        let simulator = Simulator::new(3, settings(3)).unwrap();
+        let game_state = simulator.game_state("player-1");
+
+        assert_eq!(game_state.zones.len(), 2);
+        assert_eq!(game_state.zones[0].index, b'A');
+
+        let tank_count = game_state
+            .map
+            .iter()
+            .flatten()
+            .flat_map(|tile| tile.entities.iter())
+            .filter(|entity| entity.is_tank())
+            .count();
+        assert!(tank_count >= 1);
+    }
+
+    #[test]
+    fn test_smallest_map() {
+        let mut settings = settings(1);
+        settings.grid_dimension = MIN_GRID_DIMENSION;
+
+        let mut simulator = Simulator::new(1, settings).unwrap();
+        simulator.tick(Default::default());
+        assert_eq!(simulator.game_state("player-1").dimension(), 2);
+    }
+
+    #[test]
+    fn test_rejects_too_small_map() {
+        let mut settings = settings(1);
+        settings.grid_dimension = 1;
+
+        let error = Simulator::new(1, settings).unwrap_err();
+        assert_eq!(error, "Grid dimension 1 is smaller than 2");
+    }
+
+    #[test]
+    fn test_movement_and_rotation() {
+        let mut simulator = empty_simulator();
+
+        let forward = BotResponse::Movement {
+            direction: MoveDirection::Forward,
+        };
+        simulator.tick(action("player-1", forward));
+        let tank = simulator.players[0].tank.clone().unwrap();
+        assert_eq!((tank.x, tank.y), (2, 1));
+
+        let rotate = BotResponse::Rotation {
+            tank_rotation: Some(Rotation::Right),
+            turret_rotation: None,
+        };
+        simulator.tick(action("player-1", rotate));
+        let tank = simulator.players[0].tank.clone().unwrap();
+        assert_eq!(tank.direction, Direction::Down);
+        assert_eq!(tank.turret_direction, Direction::Right);
+
+        // Movement into a wall is ignored
+        simulator.walls[2][2] = true;
+        let forward = BotResponse::Movement {
+            direction: MoveDirection::Forward,
+        };
+        simulator.tick(action("player-1", forward));
+        let tank = simulator.players[0].tank.clone().unwrap();
+        assert_eq!((tank.x, tank.y), (2, 1));
+    }
+
+    #[test]
+    fn test_bullet_hits_tank() {
+        let mut simulator = empty_simulator();
+
+        let fire = BotResponse::AbilityUse {
+            ability_type: AbilityType::FireBullet,
+        };
+        simulator.tick(action("player-1", fire));
+        assert_eq!(simulator.bullets.len(), 1);
+        assert_eq!(simulator.players[0].tank.as_ref().unwrap().bullet_count, 2);
+
+        for _ in 0..3 {
+            simulator.tick(HashMap::new());
+        }
+
+        assert!(simulator.bullets.is_empty());
+        let health = simulator.players[1].tank.as_ref().unwrap().health;
+        assert_eq!(health, TANK_HEALTH - BASIC_BULLET_DAMAGE);
+    }
+
+    #[test]
+    fn test_mines_are_not_dropped_onto_tanks_or_mines() {
+        let mut simulator = empty_simulator();
+        let drop_mine = || BotResponse::AbilityUse {
+            ability_type: AbilityType::DropMine,
+        };
+        let give_mine = |simulator: &mut Simulator, index: usize| {
+            simulator.players[index]
+                .tank
+                .as_mut()
+                .unwrap()
+                .secondary_item = Some(ItemType::Mine);
+        };
+
+        give_mine(&mut simulator, 0);
+        simulator.tick(action("player-1", drop_mine()));
+        assert_eq!(simulator.mines.len(), 1);
+        assert_eq!((simulator.mines[0].x, simulator.mines[0].y), (0, 1));
+
+        give_mine(&mut simulator, 0);
+        simulator.tick(action("player-1", drop_mine()));
+        assert_eq!(simulator.mines.len(), 1);
+        let tank = simulator.players[0].tank.as_ref().unwrap();
+        assert_eq!(tank.secondary_item, Some(ItemType::Mine));
+
+        // The tank of player-1 is right behind the one of player-2
+        simulator.players[0].tank.as_mut().unwrap().x = 4;
+        give_mine(&mut simulator, 1);
+        simulator.tick(action("player-2", drop_mine()));
+        assert_eq!(simulator.mines.len(), 1);
+    }
+
+    fn bullet(id: i64, (x, y): (usize, usize), direction: Direction) -> SimBullet {
+        SimBullet {
+            id,
+            owner_id: "player-1".to_string(),
+            x,
+            y,
+            direction,
+            speed: BASIC_BULLET_SPEED,
+            bullet_type: BulletType::Basic,
+            progress: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_bullets_swapping_tiles_collide() {
+        let mut simulator = empty_simulator();
+        simulator.bullets = vec![
+            bullet(1, (2, 3), Direction::Right),
+            bullet(2, (3, 3), Direction::Left),
+            // Bullets following each other never meet
+            bullet(3, (2, 5), Direction::Right),
+            bullet(4, (3, 5), Direction::Right),
+        ];
+
+        simulator.tick(HashMap::new());
+
+        let positions: Vec<_> = simulator
+            .bullets
+            .iter()
+            .map(|bullet| (bullet.id, bullet.x, bullet.y))
+            .collect();
+        assert_eq!(positions, vec![(3, 3, 5), (4, 4, 5)]);
+    }
+
+    #[test]
+    fn test_tank_moving_onto_bullet_is_hit() {
+        let mut simulator = empty_simulator();
+        simulator.bullets = vec![bullet(1, (6, 1), Direction::Right)];
+
+        let forward = BotResponse::Movement {
+            direction: MoveDirection::Forward,
+        };
+        simulator.tick(action("player-2", forward));
+
+        assert!(simulator.bullets.is_empty());
+        let tank = simulator.players[1].tank.as_ref().unwrap();
+        assert_eq!((tank.x, tank.y), (6, 1));
+        assert_eq!(tank.health, TANK_HEALTH - BASIC_BULLET_DAMAGE);
+    }
+
+    #[test]
+    fn test_zone_capture() {
+        let mut simulator = empty_simulator();
+        let zone = simulator.zones[0].clone();
+        {
+            let tank = simulator.players[0].tank.as_mut().unwrap();
+            (tank.x, tank.y) = (zone.x as usize, zone.y as usize);
+        }
+
+        simulator.tick(HashMap::new());
+        assert!(matches!(
+            &simulator.zones[0].status,
+            ZoneStatus::BeingCaptured(status) if status.remaining_ticks == ZONE_CAPTURE_TICKS
+        ));
+
+        for _ in 0..ZONE_CAPTURE_TICKS {
+            simulator.tick(HashMap::new());
+        }
+        assert_eq!(
+            simulator.zones[0].status,
+            ZoneStatus::Captured(CapturedStatus {
+                player_id: "player-1".to_string()
+            })
+        );
+        assert_eq!(simulator.players[0].score, ZONE_POINTS_PER_TICK);
+    }
+
+    #[test]
+    fn test_next_zone_status_retake() {
+        let captured = ZoneStatus::Captured(CapturedStatus {
+            player_id: "a".to_string(),
+        });
+
+        let status = next_zone_status(&captured, &["b"]);
+        assert!(matches!(&status, ZoneStatus::BeingRetaken(s) if s.retaken_by_id == "b"));
+
+        let status = next_zone_status(&status, &["a", "b"]);
+        assert_eq!(
+            status,
+            ZoneStatus::BeingContested(BeingContestedStatus {
+                captured_by_id: Some("a".to_string())
+            })
+        );
+
+        assert_eq!(next_zone_status(&status, &[]), captured);
+    }
+}
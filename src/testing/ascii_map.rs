@@ -0,0 +1,33 @@
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::position::Position;
+use crate::ws_client::packet::packets::game_state::tile::tile::{Tile, TileEntity};
+
+/// Builds a game state from rows of symbols, one for every tile, e.g. `["#..", ".T."]`.
+///
+/// `#` is a wall, the entities on the other tiles come from `legend`.
+/// The tiles for which `visible` returns false are hidden by fog.
+pub fn game_state_from_rows(
+    rows: &[&str],
+    tick: u64,
+    legend: impl Fn(char) -> Vec<TileEntity>,
+    visible: impl Fn(Position) -> bool,
+) -> GameState {
+    let map = rows
+        .iter()
+        .enumerate()
+        .map(|(y, row)| {
+            row.chars()
+                .enumerate()
+                .map(|(x, symbol)| {
+                    let entities = match symbol {
+                        '#' => vec![TileEntity::Wall],
+                        symbol => legend(symbol),
+                    };
+                    Tile::new(visible(Position::new(x, y)), None, entities)
+                })
+                .collect()
+        })
+        .collect();
+
+    GameState::new("test".into(), map, vec![], tick, vec![])
+}
@@ -0,0 +1,183 @@
+use crate::ws_client::packet::packet::Packet;
+use futures_util::{SinkExt, StreamExt};
+use std::io;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
+
+/// How long [`MockConnection::expect`] waits for the client to send a packet.
+pub const EXPECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A WebSocket peer that plays the part of the game server in tests.
+///
+/// Unlike [`crate::server::server::GameServer`] it has no game logic,
+/// the test decides which packets are sent and checks what the client answers:
+///
+/// ```no_run
+/// # use HackArena2_0_MonoTanks_Rust::testing::mock_server::{MockServer, Step};
+/// # use HackArena2_0_MonoTanks_Rust::ws_client::packet::packet::Packet;
+/// # async fn example() -> std::io::Result<()> {
+/// let server = MockServer::bind().await?;
+/// // Connect the client to `server.port()` here
+/// let mut connection = server.accept().await?;
+/// connection
+///     .play(vec![
+///         Step::Send(Packet::ConnectionAccepted),
+///         Step::Expect("lobbyDataRequest"),
+///     ])
+///     .await;
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockServer {
+    listener: TcpListener,
+}
+
+/// A single step of a script played by [`MockConnection::play`].
+#[derive(Debug, Clone)]
+pub enum Step {
+    /// Sends the packet to the client.
+    Send(Packet),
+
+    /// Waits for the client to send a packet of the given type, e.g. `"lobbyDataRequest"`.
+    Expect(&'static str),
+
+    /// Checks that the client sends nothing for the given duration.
+    ExpectSilence(Duration),
+}
+
+impl MockServer {
+    /// Listens on a free local port.
+    pub async fn bind() -> io::Result<MockServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        Ok(MockServer { listener })
+    }
+
+    pub fn port(&self) -> u16 {
+        self.listener
+            .local_addr()
+            .map(|address| address.port())
+            .unwrap_or_default()
+    }
+
+    /// Waits for a client to connect and performs the WebSocket handshake.
+    pub async fn accept(&self) -> io::Result<MockConnection> {
+        let (stream, _) = self.listener.accept().await?;
+        let mut query = String::new();
+
+        // The error type is dictated by tungstenite's `Callback` trait
+        #[allow(clippy::result_large_err)]
+        let callback = |request: &Request, response: Response| {
+            query = request.uri().query().unwrap_or_default().to_string();
+            Ok(response)
+        };
+
+        let stream = accept_hdr_async(stream, callback)
+            .await
+            .map_err(io::Error::other)?;
+
+        Ok(MockConnection { stream, query })
+    }
+}
+
+/// A client connected to a [`MockServer`].
+///
+/// The assertions panic with a message describing what the client sent instead,
+/// like the `assert!` macros do.
+pub struct MockConnection {
+    stream: WebSocketStream<TcpStream>,
+    query: String,
+}
+
+impl MockConnection {
+    /// The query string of the url the client connected with, e.g. `nickname=Bot&...`.
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub async fn send(&mut self, packet: Packet) {
+        let message = Message::Text(packet.into());
+        self.stream
+            .send(message)
+            .await
+            .expect("Failed to send packet to the client");
+    }
+
+    /// Returns the next packet sent by the client, skipping control messages.
+    ///
+    /// Returns `None` if nothing arrives within `timeout` or the client disconnects.
+    pub async fn receive(&mut self, timeout: Duration) -> Option<Packet> {
+        let receive = async {
+            while let Some(Ok(message)) = self.stream.next().await {
+                if let Message::Text(text) = message {
+                    let packet = serde_json::from_str(&text)
+                        .unwrap_or_else(|e| panic!("Client sent an invalid packet {text} -> {e}"));
+                    return Some(packet);
+                }
+            }
+            None
+        };
+
+        tokio::time::timeout(timeout, receive).await.ok().flatten()
+    }
+
+    /// Waits for the next packet and checks that it is of the given type.
+    pub async fn expect(&mut self, packet_type: &str) -> Packet {
+        match self.receive(EXPECT_TIMEOUT).await {
+            Some(packet) if self::packet_type(&packet) == packet_type => packet,
+            Some(packet) => panic!("Expected {packet_type}, but the client sent {packet:?}"),
+            None => panic!("Expected {packet_type}, but the client sent nothing"),
+        }
+    }
+
+    /// Checks that the client sends nothing for the given duration.
+    pub async fn expect_silence(&mut self, duration: Duration) {
+        if let Some(packet) = self.receive(duration).await {
+            panic!("Expected no packet, but the client sent {packet:?}");
+        }
+    }
+
+    /// Plays the steps in order and returns the packets the client sent for the expect steps.
+    pub async fn play(&mut self, steps: Vec<Step>) -> Vec<Packet> {
+        let mut received = vec![];
+        for step in steps {
+            match step {
+                Step::Send(packet) => self.send(packet).await,
+                Step::Expect(packet_type) => received.push(self.expect(packet_type).await),
+                Step::ExpectSilence(duration) => self.expect_silence(duration).await,
+            }
+        }
+
+        received
+    }
+
+    /// Closes the connection, as the server does after the game ends.
+    pub async fn close(mut self) {
+        let _ = self.stream.close(None).await;
+        while let Some(Ok(_)) = self.stream.next().await {}
+    }
+}
+
+/// Returns the type of the packet as it is sent over the wire, e.g. `"gameStarting"`.
+pub fn packet_type(packet: &Packet) -> String {
+    let value = serde_json::to_value(packet).expect("Packets are always serializable");
+    value["type"].as_str().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_packet_type() {
+        assert_eq!(packet_type(&Packet::GameStarting), "gameStarting");
+        assert_eq!(
+            packet_type(&Packet::Pass {
+                game_state_id: "id".to_string()
+            }),
+            "pass"
+        );
+    }
+}
@@ -0,0 +1,3 @@
+pub mod ascii_map;
+pub mod mock_server;
+pub mod pass_bot;
@@ -0,0 +1,18 @@
+use crate::bot_trait::tick_context::TickContext;
+use crate::bot_trait::BotTrait;
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+
+/// A bot that passes every tick, for tests that need players but not their moves.
+pub struct PassBot;
+
+impl BotTrait for PassBot {
+    fn on_joining_lobby(_: LobbyData) -> Self {
+        PassBot
+    }
+
+    fn next_move(&mut self, _: GameState, _: &TickContext) -> BotResponse {
+        BotResponse::Pass
+    }
+}
@@ -0,0 +1,3 @@
+pub mod pairing;
+pub mod rating;
+pub mod tournament;
@@ -0,0 +1,109 @@
+use derive_more::derive::IsVariant;
+use std::collections::HashSet;
+
+/// The way entrants of a tournament are matched against each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, IsVariant)]
+pub enum Pairing {
+    /// Every combination of entrants plays together once per seed.
+    #[default]
+    RoundRobin,
+
+    /// Entrants with similar ratings play together, for a fixed number of rounds.
+    Swiss,
+}
+
+/// Returns every combination of `players_per_match` entrants out of `entrants`.
+pub fn round_robin(entrants: usize, players_per_match: usize) -> Vec<Vec<usize>> {
+    let mut matches = vec![];
+    let mut current = vec![];
+    combinations(0, entrants, players_per_match, &mut current, &mut matches);
+    matches
+}
+
+fn combinations(
+    start: usize,
+    entrants: usize,
+    players_per_match: usize,
+    current: &mut Vec<usize>,
+    matches: &mut Vec<Vec<usize>>,
+) {
+    if current.len() == players_per_match {
+        matches.push(current.clone());
+        return;
+    }
+
+    for entrant in start..entrants {
+        current.push(entrant);
+        combinations(entrant + 1, entrants, players_per_match, current, matches);
+        current.pop();
+    }
+}
+
+/// Splits the entrants into matches of `players_per_match` for a single Swiss round.
+///
+/// `ranking` lists the entrants from the best to the worst. Each match is filled with
+/// the best ranked entrants left, skipping ones that already played against someone
+/// in the match according to `played`, unless there is no other choice.
+/// Entrants that do not fit into a full match sit the round out.
+pub fn swiss_round(
+    ranking: &[usize],
+    players_per_match: usize,
+    played: &HashSet<(usize, usize)>,
+) -> Vec<Vec<usize>> {
+    let have_played = |a: usize, b: usize| played.contains(&(a.min(b), a.max(b)));
+
+    let mut unpaired: Vec<usize> = ranking.to_vec();
+    let mut matches = vec![];
+
+    while unpaired.len() >= players_per_match {
+        let mut current = vec![unpaired.remove(0)];
+
+        while current.len() < players_per_match {
+            let index = unpaired
+                .iter()
+                .position(|&candidate| {
+                    current
+                        .iter()
+                        .all(|&member| !have_played(member, candidate))
+                })
+                .unwrap_or(0);
+            current.push(unpaired.remove(index));
+        }
+
+        matches.push(current);
+    }
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_robin() {
+        assert_eq!(round_robin(3, 2), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+        assert_eq!(round_robin(5, 4).len(), 5);
+        assert!(round_robin(2, 3).is_empty());
+    }
+
+    #[test]
+    fn test_swiss_round_avoids_rematches() {
+        let ranking = [3, 0, 1, 2, 4];
+
+        assert_eq!(
+            swiss_round(&ranking, 2, &HashSet::new()),
+            vec![vec![3, 0], vec![1, 2]]
+        );
+
+        let played = HashSet::from([(0, 3), (1, 2)]);
+        assert_eq!(
+            swiss_round(&ranking, 2, &played),
+            vec![vec![3, 1], vec![0, 2]]
+        );
+
+        // Rematches are allowed when there is nobody else left
+        let played = HashSet::from([(0, 1)]);
+        assert_eq!(swiss_round(&[0, 1], 2, &played), vec![vec![0, 1]]);
+    }
+}
@@ -0,0 +1,106 @@
+/// The rating every entrant starts with.
+pub const INITIAL_RATING: f64 = 1500.0;
+
+/// The maximum rating change from a single two-player game.
+pub const K_FACTOR: f64 = 32.0;
+
+/// Elo ratings of the entrants of a tournament.
+///
+/// A match of more than two players is treated as a separate game between
+/// every pair of them, decided by their scores, with the rating change
+/// divided by the number of opponents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EloRatings {
+    ratings: Vec<f64>,
+}
+
+impl EloRatings {
+    /// Creates ratings for the given number of entrants.
+    pub fn new(entrants: usize) -> Self {
+        EloRatings {
+            ratings: vec![INITIAL_RATING; entrants],
+        }
+    }
+
+    /// Returns the rating of the entrant with the given index.
+    pub fn rating(&self, entrant: usize) -> f64 {
+        self.ratings[entrant]
+    }
+
+    /// Returns the indices of the entrants from the highest to the lowest rating.
+    pub fn ranking(&self) -> Vec<usize> {
+        let mut ranking: Vec<usize> = (0..self.ratings.len()).collect();
+        ranking.sort_by(|&a, &b| self.ratings[b].total_cmp(&self.ratings[a]));
+        ranking
+    }
+
+    /// Updates the ratings after a match, given the index and score of each participant.
+    pub fn update(&mut self, results: &[(usize, u64)]) {
+        if results.len() < 2 {
+            return;
+        }
+
+        let k = K_FACTOR / (results.len() - 1) as f64;
+        let changes: Vec<f64> = results
+            .iter()
+            .map(|&(entrant, score)| {
+                results
+                    .iter()
+                    .filter(|&&(opponent, _)| opponent != entrant)
+                    .map(|&(opponent, opponent_score)| {
+                        let actual = match score.cmp(&opponent_score) {
+                            std::cmp::Ordering::Greater => 1.0,
+                            std::cmp::Ordering::Equal => 0.5,
+                            std::cmp::Ordering::Less => 0.0,
+                        };
+                        let expected =
+                            expected_score(self.ratings[entrant], self.ratings[opponent]);
+                        k * (actual - expected)
+                    })
+                    .sum()
+            })
+            .collect();
+
+        for (&(entrant, _), change) in results.iter().zip(changes) {
+            self.ratings[entrant] += change;
+        }
+    }
+}
+
+/// Returns the probability of a player with `rating` beating one with `opponent_rating`.
+fn expected_score(rating: f64, opponent_rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent_rating - rating) / 400.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_winner_gains_what_loser_loses() {
+        let mut ratings = EloRatings::new(3);
+        ratings.update(&[(0, 100), (2, 40)]);
+
+        assert_eq!(ratings.rating(0), INITIAL_RATING + K_FACTOR / 2.0);
+        assert_eq!(ratings.rating(1), INITIAL_RATING);
+        assert_eq!(ratings.rating(2), INITIAL_RATING - K_FACTOR / 2.0);
+        assert_eq!(ratings.ranking(), vec![0, 1, 2]);
+
+        // Beating a stronger opponent is worth more
+        let mut upset = ratings.clone();
+        upset.update(&[(0, 0), (2, 10)]);
+        assert!(upset.rating(2) - ratings.rating(2) > K_FACTOR / 2.0);
+    }
+
+    #[test]
+    fn test_multiplayer_match() {
+        let mut ratings = EloRatings::new(4);
+        ratings.update(&[(0, 30), (1, 20), (2, 20), (3, 10)]);
+
+        assert!(ratings.rating(0) > ratings.rating(1));
+        assert_eq!(ratings.rating(1), ratings.rating(2));
+        assert!(ratings.rating(2) > ratings.rating(3));
+        let total: f64 = (0..4).map(|entrant| ratings.rating(entrant)).sum();
+        assert!((total - 4.0 * INITIAL_RATING).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,629 @@
+use super::pairing::{round_robin, swiss_round, Pairing};
+use super::rating::EloRatings;
+use crate::bot_trait::tick_context::TickContext;
+use crate::bot_trait::BotTrait;
+use crate::server::server::{GameServer, ServerConfig};
+use crate::simulator::rules::{MIN_GRID_DIMENSION, PLAYER_COLORS};
+use crate::simulator::simulator::Simulator;
+use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
+use crate::ws_client::packet::packets::lobby_data::lobby_player::LobbyPlayer;
+use crate::ws_client::packet::packets::lobby_data::server_settings::{
+    ServerSettings, SUPPORTED_SERVER_VERSION,
+};
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+use crate::ws_client::reconnect_config::ReconnectConfig;
+use crate::ws_client::ws_client::WebSocketClient;
+use futures_util::future::join_all;
+use futures_util::StreamExt;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::process::Command;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// Creates a bot for the lobby it joined.
+pub type CreateBot = fn(LobbyData) -> Box<dyn BotTrait>;
+
+/// Spawns a bot connecting to the local server on the given port with the given nickname.
+pub type ConnectBot = fn(u16, String, CancellationToken) -> JoinHandle<()>;
+
+/// A bot taking part in a tournament.
+#[derive(Debug, Clone)]
+pub enum Entrant {
+    /// A [`BotTrait`] implementation run in the same process.
+    InProcess {
+        name: String,
+        create: CreateBot,
+        connect: ConnectBot,
+    },
+
+    /// A bot started as a separate process, which connects to a local [`GameServer`].
+    ///
+    /// The process is started with `--host`, `--port` and `--nickname` arguments
+    /// appended to `args`, the same ones this crate's bot accepts.
+    Executable {
+        name: String,
+        path: PathBuf,
+        args: Vec<String>,
+    },
+}
+
+impl Entrant {
+    /// Creates an entrant running the bot of type `B` in the same process.
+    pub fn in_process<B: BotTrait + 'static>(name: impl Into<String>) -> Entrant {
+        Entrant::InProcess {
+            name: name.into(),
+            create: |lobby_data| Box::new(B::on_joining_lobby(lobby_data)),
+            connect: connect::<B>,
+        }
+    }
+
+    /// Creates an entrant starting the given executable for every match.
+    pub fn executable(name: impl Into<String>, path: PathBuf, args: Vec<String>) -> Entrant {
+        Entrant::Executable {
+            name: name.into(),
+            path,
+            args,
+        }
+    }
+
+    /// Returns the name of the entrant, used as its nickname in matches.
+    pub fn name(&self) -> &str {
+        match self {
+            Entrant::InProcess { name, .. } | Entrant::Executable { name, .. } => name,
+        }
+    }
+}
+
+/// Connects a bot of type `B` to the local server as a WebSocket client.
+fn connect<B: BotTrait + 'static>(
+    port: u16,
+    nickname: String,
+    cancel_token: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        match WebSocketClient::<B>::connect("127.0.0.1", port, "", &nickname, cancel_token).await {
+            Ok(client) => {
+                let result = client
+                    .with_reconnect_config(ReconnectConfig::DISABLED)
+                    .run()
+                    .await;
+                if let Err(e) = result {
+                    error!("🌋 {} failed -> {}", nickname, e);
+                }
+            }
+            Err(e) => error!("🌋 {} could not connect -> {}", nickname, e),
+        }
+    })
+}
+
+/// Settings of a tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentConfig {
+    pub pairing: Pairing,
+
+    /// The number of players in every match, from 2 to 4.
+    pub players_per_match: usize,
+
+    /// Every pairing is played once on the map generated from each of these seeds.
+    pub seeds: Vec<u32>,
+
+    /// The number of rounds of a Swiss tournament. Ignored in round-robin tournaments.
+    pub rounds: usize,
+
+    /// The maximum number of matches played at the same time.
+    pub parallel: usize,
+
+    pub grid_dimension: u32,
+
+    /// The number of ticks every match lasts.
+    pub ticks: u32,
+
+    /// The longest time the server waits for responses of the bots, in milliseconds.
+    ///
+    /// Matches are broadcast eagerly, so the next tick starts as soon as all bots respond.
+    /// This only affects matches played on the server.
+    pub broadcast_interval: u32,
+
+    /// Matches with executables that do not end in time are aborted.
+    pub match_timeout: Duration,
+}
+
+/// The outcome of a single match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchResult {
+    pub round: usize,
+
+    pub seed: u32,
+
+    /// Indices of the participating entrants.
+    pub entrants: Vec<usize>,
+
+    /// The final score of each participant, in the order of `entrants`.
+    pub scores: Vec<u64>,
+
+    /// The number of kills of each participant, in the order of `entrants`.
+    pub kills: Vec<u64>,
+}
+
+impl MatchResult {
+    /// Returns the entrant with the highest score, or `None` on a tie.
+    pub fn winner(&self) -> Option<usize> {
+        let best = *self.scores.iter().max()?;
+        let mut winners = self
+            .entrants
+            .iter()
+            .zip(&self.scores)
+            .filter(|(_, &score)| score == best);
+
+        match (winners.next(), winners.next()) {
+            (Some((&winner, _)), None) => Some(winner),
+            _ => None,
+        }
+    }
+}
+
+/// The results of an entrant over the whole tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub matches: u64,
+    pub wins: u64,
+    pub score: u64,
+    pub kills: u64,
+    pub rating: f64,
+}
+
+impl Standing {
+    /// Returns the average score per match.
+    pub fn average_score(&self) -> f64 {
+        match self.matches {
+            0 => 0.0,
+            matches => self.score as f64 / matches as f64,
+        }
+    }
+}
+
+/// The results of a whole tournament.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TournamentReport {
+    /// All played matches, ordered by round, pairing and seed.
+    pub matches: Vec<MatchResult>,
+
+    /// Standings of all entrants, from the highest to the lowest rating.
+    pub standings: Vec<Standing>,
+
+    /// Matches that could not be finished, with the reason.
+    pub failures: Vec<String>,
+}
+
+/// Plays a tournament between the given entrants and rates them.
+///
+/// Matches are played on the [`Simulator`] directly if all their participants run
+/// in the same process, or on a local [`GameServer`] if any of them is an executable.
+pub async fn run_tournament(
+    entrants: &[Entrant],
+    config: &TournamentConfig,
+) -> Result<TournamentReport, String> {
+    if !(2..=4).contains(&config.players_per_match) {
+        return Err("A match needs between 2 and 4 players".to_string());
+    }
+    if entrants.len() < config.players_per_match {
+        return Err(format!(
+            "At least {} entrants are needed, got {}",
+            config.players_per_match,
+            entrants.len()
+        ));
+    }
+    let names: HashSet<&str> = entrants.iter().map(Entrant::name).collect();
+    if names.len() != entrants.len() {
+        return Err("Entrant names have to be unique".to_string());
+    }
+    if config.grid_dimension < MIN_GRID_DIMENSION {
+        return Err(format!(
+            "Grid dimension {} is smaller than {}",
+            config.grid_dimension, MIN_GRID_DIMENSION
+        ));
+    }
+
+    let rounds = match config.pairing {
+        Pairing::RoundRobin => 1,
+        Pairing::Swiss => config.rounds,
+    };
+
+    let mut ratings = EloRatings::new(entrants.len());
+    let mut played: HashSet<(usize, usize)> = HashSet::new();
+    let mut report = TournamentReport {
+        matches: vec![],
+        standings: vec![],
+        failures: vec![],
+    };
+
+    for round in 0..rounds {
+        let pairings = match config.pairing {
+            Pairing::RoundRobin => round_robin(entrants.len(), config.players_per_match),
+            Pairing::Swiss => swiss_round(&ratings.ranking(), config.players_per_match, &played),
+        };
+
+        let jobs: Vec<(Vec<usize>, u32)> = pairings
+            .iter()
+            .flat_map(|pairing| config.seeds.iter().map(|&seed| (pairing.clone(), seed)))
+            .collect();
+        let total = jobs.len();
+
+        let mut results: Vec<(usize, Result<MatchResult, String>)> =
+            futures_util::stream::iter(jobs.into_iter().enumerate())
+                .map(|(index, (participants, seed))| async move {
+                    let result = play_match(entrants, &participants, round, seed, config).await;
+                    (index, result)
+                })
+                .buffer_unordered(config.parallel.max(1))
+                .inspect(|(index, result)| match result {
+                    Ok(result) => info!(
+                        "🏁 Round {}, match {}/{} (seed {}) won by {}",
+                        round + 1,
+                        index + 1,
+                        total,
+                        result.seed,
+                        result
+                            .winner()
+                            .map_or("nobody", |winner| entrants[winner].name())
+                    ),
+                    Err(e) => error!(
+                        "🌋 Round {}, match {}/{} failed -> {}",
+                        round + 1,
+                        index + 1,
+                        total,
+                        e
+                    ),
+                })
+                .collect()
+                .await;
+
+        // Ratings depend on the order of updates, which should not depend on timing
+        results.sort_by_key(|(index, _)| *index);
+
+        for (_, result) in results {
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => {
+                    report.failures.push(e);
+                    continue;
+                }
+            };
+
+            let scores: Vec<(usize, u64)> = result
+                .entrants
+                .iter()
+                .copied()
+                .zip(result.scores.iter().copied())
+                .collect();
+            ratings.update(&scores);
+
+            for &a in &result.entrants {
+                for &b in &result.entrants {
+                    if a < b {
+                        played.insert((a, b));
+                    }
+                }
+            }
+
+            report.matches.push(result);
+        }
+    }
+
+    report.standings = ratings
+        .ranking()
+        .into_iter()
+        .map(|entrant| {
+            let mut standing = Standing {
+                name: entrants[entrant].name().to_string(),
+                matches: 0,
+                wins: 0,
+                score: 0,
+                kills: 0,
+                rating: ratings.rating(entrant),
+            };
+
+            for result in &report.matches {
+                let Some(position) = result.entrants.iter().position(|&e| e == entrant) else {
+                    continue;
+                };
+                standing.matches += 1;
+                standing.wins += u64::from(result.winner() == Some(entrant));
+                standing.score += result.scores[position];
+                standing.kills += result.kills[position];
+            }
+
+            standing
+        })
+        .collect();
+
+    Ok(report)
+}
+
+/// Plays a single match between the given entrants on the map generated from `seed`.
+async fn play_match(
+    entrants: &[Entrant],
+    participants: &[usize],
+    round: usize,
+    seed: u32,
+    config: &TournamentConfig,
+) -> Result<MatchResult, String> {
+    let settings = ServerSettings::new(
+        config.grid_dimension,
+        participants.len() as u32,
+        seed,
+        config.broadcast_interval,
+        true,
+        false,
+        Some(config.ticks),
+        Some(format!("tournament-round-{}-seed-{}", round + 1, seed)),
+        SUPPORTED_SERVER_VERSION.to_string(),
+    );
+    let match_entrants: Vec<Entrant> = participants
+        .iter()
+        .map(|&index| entrants[index].clone())
+        .collect();
+
+    let in_process: Option<Vec<(String, CreateBot)>> = match_entrants
+        .iter()
+        .map(|entrant| match entrant {
+            Entrant::InProcess { name, create, .. } => Some((name.clone(), *create)),
+            Entrant::Executable { .. } => None,
+        })
+        .collect();
+
+    let game_end = match in_process {
+        Some(bots) => tokio::task::spawn_blocking(move || play_in_process(bots, settings))
+            .await
+            .map_err(|e| format!("Match panicked -> {}", e))??,
+        None => play_on_server(match_entrants, settings, config.match_timeout).await?,
+    };
+
+    let mut result = MatchResult {
+        round,
+        seed,
+        entrants: participants.to_vec(),
+        scores: vec![],
+        kills: vec![],
+    };
+
+    for &index in participants {
+        let player = game_end
+            .players
+            .iter()
+            .find(|player| player.nickname == entrants[index].name())
+            .ok_or_else(|| format!("{} is missing in the results", entrants[index].name()))?;
+        result.scores.push(player.score);
+        result.kills.push(player.kills);
+    }
+
+    Ok(result)
+}
+
+/// Plays a match between bots running in the current thread, without any network communication.
+///
+/// Bots get an unlimited time budget, as there is no server waiting for them.
+pub fn play_in_process(
+    bots: Vec<(String, CreateBot)>,
+    settings: ServerSettings,
+) -> Result<GameEnd, String> {
+    let players: Vec<LobbyPlayer> = bots
+        .iter()
+        .enumerate()
+        .map(|(index, (name, _))| {
+            LobbyPlayer::new(
+                format!("player-{}", index + 1),
+                name.clone(),
+                PLAYER_COLORS[index % PLAYER_COLORS.len()],
+            )
+        })
+        .collect();
+
+    let mut simulator = Simulator::with_players(settings.seed, settings, players.clone())?;
+    let mut bots: Vec<Box<dyn BotTrait>> = players
+        .iter()
+        .zip(bots)
+        .map(|(player, (_, create))| create(simulator.lobby_data(&player.id)))
+        .collect();
+
+    while !simulator.is_finished() {
+        let actions = players
+            .iter()
+            .zip(bots.iter_mut())
+            .map(|(player, bot)| {
+                let game_state = simulator.game_state(&player.id);
+                let response = bot.next_move(game_state, &TickContext::unlimited());
+                (player.id.clone(), response)
+            })
+            .collect();
+
+        simulator.tick(actions);
+    }
+
+    let game_end = simulator.game_end();
+    for bot in &bots {
+        bot.on_game_ended(game_end.clone());
+    }
+
+    Ok(game_end)
+}
+
+/// Plays a match on a local [`GameServer`], with every entrant connecting to it over WebSocket.
+pub async fn play_on_server(
+    entrants: Vec<Entrant>,
+    settings: ServerSettings,
+    timeout: Duration,
+) -> Result<GameEnd, String> {
+    let config = ServerConfig {
+        join_code: None,
+        settings,
+    };
+    let server = GameServer::bind("127.0.0.1:0", config)
+        .await
+        .map_err(|e| format!("Error starting the server -> {}", e))?;
+    let port = server
+        .local_addr()
+        .map_err(|e| format!("Error starting the server -> {}", e))?
+        .port();
+
+    let cancel_token = CancellationToken::new();
+    let server_task = tokio::spawn(server.run(cancel_token.clone()));
+
+    let mut clients = vec![];
+    let mut processes = vec![];
+    for entrant in entrants {
+        match entrant {
+            Entrant::InProcess { name, connect, .. } => {
+                clients.push(connect(port, name, cancel_token.child_token()));
+            }
+            Entrant::Executable { name, path, args } => {
+                let process = Command::new(&path)
+                    .args(args)
+                    .args(["--host", "127.0.0.1", "--port", &port.to_string()])
+                    .args(["--nickname", &name])
+                    .kill_on_drop(true)
+                    .spawn()
+                    .map_err(|e| format!("Error starting {} -> {}", path.display(), e));
+
+                match process {
+                    Ok(process) => processes.push(process),
+                    Err(e) => {
+                        cancel_token.cancel();
+                        return Err(e);
+                    }
+                }
+            }
+        }
+    }
+
+    let game_end = tokio::time::timeout(timeout, server_task).await;
+    cancel_token.cancel();
+
+    join_all(clients).await;
+    for mut process in processes {
+        if tokio::time::timeout(Duration::from_secs(5), process.wait())
+            .await
+            .is_err()
+        {
+            let _ = process.kill().await;
+        }
+    }
+
+    match game_end {
+        Ok(Ok(Some(game_end))) => Ok(game_end),
+        Ok(Ok(None)) => Err("The match was cancelled".to_string()),
+        Ok(Err(e)) => Err(format!("The server panicked -> {}", e)),
+        Err(_) => Err(format!("The match did not end within {:?}", timeout)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::pass_bot::PassBot;
+    use crate::ws_client::packet::packets::bot_response::ability_type::AbilityType;
+    use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+    use crate::ws_client::packet::packets::game_state::game_state::GameState;
+
+    struct FiringBot;
+
+    impl BotTrait for FiringBot {
+        fn on_joining_lobby(_: LobbyData) -> Self {
+            FiringBot
+        }
+
+        fn next_move(&mut self, _: GameState, _: &TickContext) -> BotResponse {
+            BotResponse::AbilityUse {
+                ability_type: AbilityType::FireBullet,
+            }
+        }
+    }
+
+    fn config(pairing: Pairing) -> TournamentConfig {
+        TournamentConfig {
+            pairing,
+            players_per_match: 2,
+            seeds: vec![1, 2],
+            rounds: 2,
+            parallel: 4,
+            grid_dimension: 16,
+            ticks: 30,
+            broadcast_interval: 100,
+            match_timeout: Duration::from_secs(30),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_round_robin_tournament() {
+        let entrants = [
+            Entrant::in_process::<PassBot>("first"),
+            Entrant::in_process::<PassBot>("second"),
+            Entrant::in_process::<FiringBot>("third"),
+        ];
+
+        let report = run_tournament(&entrants, &config(Pairing::RoundRobin))
+            .await
+            .unwrap();
+
+        assert_eq!(report.matches.len(), 6);
+        assert!(report.failures.is_empty());
+        assert_eq!(report.standings.len(), 3);
+        assert!(report
+            .standings
+            .iter()
+            .all(|standing| standing.matches == 4));
+    }
+
+    #[tokio::test]
+    async fn test_swiss_tournament() {
+        let entrants = [
+            Entrant::in_process::<PassBot>("first"),
+            Entrant::in_process::<PassBot>("second"),
+            Entrant::in_process::<PassBot>("third"),
+        ];
+
+        let report = run_tournament(&entrants, &config(Pairing::Swiss))
+            .await
+            .unwrap();
+
+        // One entrant sits out every round
+        assert_eq!(report.matches.len(), 4);
+        assert_eq!(report.matches[0].round, 0);
+        assert_eq!(report.matches[3].round, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_entrants() {
+        let duplicates = [
+            Entrant::in_process::<PassBot>("bot"),
+            Entrant::in_process::<PassBot>("bot"),
+        ];
+        assert!(run_tournament(&duplicates, &config(Pairing::RoundRobin))
+            .await
+            .is_err());
+
+        let too_few = [Entrant::in_process::<PassBot>("bot")];
+        assert!(run_tournament(&too_few, &config(Pairing::RoundRobin))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_plays_on_server() {
+        let entrants = vec![
+            Entrant::in_process::<PassBot>("first"),
+            Entrant::in_process::<PassBot>("second"),
+        ];
+        let settings =
+            ServerSettings::new(16, 2, 5, 10, true, false, Some(10), None, "test".into());
+
+        let game_end = play_on_server(entrants, settings, Duration::from_secs(30))
+            .await
+            .unwrap();
+        assert_eq!(game_end.players.len(), 2);
+    }
+}
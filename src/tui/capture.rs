@@ -0,0 +1,88 @@
+use super::viewer::Viewer;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::fd::{AsFd, AsRawFd, FromRawFd, OwnedFd};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// Redirects the standard output and error of the process into the log of a [`Viewer`].
+///
+/// Everything printed with `println!` or `eprintln!` while the terminal UI is open,
+/// by the bot or by the client, would otherwise be drawn over it.
+/// The original streams are restored when the capture is dropped,
+/// once every line printed until then has been pushed to the log.
+pub struct OutputCapture {
+    stdout: OwnedFd,
+    stderr: OwnedFd,
+    /// Reads the pipe until all of its writing ends are closed.
+    reader: Option<JoinHandle<()>>,
+}
+
+impl OutputCapture {
+    pub fn start(viewer: Arc<Viewer>) -> io::Result<OutputCapture> {
+        let stdout = io::stdout().as_fd().try_clone_to_owned()?;
+        let stderr = io::stderr().as_fd().try_clone_to_owned()?;
+        let (reader, writer) = pipe()?;
+
+        io::stdout().flush()?;
+        redirect(writer.as_fd(), io::stdout().as_fd())?;
+        redirect(writer.as_fd(), io::stderr().as_fd())?;
+        drop(writer);
+
+        let reader = std::thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                match line {
+                    Ok(line) => viewer.push_log(line),
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(OutputCapture {
+            stdout,
+            stderr,
+            reader: Some(reader),
+        })
+    }
+
+    /// Returns a handle to the original standard output, for drawing the terminal UI.
+    pub fn terminal(&self) -> io::Result<File> {
+        Ok(File::from(self.stdout.try_clone()?))
+    }
+}
+
+impl Drop for OutputCapture {
+    fn drop(&mut self) {
+        let _ = io::stdout().flush();
+        let _ = redirect(self.stdout.as_fd(), io::stdout().as_fd());
+        let _ = redirect(self.stderr.as_fd(), io::stderr().as_fd());
+
+        // Restoring the streams closed the last writing ends of the pipe,
+        // so the reader stops once it has read what is left in it
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+/// Creates a pipe and returns its reading and writing ends.
+fn pipe() -> io::Result<(File, OwnedFd)> {
+    let mut fds = [0; 2];
+    // SAFETY: `fds` has room for both file descriptors
+    if unsafe { libc::pipe(fds.as_mut_ptr()) } == -1 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // SAFETY: the file descriptors were just opened and are not owned by anything else
+    let (reader, writer) = unsafe { (OwnedFd::from_raw_fd(fds[0]), OwnedFd::from_raw_fd(fds[1])) };
+    Ok((File::from(reader), writer))
+}
+
+/// Makes `target` refer to the same file as `source`.
+fn redirect(source: impl AsRawFd, target: impl AsRawFd) -> io::Result<()> {
+    // SAFETY: both file descriptors are open for the duration of the call
+    match unsafe { libc::dup2(source.as_raw_fd(), target.as_raw_fd()) } {
+        -1 => Err(io::Error::last_os_error()),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,4 @@
+#[cfg(unix)]
+pub mod capture;
+pub mod tui;
+pub mod viewer;
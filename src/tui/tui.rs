@@ -0,0 +1,374 @@
+use super::viewer::{Viewer, ViewerState, MAX_WARNINGS};
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::glyphs::tile_glyph;
+use crate::ws_client::packet::packets::game_state::player::Player;
+use crate::ws_client::packet::packets::game_state::tile::tile::{Tile, TileEntity};
+use crate::ws_client::packet::packets::game_state::zone::ZoneStatus;
+use ratatui::backend::{Backend, CrosstermBackend};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{
+    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph, Row, Table};
+use ratatui::{Frame, Terminal};
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// How often key presses and terminal resizes are checked when nothing else changes.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Draws the game in the terminal, redrawing it in place whenever the viewer is updated.
+///
+/// Runs until the cancellation token is cancelled, or until `q`, `Esc` or `Ctrl+C`
+/// is pressed, which cancels it. The output printed while the UI was open
+/// is printed again once it is closed, on Unix it is shown in the log panel meanwhile.
+pub async fn run_tui(viewer: Arc<Viewer>, cancel_token: CancellationToken) -> io::Result<()> {
+    #[cfg(unix)]
+    let capture = super::capture::OutputCapture::start(viewer.clone())?;
+    #[cfg(unix)]
+    let output: Box<dyn Write + Send> = Box::new(capture.terminal()?);
+    #[cfg(not(unix))]
+    let output: Box<dyn Write + Send> = Box::new(io::stdout());
+
+    enable_raw_mode()?;
+    let mut terminal = Terminal::new(CrosstermBackend::new(output))?;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+
+    let result = draw_loop(&mut terminal, &viewer, &cancel_token).await;
+
+    let _ = execute!(terminal.backend_mut(), LeaveAlternateScreen);
+    let _ = disable_raw_mode();
+    let _ = terminal.show_cursor();
+
+    // Waits for the lines printed until now to reach the log, so none are lost
+    #[cfg(unix)]
+    let _ = tokio::task::spawn_blocking(move || drop(capture)).await;
+    for line in viewer.snapshot().log {
+        println!("{}", line);
+    }
+
+    result
+}
+
+async fn draw_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    viewer: &Viewer,
+    cancel_token: &CancellationToken,
+) -> io::Result<()> {
+    loop {
+        let state = viewer.snapshot();
+        terminal.draw(|frame| draw(frame, &state))?;
+
+        tokio::select! {
+            _ = viewer.changed() => {}
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = cancel_token.cancelled() => return Ok(()),
+        }
+
+        while event::poll(Duration::ZERO)? {
+            if let Event::Key(key) = event::read()? {
+                let quit = matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                    || (key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL));
+
+                if key.kind == KeyEventKind::Press && quit {
+                    info!("🛑 Viewer closed, initiating shutdown...");
+                    cancel_token.cancel();
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Draws the map next to a side panel with players, zones, the last response,
+/// recent warnings and the log.
+pub fn draw(frame: &mut Frame, state: &ViewerState) {
+    let Some(game_state) = &state.game_state else {
+        let waiting = Paragraph::new("Waiting for the game to start...").block(
+            Block::bordered()
+                .title(" MonoTanks ")
+                .title_bottom(" q to quit "),
+        );
+        frame.render_widget(waiting, frame.area());
+        return;
+    };
+
+    let player_id = state.player_id.as_deref().unwrap_or_default();
+    let map_width = game_state.dimension() as u16 * 2 + 3;
+    let [map_area, side_area] =
+        Layout::horizontal([Constraint::Length(map_width), Constraint::Min(30)])
+            .areas(frame.area());
+
+    let map = Paragraph::new(map_lines(game_state, player_id)).block(
+        Block::bordered()
+            .title(format!(" Tick {} ", game_state.tick))
+            .title_bottom(" q to quit "),
+    );
+    frame.render_widget(map, map_area);
+
+    let [players_area, tank_area, zones_area, response_area, warnings_area, log_area] =
+        Layout::vertical([
+            Constraint::Length(game_state.players.len() as u16 + 3),
+            Constraint::Length(3),
+            Constraint::Length(game_state.zones.len() as u16 + 2),
+            Constraint::Length(3),
+            Constraint::Length(MAX_WARNINGS as u16 + 2),
+            Constraint::Min(3),
+        ])
+        .areas(side_area);
+
+    frame.render_widget(players_table(&game_state.players, player_id), players_area);
+
+    let tank = Paragraph::new(tank_summary(game_state, player_id))
+        .block(Block::bordered().title(" Tank "));
+    frame.render_widget(tank, tank_area);
+
+    let zones: Vec<Line> = game_state
+        .zones
+        .iter()
+        .map(|zone| {
+            Line::from(vec![
+                Span::styled(
+                    format!("{} ", zone.index as char),
+                    Style::new().fg(Color::Blue),
+                ),
+                Span::raw(zone_status(&zone.status, &game_state.players)),
+            ])
+        })
+        .collect();
+    frame.render_widget(
+        Paragraph::new(zones).block(Block::bordered().title(" Zones ")),
+        zones_area,
+    );
+
+    let response = state
+        .last_response
+        .as_ref()
+        .map_or("-".to_string(), |response| format!("{:?}", response));
+    frame.render_widget(
+        Paragraph::new(response).block(Block::bordered().title(" Last response ")),
+        response_area,
+    );
+
+    let warnings: Vec<Line> = state
+        .warnings
+        .iter()
+        .map(|warning| Line::styled(format!("{:?}", warning), Style::new().fg(Color::Yellow)))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(warnings).block(Block::bordered().title(" Warnings ")),
+        warnings_area,
+    );
+
+    let visible_lines = log_area.height.saturating_sub(2) as usize;
+    let log: Vec<Line> = state
+        .log
+        .iter()
+        .skip(state.log.len().saturating_sub(visible_lines))
+        .map(|line| Line::raw(line.as_str()))
+        .collect();
+    frame.render_widget(
+        Paragraph::new(log).block(Block::bordered().title(" Log ")),
+        log_area,
+    );
+}
+
+fn map_lines<'a>(game_state: &GameState, player_id: &str) -> Vec<Line<'a>> {
+    game_state
+        .map
+        .iter()
+        .map(|row| {
+            Line::from(
+                row.iter()
+                    .map(|tile| {
+                        Span::styled(
+                            format!(" {}", tile_glyph(tile, player_id)),
+                            tile_style(tile, &game_state.players),
+                        )
+                    })
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect()
+}
+
+/// Colors tanks with the color of their owner, and dims tiles hidden by the fog of war.
+fn tile_style(tile: &Tile, players: &[Player]) -> Style {
+    let style = if tile.entities.iter().any(TileEntity::is_wall) {
+        Style::new().fg(Color::DarkGray)
+    } else {
+        match tile.entities.first() {
+            Some(TileEntity::Tank(tank)) => players
+                .iter()
+                .find(|player| player.id == tank.owner_id)
+                .map_or(Style::new(), |player| Style::new().fg(player_color(player)))
+                .add_modifier(Modifier::BOLD),
+            Some(TileEntity::Bullet(_)) | Some(TileEntity::Mine(_)) => {
+                Style::new().fg(Color::LightRed)
+            }
+            Some(TileEntity::Laser(_)) => Style::new().fg(Color::Magenta),
+            Some(TileEntity::Item(_)) => Style::new().fg(Color::Cyan),
+            Some(TileEntity::Wall) | Some(TileEntity::Unknown { .. }) => Style::new(),
+            None if tile.zone_index.is_some() => Style::new().fg(Color::Blue),
+            None => Style::new(),
+        }
+    };
+
+    if tile.visible {
+        style
+    } else {
+        style.add_modifier(Modifier::DIM)
+    }
+}
+
+fn players_table<'a>(players: &'a [Player], player_id: &str) -> Table<'a> {
+    let rows = players.iter().map(|player| {
+        let mut name_style = Style::new().fg(player_color(player));
+        if player.id == player_id {
+            name_style = name_style.add_modifier(Modifier::BOLD);
+        }
+
+        let respawn = player
+            .ticks_to_regen
+            .map_or(String::new(), |ticks| format!("in {} ticks", ticks));
+
+        Row::new(vec![
+            Span::styled(player.nickname.as_str(), name_style),
+            Span::raw(
+                player
+                    .score
+                    .map_or("-".to_string(), |score| score.to_string()),
+            ),
+            Span::raw(format!("{} ms", player.ping)),
+            Span::raw(respawn),
+        ])
+    });
+
+    Table::new(
+        rows,
+        [
+            Constraint::Min(12),
+            Constraint::Length(6),
+            Constraint::Length(8),
+            Constraint::Length(14),
+        ],
+    )
+    .header(
+        Row::new(vec!["Player", "Score", "Ping", "Respawn"])
+            .style(Style::new().add_modifier(Modifier::BOLD)),
+    )
+    .block(Block::bordered().title(" Players "))
+}
+
+/// Describes the health, bullets and secondary item of your own tank.
+fn tank_summary(game_state: &GameState, player_id: &str) -> String {
+    let Some((_, tank)) = game_state.my_tank(player_id) else {
+        return "Dead".to_string();
+    };
+
+    let optional = |value: Option<i64>| value.map_or("-".to_string(), |value| value.to_string());
+    let item = tank
+        .secondary_item
+        .as_ref()
+        .map_or("-".to_string(), |item| format!("{:?}", item));
+
+    format!(
+        "Health {}  Bullets {} (next in {})  Item {}",
+        optional(tank.health),
+        optional(tank.turret.bullet_count),
+        optional(tank.turret.ticks_to_regen_bullet),
+        item
+    )
+}
+
+fn zone_status(status: &ZoneStatus, players: &[Player]) -> String {
+    let nickname = |id: &str| {
+        players
+            .iter()
+            .find(|player| player.id == id)
+            .map_or(id.to_string(), |player| player.nickname.clone())
+    };
+
+    match status {
+        ZoneStatus::Neutral => "Neutral".to_string(),
+        ZoneStatus::BeingCaptured(capturing) => format!(
+            "Being captured by {} in {} ticks",
+            nickname(&capturing.player_id),
+            capturing.remaining_ticks
+        ),
+        ZoneStatus::Captured(captured) => {
+            format!("Captured by {}", nickname(&captured.player_id))
+        }
+        ZoneStatus::BeingContested(contested) => match &contested.captured_by_id {
+            Some(id) => format!("Contested, held by {}", nickname(id)),
+            None => "Contested".to_string(),
+        },
+        ZoneStatus::BeingRetaken(retaking) => format!(
+            "Being retaken from {} by {} in {} ticks",
+            nickname(&retaking.captured_by_id),
+            nickname(&retaking.retaken_by_id),
+            retaking.remaining_ticks
+        ),
+    }
+}
+
+/// Converts the ARGB color of a player to a terminal color.
+fn player_color(player: &Player) -> Color {
+    Color::Rgb(
+        (player.color >> 16) as u8,
+        (player.color >> 8) as u8,
+        player.color as u8,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::simulator::Simulator;
+    use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+    use crate::ws_client::packet::warning::Warning;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_draws_map_and_side_panel() {
+        let settings = ServerSettings::new(16, 2, 1, 100, false, false, None, None, "test".into());
+        let game_state: GameState = Simulator::new(1, settings)
+            .unwrap()
+            .raw_game_state("player-1")
+            .into();
+
+        let viewer = Viewer::new();
+        viewer.set_player_id("player-1".to_string());
+        viewer.set_game_state(game_state.clone());
+        viewer.set_last_response(BotResponse::Pass);
+        viewer.push_warning(Warning::SlowResponseWarning);
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal
+            .draw(|frame| draw(frame, &viewer.snapshot()))
+            .unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains(&format!("Tick {}", game_state.tick)));
+        assert!(screen.contains("Pass"));
+        assert!(screen.contains("SlowResponseWarning"));
+        for player in &game_state.players {
+            assert!(screen.contains(&player.nickname));
+        }
+    }
+}
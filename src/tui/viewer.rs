@@ -0,0 +1,113 @@
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::warning::Warning;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// The number of most recent warnings kept for display.
+pub const MAX_WARNINGS: usize = 5;
+
+/// The number of most recent output lines kept for display.
+pub const MAX_LOG_LINES: usize = 200;
+
+/// Everything the terminal UI shows, as of the last update.
+#[derive(Debug, Clone, Default)]
+pub struct ViewerState {
+    /// The ID of the player the bot plays as, known once the lobby data is received.
+    pub player_id: Option<String>,
+
+    /// The most recent game state received from the server.
+    pub game_state: Option<GameState>,
+
+    /// The last response sent to the server, including fallback responses.
+    pub last_response: Option<BotResponse>,
+
+    /// The most recent warnings received from the server, the newest last.
+    pub warnings: VecDeque<Warning>,
+
+    /// The most recent lines printed by the bot and the client, the newest last.
+    pub log: VecDeque<String>,
+}
+
+/// Collects what the client receives and sends, so the terminal UI can draw it.
+///
+/// It is shared between the client, which updates it, and the UI task,
+/// which is woken up after every update.
+#[derive(Debug, Default)]
+pub struct Viewer {
+    state: Mutex<ViewerState>,
+    changed: Notify,
+}
+
+impl Viewer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_player_id(&self, player_id: String) {
+        self.update(|state| state.player_id = Some(player_id));
+    }
+
+    pub fn set_game_state(&self, game_state: GameState) {
+        self.update(|state| state.game_state = Some(game_state));
+    }
+
+    pub fn set_last_response(&self, bot_response: BotResponse) {
+        self.update(|state| state.last_response = Some(bot_response));
+    }
+
+    pub fn push_warning(&self, warning: Warning) {
+        self.update(|state| push_bounded(&mut state.warnings, warning, MAX_WARNINGS));
+    }
+
+    pub fn push_log(&self, line: String) {
+        self.update(|state| push_bounded(&mut state.log, line, MAX_LOG_LINES));
+    }
+
+    /// Returns a copy of the current state, so drawing does not block the client.
+    pub fn snapshot(&self) -> ViewerState {
+        self.state.lock().unwrap().clone()
+    }
+
+    /// Waits until the state is updated.
+    pub async fn changed(&self) {
+        self.changed.notified().await;
+    }
+
+    fn update(&self, update: impl FnOnce(&mut ViewerState)) {
+        update(&mut self.state.lock().unwrap());
+        self.changed.notify_one();
+    }
+}
+
+fn push_bounded<T>(queue: &mut VecDeque<T>, value: T, limit: usize) {
+    if queue.len() == limit {
+        queue.pop_front();
+    }
+    queue.push_back(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_recent_warnings() {
+        let viewer = Viewer::new();
+        for index in 0..MAX_WARNINGS + 2 {
+            viewer.push_warning(Warning::CustomWarning {
+                message: index.to_string(),
+            });
+        }
+
+        let warnings = viewer.snapshot().warnings;
+        assert_eq!(warnings.len(), MAX_WARNINGS);
+        assert_eq!(
+            warnings.front(),
+            Some(&Warning::CustomWarning {
+                message: "2".to_string()
+            })
+        );
+    }
+}
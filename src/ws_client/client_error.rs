@@ -0,0 +1,84 @@
+use derive_more::derive::{Display, Error, IsVariant};
+use tokio::task::JoinError;
+use tokio_tungstenite::tungstenite;
+
+/// Everything that can go wrong while a bot plays on the server.
+///
+/// [`WebSocketClient::run`](super::ws_client::WebSocketClient::run) returns the errors
+/// that end the client: failing to connect or reconnect, a rejected connection
+/// and a failed client task. The others are logged and passed to the error handler,
+/// if one is set, and the game goes on.
+#[derive(Debug, Display, Error, IsVariant)]
+pub enum ClientError {
+    /// Opening the WebSocket connection failed.
+    #[display("Failed to connect to the server -> {_0}")]
+    Connect(#[error(source)] tungstenite::Error),
+
+    /// The connection was lost and every reconnection attempt failed.
+    #[display("Failed to reconnect to the server after {attempts} attempts")]
+    Reconnect { attempts: u32 },
+
+    /// The server refused the connection, e.g. because of a wrong join code
+    /// or a nickname that is already taken.
+    #[display("Connection rejected -> {reason}")]
+    ConnectionRejected { reason: String },
+
+    /// Reading from or writing to the WebSocket connection failed.
+    #[display("WebSocket error -> {_0}")]
+    WebSocket(#[error(source)] tungstenite::Error),
+
+    /// A message from the server is not a valid packet.
+    #[display("Failed to parse message -> {source}")]
+    Parse {
+        /// The raw text of the message.
+        payload: String,
+        source: serde_json::Error,
+    },
+
+    /// The server could not process the payload of a packet sent by the client.
+    #[display("Invalid payload -> {message}")]
+    InvalidPayload { message: String },
+
+    /// The client sent a packet the server did not expect at that moment.
+    #[display("Invalid packet usage")]
+    InvalidPacketUsage,
+
+    /// The client sent a packet of a type unknown to the server.
+    #[display("Invalid packet type")]
+    InvalidPacketType,
+
+    /// The bot panicked while making its move, the fallback response was sent instead.
+    #[display("Bot panicked -> {message}")]
+    BotPanicked { message: String },
+
+    /// A packet that needs the bot arrived before the lobby data.
+    #[display("Bot not initialized")]
+    BotNotInitialized,
+
+    /// The packet could not be passed on, because the connection is closing.
+    #[display("Failed to send {packet}")]
+    Send { packet: &'static str },
+
+    /// A task of the client failed unexpectedly.
+    #[display("Client task failed -> {_0}")]
+    Task(#[error(source)] JoinError),
+}
+
+impl ClientError {
+    /// Creates the error of a panicked bot task, with the message it panicked with.
+    pub fn bot_panicked(error: JoinError) -> ClientError {
+        let message = match error.try_into_panic() {
+            Ok(payload) => payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_default(),
+            Err(error) => error.to_string(),
+        };
+
+        ClientError::BotPanicked { message }
+    }
+}
+
+/// Called with every error that does not end the client.
+pub type ErrorHandler = std::sync::Arc<dyn Fn(&ClientError) + Send + Sync>;
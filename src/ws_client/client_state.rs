@@ -0,0 +1,262 @@
+use crate::bot_trait::tick_context::TickContext;
+use crate::bot_trait::BotTrait;
+#[cfg(feature = "tui")]
+use crate::tui::viewer::Viewer;
+use crate::ws_client::client_error::{ClientError, ErrorHandler};
+use crate::ws_client::compatibility::warn_about_unknown_entities;
+use crate::ws_client::packet::enum_serialization_format::EnumSerializationFormat;
+use crate::ws_client::packet::packet::Packet;
+use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+use crate::ws_client::packet::packets::game_state::raw_game_state::RawGameState;
+use crate::ws_client::packet::packets::lobby_data::LobbyData;
+use crate::ws_client::packet::warning::Warning;
+use crate::ws_client::recorder::{PacketDirection, Recorder};
+use crate::ws_client::response_config::{FallbackResponse, ResponseConfig};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::sync::{Mutex, Notify};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::error;
+
+/// State of a client that outlives a single connection.
+///
+/// It is shared between the reader task and the packet handlers and is kept
+/// when the client reconnects, so the bot does not lose its memory.
+pub struct ClientState<B: BotTrait> {
+    /// The bot instance, created when the first lobby data is received.
+    pub bot: Arc<Mutex<Option<B>>>,
+
+    /// Notified once the bot instance has been created.
+    bot_created: Notify,
+
+    /// Set once the game has ended or the connection was rejected,
+    /// after which the client no longer tries to reconnect.
+    finished: AtomicBool,
+
+    /// The most recent lobby data, used to compute the deadline of each tick.
+    lobby_data: std::sync::Mutex<Option<LobbyData>>,
+
+    /// How long the bot may take to respond and what is sent when it overruns.
+    pub response_config: ResponseConfig,
+
+    /// How enums are encoded in the packets sent to the server.
+    pub enum_format: EnumSerializationFormat,
+
+    /// The id of the most recently received game state.
+    /// Responses to older game states are not sent.
+    latest_game_state_id: std::sync::Mutex<Option<String>>,
+
+    /// The last response the bot returned in time.
+    last_response: std::sync::Mutex<Option<BotResponse>>,
+
+    /// Records every packet exchanged with the server, if enabled.
+    pub recorder: Option<Recorder>,
+
+    /// Receives the game states, responses and warnings for the terminal UI, if enabled.
+    #[cfg(feature = "tui")]
+    pub viewer: Option<Arc<Viewer>>,
+
+    /// Called with every error that does not end the client, if set.
+    error_handler: Option<ErrorHandler>,
+
+    /// The first error that ended the client, returned when it stops.
+    fatal_error: std::sync::Mutex<Option<ClientError>>,
+
+    /// The types of unknown entities already warned about, so the warning is not repeated every tick.
+    reported_entity_types: std::sync::Mutex<HashSet<String>>,
+
+    /// Warnings received while the bot was busy making its move,
+    /// passed to it once it is done.
+    pending_warnings: std::sync::Mutex<Vec<Warning>>,
+}
+
+impl<B: BotTrait> ClientState<B> {
+    pub fn new(
+        response_config: ResponseConfig,
+        enum_format: EnumSerializationFormat,
+        recorder: Option<Recorder>,
+        error_handler: Option<ErrorHandler>,
+    ) -> Self {
+        ClientState {
+            bot: Arc::new(Mutex::new(None)),
+            bot_created: Notify::new(),
+            finished: AtomicBool::new(false),
+            lobby_data: std::sync::Mutex::new(None),
+            response_config,
+            enum_format,
+            latest_game_state_id: std::sync::Mutex::new(None),
+            last_response: std::sync::Mutex::new(None),
+            recorder,
+            #[cfg(feature = "tui")]
+            viewer: None,
+            error_handler,
+            fatal_error: std::sync::Mutex::new(None),
+            reported_entity_types: std::sync::Mutex::new(HashSet::new()),
+            pending_warnings: std::sync::Mutex::new(vec![]),
+        }
+    }
+
+    /// Shares the game states, responses and warnings with a viewer.
+    #[cfg(feature = "tui")]
+    pub fn with_viewer(mut self, viewer: Option<Arc<Viewer>>) -> Self {
+        self.viewer = viewer;
+        self
+    }
+
+    /// Wakes up everything waiting in [`ClientState::wait_for_bot`].
+    pub fn notify_bot_created(&self) {
+        self.bot_created.notify_waiters();
+    }
+
+    /// Waits until the bot instance is created from the first lobby data.
+    pub async fn wait_for_bot(&self) {
+        loop {
+            // Registered before checking, so a notification in between is not missed
+            let created = self.bot_created.notified();
+            if self.bot.lock().await.is_some() {
+                return;
+            }
+            created.await;
+        }
+    }
+
+    /// Returns true if a viewer draws the game, so the client does not log the map.
+    pub fn has_viewer(&self) -> bool {
+        #[cfg(feature = "tui")]
+        return self.viewer.is_some();
+        #[cfg(not(feature = "tui"))]
+        false
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::SeqCst)
+    }
+
+    pub fn mark_finished(&self) {
+        self.finished.store(true, Ordering::SeqCst);
+    }
+
+    /// Logs an error that does not end the client and passes it to the error handler.
+    pub fn report_error(&self, error: ClientError) {
+        error!("🚨 {}", error);
+        if let Some(error_handler) = &self.error_handler {
+            error_handler(&error);
+        }
+    }
+
+    /// Marks the client as finished because of an error, which is returned when it stops.
+    /// Only the first error is kept.
+    pub fn fail(&self, error: ClientError) {
+        self.mark_finished();
+        self.fatal_error.lock().unwrap().get_or_insert(error);
+    }
+
+    /// Takes the error that ended the client, if any.
+    pub fn take_fatal_error(&self) -> Option<ClientError> {
+        self.fatal_error.lock().unwrap().take()
+    }
+
+    /// Keeps a warning for the bot until [`ClientState::deliver_pending_warnings`] is called.
+    pub fn queue_warning(&self, warning: Warning) {
+        self.pending_warnings.lock().unwrap().push(warning);
+    }
+
+    /// Passes the warnings received while the bot was busy to it, oldest first.
+    pub fn deliver_pending_warnings(&self, bot: &mut B) {
+        let warnings = std::mem::take(&mut *self.pending_warnings.lock().unwrap());
+        for warning in warnings {
+            bot.on_warning_received(warning);
+        }
+    }
+
+    pub fn set_lobby_data(&self, lobby_data: LobbyData) {
+        *self.lobby_data.lock().unwrap() = Some(lobby_data);
+    }
+
+    /// Records the packet, if enabled, and serializes it into a message for the server.
+    pub fn message(&self, packet: Packet) -> Message {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(PacketDirection::Sent, &packet);
+        }
+
+        Message::Text(packet.to_json(self.enum_format))
+    }
+
+    /// Records the text of a received message that could not be parsed as a packet, if enabled.
+    pub fn record_unparsed(&self, text: &str) {
+        if let Some(recorder) = &self.recorder {
+            recorder.record_unparsed(PacketDirection::Received, text);
+        }
+    }
+
+    /// Returns the ID of the player, once the lobby data has been received.
+    pub fn player_id(&self) -> Option<String> {
+        let lobby_data = self.lobby_data.lock().unwrap();
+        lobby_data
+            .as_ref()
+            .map(|lobby_data| lobby_data.player_id.clone())
+    }
+
+    /// Creates the context for a game state received at `received_at`,
+    /// based on the broadcast interval and the current ping of the player.
+    pub fn tick_context(&self, raw_game_state: &RawGameState, received_at: Instant) -> TickContext {
+        let lobby_data = self.lobby_data.lock().unwrap();
+        let Some(lobby_data) = lobby_data.as_ref() else {
+            return TickContext::unlimited();
+        };
+
+        let ping = raw_game_state
+            .players
+            .iter()
+            .find(|player| player.id == lobby_data.player_id)
+            .map_or(0, |player| player.ping);
+        let broadcast_interval = lobby_data.server_settings.broadcast_interval as u64;
+
+        TickContext::from_broadcast_interval(received_at, broadcast_interval, ping)
+    }
+
+    /// Warns about the types of the unknown entities in the game state that were not reported yet.
+    pub fn warn_about_unknown_entities(&self, raw_game_state: &RawGameState) {
+        let mut reported = self.reported_entity_types.lock().unwrap();
+        warn_about_unknown_entities(raw_game_state, &mut reported);
+    }
+
+    pub fn set_latest_game_state_id(&self, game_state_id: String) {
+        *self.latest_game_state_id.lock().unwrap() = Some(game_state_id);
+    }
+
+    /// Returns false if a newer game state has been received since.
+    pub fn is_latest_game_state(&self, game_state_id: &str) -> bool {
+        self.latest_game_state_id.lock().unwrap().as_deref() == Some(game_state_id)
+    }
+
+    pub fn set_last_response(&self, bot_response: BotResponse) {
+        *self.last_response.lock().unwrap() = Some(bot_response);
+    }
+
+    /// The response to send when the bot does not respond in time.
+    pub fn fallback_response(&self) -> BotResponse {
+        match self.response_config.fallback {
+            FallbackResponse::Pass => BotResponse::Pass,
+            FallbackResponse::LastMove => self
+                .last_response
+                .lock()
+                .unwrap()
+                .clone()
+                .unwrap_or(BotResponse::Pass),
+        }
+    }
+}
+
+impl<B: BotTrait> Default for ClientState<B> {
+    fn default() -> Self {
+        Self::new(
+            ResponseConfig::default(),
+            EnumSerializationFormat::default(),
+            None,
+            None,
+        )
+    }
+}
@@ -0,0 +1,32 @@
+use crate::ws_client::packet::packets::game_state::raw_game_state::RawGameState;
+use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+use crate::ws_client::packet::packets::lobby_data::server_settings::{
+    ServerSettings, SUPPORTED_SERVER_VERSION,
+};
+use std::collections::HashSet;
+use tracing::warn;
+
+/// Warns if the server runs a version this wrapper was not written for.
+pub fn warn_if_unsupported_version(server_settings: &ServerSettings) {
+    if !server_settings.is_supported_version() {
+        warn!(
+            "⚠️ Server version {} differs from the supported version {}, \
+            packets and entities this wrapper does not know will be skipped",
+            server_settings.version, SUPPORTED_SERVER_VERSION
+        );
+    }
+}
+
+/// Warns about the types of the unknown entities in the game state,
+/// once per type. `reported` keeps the types already warned about.
+pub fn warn_about_unknown_entities(raw_game_state: &RawGameState, reported: &mut HashSet<String>) {
+    let entities = raw_game_state.map.tiles.iter().flatten().flatten();
+
+    for entity in entities {
+        if let TileEntity::Unknown { entity_type, .. } = entity {
+            if reported.insert(entity_type.clone()) {
+                warn!("⚠️ Unknown entity received: {}", entity_type);
+            }
+        }
+    }
+}
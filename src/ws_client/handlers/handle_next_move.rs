@@ -1,43 +1,306 @@
-use crate::bot::Bot;
+use crate::bot_trait::tick_context::TickContext;
 use crate::bot_trait::BotTrait;
+use crate::ws_client::client_error::ClientError;
+use crate::ws_client::client_state::ClientState;
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
 use crate::ws_client::packet::packets::game_state::raw_game_state::RawGameState;
 use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
-use tokio::sync::Mutex;
 use tokio_tungstenite::tungstenite::Message;
+use tracing::{warn, Span};
 
-pub async fn handle_next_move(
+pub async fn handle_next_move<B: BotTrait + 'static>(
     tx: Sender<Message>,
-    bot: Arc<Mutex<Option<Bot>>>,
+    state: Arc<ClientState<B>>,
     raw_game_state: RawGameState,
-) -> Result<(), String> {
+    context: TickContext,
+) -> Result<(), ClientError> {
     let game_state_id = raw_game_state.id.clone();
 
-    // Spawn the blocking task with a timeout
-    let game_state = raw_game_state
-        .try_into()
-        .map_err(|e| format!("Failed to parse into game state, {}", e))?;
+    // A newer game state has already arrived, there is no point in answering this one
+    if !state.is_latest_game_state(&game_state_id) {
+        return Ok(());
+    }
+
+    let bot_response = match state.bot.clone().try_lock_owned() {
+        Ok(mut bot_guard) => {
+            let timeout = state
+                .response_config
+                .timeout
+                .unwrap_or_else(|| context.remaining());
 
-    // Let's do this in separate scope to release the lock as soon as possible
-    let bot_response = {
-        let mut bot_lock = bot
-            .try_lock()
-            .map_err(|_| "Failed to lock bot, it is already in use")?;
+            // Run the bot logic on a blocking thread, so it does not stall the reader.
+            // The span of the tick is entered there too, so the bot logs within it
+            let span = Span::current();
+            let bot_state = state.clone();
+            let bot_task = tokio::task::spawn_blocking(move || {
+                let _entered = span.enter();
+                let game_state: GameState = raw_game_state.into();
+                bot_guard.as_mut().map(|bot| {
+                    bot_state.deliver_pending_warnings(bot);
+                    let bot_response = bot.next_move(game_state, &context);
 
-        match bot_lock.as_mut() {
-            Some(bot) => Ok(bot.next_move(game_state)),
-            None => Err("Bot not initialized".to_string()),
+                    // Warnings about this move often arrive while the bot is still making it
+                    bot_state.deliver_pending_warnings(bot);
+                    bot_response
+                })
+            });
+
+            match tokio::time::timeout(timeout, bot_task).await {
+                Ok(Ok(Some(bot_response))) => {
+                    state.set_last_response(bot_response.clone());
+                    bot_response
+                }
+                Ok(Ok(None)) => return Err(ClientError::BotNotInitialized),
+                Ok(Err(e)) => {
+                    state.report_error(ClientError::bot_panicked(e));
+                    state.fallback_response()
+                }
+                Err(_) => {
+                    warn!("⏰ Bot did not respond in time, sending fallback response");
+                    state.fallback_response()
+                }
+            }
+        }
+        Err(_) => {
+            warn!("⏳ Bot is still thinking about a previous tick, sending fallback response");
+            state.fallback_response()
         }
+    };
+
+    // The bot was too slow and the server already moved on
+    if !state.is_latest_game_state(&game_state_id) {
+        return Ok(());
+    }
+
+    #[cfg(feature = "tui")]
+    if let Some(viewer) = &state.viewer {
+        viewer.set_last_response(bot_response.clone());
     }
-    .map_err(|e| format!("Failed to get bot response, {}", e))?;
 
     let response_packet = bot_response.to_packet(game_state_id);
-    let response_string = serde_json::to_string(&response_packet).map_err(|e| e.to_string())?;
 
     // Send the response
-    tx.send(Message::Text(response_string))
+    tx.send(state.message(response_packet))
         .await
-        .map_err(|_| "Failed to send message")?;
+        .map_err(|_| ClientError::Send { packet: "response" })?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::simulator::Simulator;
+    use crate::ws_client::handlers::handle_on_warning_received::handle_on_warning_received;
+    use crate::ws_client::packet::enum_serialization_format::EnumSerializationFormat;
+    use crate::ws_client::packet::packet::Packet;
+    use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+    use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+    use crate::ws_client::packet::packets::lobby_data::LobbyData;
+    use crate::ws_client::packet::warning::Warning;
+    use crate::ws_client::response_config::{FallbackResponse, ResponseConfig};
+    use std::time::Duration;
+
+    struct SlowBot;
+
+    impl BotTrait for SlowBot {
+        fn on_joining_lobby(_: LobbyData) -> Self {
+            SlowBot
+        }
+
+        fn next_move(&mut self, _: GameState, _: &TickContext) -> BotResponse {
+            std::thread::sleep(Duration::from_millis(200));
+            BotResponse::Pass
+        }
+    }
+
+    fn raw_game_state() -> RawGameState {
+        let settings = ServerSettings::new(16, 2, 1, 100, false, false, None, None, "test".into());
+        Simulator::new(1, settings)
+            .unwrap()
+            .raw_game_state("player-1")
+    }
+
+    fn client_state(
+        fallback: FallbackResponse,
+        enum_format: EnumSerializationFormat,
+    ) -> Arc<ClientState<SlowBot>> {
+        let response_config = ResponseConfig::new(Some(Duration::from_millis(20)), fallback);
+        let state = ClientState::new(response_config, enum_format, None, None);
+        *state.bot.try_lock().unwrap() = Some(SlowBot);
+        Arc::new(state)
+    }
+
+    async fn response(state: Arc<ClientState<SlowBot>>) -> Option<(String, BotResponse)> {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let raw_game_state = raw_game_state();
+        state.set_latest_game_state_id(raw_game_state.id.clone());
+
+        handle_next_move(tx, state, raw_game_state, TickContext::unlimited())
+            .await
+            .unwrap();
+
+        let message = rx.recv().await?;
+        let packet: Packet = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        BotResponse::from_packet(packet)
+    }
+
+    #[tokio::test]
+    async fn test_sends_fallback_on_timeout() {
+        let state = client_state(FallbackResponse::Pass, EnumSerializationFormat::String);
+        let (game_state_id, bot_response) = response(state).await.unwrap();
+
+        assert_eq!(game_state_id, raw_game_state().id);
+        assert_eq!(bot_response, BotResponse::Pass);
+    }
+
+    #[tokio::test]
+    async fn test_repeats_last_move_on_timeout() {
+        let state = client_state(FallbackResponse::LastMove, EnumSerializationFormat::String);
+        state.set_last_response(BotResponse::Rotation {
+            tank_rotation: Some(Rotation::Left),
+            turret_rotation: None,
+        });
+
+        let (_, bot_response) = response(state).await.unwrap();
+        assert_eq!(
+            bot_response,
+            BotResponse::Rotation {
+                tank_rotation: Some(Rotation::Left),
+                turret_rotation: None,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sends_response_in_enum_serialization_format() {
+        let state = client_state(FallbackResponse::LastMove, EnumSerializationFormat::Int);
+        state.set_last_response(BotResponse::Rotation {
+            tank_rotation: Some(Rotation::Right),
+            turret_rotation: None,
+        });
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let raw_game_state = raw_game_state();
+        state.set_latest_game_state_id(raw_game_state.id.clone());
+        handle_next_move(tx, state, raw_game_state, TickContext::unlimited())
+            .await
+            .unwrap();
+
+        let message = rx.recv().await.unwrap();
+        let payload: serde_json::Value = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(payload["payload"]["tankRotation"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_reports_panicking_bot() {
+        struct PanickingBot;
+
+        impl BotTrait for PanickingBot {
+            fn on_joining_lobby(_: LobbyData) -> Self {
+                PanickingBot
+            }
+
+            fn next_move(&mut self, _: GameState, _: &TickContext) -> BotResponse {
+                panic!("out of ideas");
+            }
+        }
+
+        let errors = Arc::new(std::sync::Mutex::new(vec![]));
+        let reported = errors.clone();
+        let state = ClientState::new(
+            ResponseConfig::default(),
+            EnumSerializationFormat::String,
+            None,
+            Some(Arc::new(move |error: &ClientError| {
+                reported.lock().unwrap().push(error.to_string())
+            })),
+        );
+        *state.bot.try_lock().unwrap() = Some(PanickingBot);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let raw_game_state = raw_game_state();
+        state.set_latest_game_state_id(raw_game_state.id.clone());
+        handle_next_move(
+            tx,
+            Arc::new(state),
+            raw_game_state,
+            TickContext::unlimited(),
+        )
+        .await
+        .unwrap();
+
+        let message = rx.recv().await.unwrap();
+        let packet: Packet = serde_json::from_str(message.to_text().unwrap()).unwrap();
+        assert_eq!(
+            BotResponse::from_packet(packet).unwrap().1,
+            BotResponse::Pass
+        );
+        assert_eq!(
+            *errors.lock().unwrap(),
+            vec!["Bot panicked -> out of ideas"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_passes_warnings_received_while_thinking() {
+        #[derive(Default)]
+        struct WarnedBot {
+            warnings: Vec<Warning>,
+        }
+
+        impl BotTrait for WarnedBot {
+            fn on_joining_lobby(_: LobbyData) -> Self {
+                WarnedBot::default()
+            }
+
+            fn next_move(&mut self, _: GameState, _: &TickContext) -> BotResponse {
+                std::thread::sleep(Duration::from_millis(200));
+                BotResponse::Pass
+            }
+
+            fn on_warning_received(&mut self, warning: Warning) {
+                self.warnings.push(warning);
+            }
+        }
+
+        let response_config =
+            ResponseConfig::new(Some(Duration::from_millis(20)), FallbackResponse::Pass);
+        let state = ClientState::new(response_config, EnumSerializationFormat::String, None, None);
+        *state.bot.try_lock().unwrap() = Some(WarnedBot::default());
+        let state = Arc::new(state);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let raw_game_state = raw_game_state();
+        state.set_latest_game_state_id(raw_game_state.id.clone());
+        handle_next_move(tx, state.clone(), raw_game_state, TickContext::unlimited())
+            .await
+            .unwrap();
+        assert!(rx.recv().await.is_some());
+
+        // The fallback response was sent, but the bot is still making its move
+        handle_on_warning_received(&state, Warning::SlowResponseWarning)
+            .await
+            .unwrap();
+
+        let bot = state.bot.lock().await;
+        assert_eq!(
+            bot.as_ref().unwrap().warnings,
+            vec![Warning::SlowResponseWarning]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_drops_stale_game_state() {
+        let state = client_state(FallbackResponse::Pass, EnumSerializationFormat::String);
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        state.set_latest_game_state_id("newer".to_string());
+
+        handle_next_move(tx, state, raw_game_state(), TickContext::unlimited())
+            .await
+            .unwrap();
+        assert!(rx.recv().await.is_none());
+    }
+}
@@ -1,25 +1,24 @@
-use crate::bot::Bot;
-use crate::bot_trait::BotTrait;
-use crate::ws_client::packet::warning::Warning;
-use std::sync::Arc;
-use tokio::sync::Mutex;
-
-pub async fn handle_on_warning_received(
-    bot: Arc<Mutex<Option<Bot>>>,
-    warning: Warning,
-) -> Result<(), String> {
-    // Let's do this in separate scope to release the lock as soon as possible
-    {
-        let mut bot_lock = bot
-            .try_lock()
-            .map_err(|_| "Failed to lock bot, it is already in use")?;
-
-        match bot_lock.as_mut() {
-            Some(bot) => Ok(bot.on_warning_received(warning)),
-            None => Err("Bot not initialized".to_string()),
-        }
-    }
-    .map_err(|e| format!("Failed to get bot response, {}", e))?;
-
-    Ok(())
-}
+use crate::bot_trait::BotTrait;
+use crate::ws_client::client_error::ClientError;
+use crate::ws_client::client_state::ClientState;
+use crate::ws_client::packet::warning::Warning;
+
+pub async fn handle_on_warning_received<B: BotTrait>(
+    state: &ClientState<B>,
+    warning: Warning,
+) -> Result<(), ClientError> {
+    // The bot is making its move on a blocking thread, it gets the warning once it is done
+    let Ok(mut bot_lock) = state.bot.try_lock() else {
+        state.queue_warning(warning);
+        return Ok(());
+    };
+
+    match bot_lock.as_mut() {
+        Some(bot) => {
+            state.deliver_pending_warnings(bot);
+            bot.on_warning_received(warning);
+            Ok(())
+        }
+        None => Err(ClientError::BotNotInitialized),
+    }
+}
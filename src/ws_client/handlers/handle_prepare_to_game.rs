@@ -1,42 +1,47 @@
-use crate::bot::Bot;
 use crate::bot_trait::BotTrait;
+use crate::ws_client::client_error::ClientError;
+use crate::ws_client::client_state::ClientState;
+use crate::ws_client::compatibility::warn_if_unsupported_version;
 use crate::ws_client::packet::packet::Packet;
 use crate::ws_client::packet::packets::lobby_data::LobbyData;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info};
 
-pub async fn handle_prepare_to_game(
+pub async fn handle_prepare_to_game<B: BotTrait>(
     tx: tokio::sync::mpsc::Sender<Message>,
-    bot: Arc<Mutex<Option<Bot>>>,
+    state: Arc<ClientState<B>>,
     lobby_data: LobbyData,
-) -> Result<(), String> {
-    let mut bot_guard = bot.lock().await;
+) -> Result<(), ClientError> {
+    let mut bot_guard = state.bot.lock().await;
 
     match bot_guard.as_mut() {
         Some(bot) => bot.on_lobby_data_changed(lobby_data),
         None => {
             let sandbox_mode = lobby_data.server_settings.sandbox_mode;
 
-            *bot_guard = Some(Bot::on_joining_lobby(lobby_data));
-            println!("[System] 🤖 Created bot");
+            warn_if_unsupported_version(&lobby_data.server_settings);
+
+            *bot_guard = Some(B::on_joining_lobby(lobby_data));
+            state.notify_bot_created();
+            info!("🤖 Created bot");
 
             if sandbox_mode {
-                println!("[System] 🛠️ Sandbox mode enabled");
+                info!("🛠️ Sandbox mode enabled");
 
                 match tx
-                    .send(Message::Text(Packet::ReadyToReceiveGameState.into()))
+                    .send(state.message(Packet::ReadyToReceiveGameState))
                     .await
                 {
-                    Ok(_) => println!("[System] 🎳 Ready to receive game state sent"),
-                    Err(e) => {
-                        eprintln!("[System] 🚨 Error sending ReadyToReceiveGameState -> {}", e)
-                    }
+                    Ok(_) => debug!("🎳 Ready to receive game state sent"),
+                    Err(e) => error!("🚨 Error sending ReadyToReceiveGameState -> {}", e),
                 }
 
-                tx.send(Message::Text(Packet::GameStatusRequest.into()))
+                tx.send(state.message(Packet::GameStatusRequest))
                     .await
-                    .map_err(|e| format!("🚨 Error sending GameStatusRequest -> {}", e))?;
+                    .map_err(|_| ClientError::Send {
+                        packet: "GameStatusRequest",
+                    })?;
             }
         }
     }
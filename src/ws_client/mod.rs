@@ -1,4 +1,13 @@
+pub mod client_error;
+pub mod client_state;
+mod compatibility;
 mod handlers;
 pub mod packet;
+pub mod reconnect_config;
+pub mod recorder;
+pub mod response_config;
+pub mod server_url;
+pub mod spectator_client;
+pub mod tls_config;
 pub mod writer_task;
 pub mod ws_client;
@@ -0,0 +1,106 @@
+use serde::de::value::MapAccessDeserializer;
+use serde::de::{DeserializeSeed, Error, IntoDeserializer, MapAccess};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use serde_json::Value;
+
+/// An adjacently tagged enum of the protocol, like `{"type": "...", "payload": {...}}`,
+/// that does not fail on types added by newer versions of the server.
+pub trait AdjacentlyTagged: Sized {
+    /// The types of the variants known to this version of the wrapper.
+    /// Variants missing from the list are deserialized as unknown.
+    const TAGS: &'static [&'static str];
+
+    /// Deserializes a variant with one of the known types, using the derived implementation.
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>;
+
+    /// Creates the variant keeping the type and payload of an unknown variant.
+    fn unknown(tag: String, payload: Value) -> Self;
+}
+
+/// The type of a variant, with its payload left unparsed until the type is known.
+#[derive(Deserialize)]
+struct Tagged {
+    #[serde(rename = "type")]
+    tag: String,
+    #[serde(default)]
+    payload: Option<Box<RawValue>>,
+}
+
+/// Deserializes an adjacently tagged enum, passing variants of unknown types
+/// to [`AdjacentlyTagged::unknown`] with their type and payload.
+pub fn deserialize<'de, T: AdjacentlyTagged, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let Tagged { tag, payload } = Tagged::deserialize(deserializer)?;
+
+    if !T::TAGS.contains(&tag.as_str()) {
+        let payload = match payload {
+            Some(payload) => serde_json::from_str(payload.get()).map_err(D::Error::custom)?,
+            None => Value::Null,
+        };
+        return Ok(T::unknown(tag, payload));
+    }
+
+    let variant = KnownVariant {
+        tag: Some(tag),
+        payload: payload.as_deref(),
+    };
+    T::deserialize_known(MapAccessDeserializer::new(variant)).map_err(D::Error::custom)
+}
+
+/// Serializes the type and payload of a variant unknown to this version of the wrapper,
+/// as they were received.
+pub fn serialize_unknown<S: Serializer>(
+    tag: &str,
+    payload: &Value,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    #[derive(Serialize)]
+    struct Unknown<'a> {
+        #[serde(rename = "type")]
+        tag: &'a str,
+        #[serde(skip_serializing_if = "Value::is_null")]
+        payload: &'a Value,
+    }
+
+    Unknown { tag, payload }.serialize(serializer)
+}
+
+/// Presents the already read type and the raw payload to the derived implementation
+/// as the object they came from.
+struct KnownVariant<'a> {
+    tag: Option<String>,
+    payload: Option<&'a RawValue>,
+}
+
+impl<'a> MapAccess<'a> for KnownVariant<'a> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'a>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let key = match (&self.tag, &self.payload) {
+            (Some(_), _) => "type",
+            (None, Some(_)) => "payload",
+            (None, None) => return Ok(None),
+        };
+
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'a>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        if let Some(tag) = self.tag.take() {
+            return seed.deserialize(tag.into_deserializer());
+        }
+
+        match self.payload.take() {
+            Some(payload) => seed.deserialize(payload.into_deserializer()),
+            None => Err(Self::Error::custom("value requested before key")),
+        }
+    }
+}
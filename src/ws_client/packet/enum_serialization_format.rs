@@ -0,0 +1,194 @@
+use derive_more::derive::{Display, IsVariant};
+use serde::de::{self, Unexpected, Visitor};
+use serde::{Deserializer, Serializer};
+use std::cell::Cell;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// How enums are encoded in the packets exchanged with the server.
+///
+/// The client chooses the format when connecting, with the `enumSerializationFormat`
+/// query parameter. Integers make the game states of large maps noticeably smaller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Display, IsVariant, clap::ValueEnum)]
+pub enum EnumSerializationFormat {
+    /// The camelCase name of the variant, e.g. `"up"`.
+    #[default]
+    #[display("string")]
+    String,
+
+    /// The index of the variant, e.g. `0` for `Direction::Up`.
+    #[display("int")]
+    Int,
+}
+
+thread_local! {
+    static FORMAT: Cell<EnumSerializationFormat> = const { Cell::new(EnumSerializationFormat::String) };
+}
+
+impl EnumSerializationFormat {
+    /// Parses the value of the `enumSerializationFormat` query parameter.
+    pub fn from_query(value: &str) -> Option<Self> {
+        match value {
+            "string" => Some(EnumSerializationFormat::String),
+            "int" => Some(EnumSerializationFormat::Int),
+            _ => None,
+        }
+    }
+
+    /// Runs `f` with enums serialized in this format on the current thread.
+    pub fn scope<T>(self, f: impl FnOnce() -> T) -> T {
+        struct Restore(EnumSerializationFormat);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                FORMAT.set(self.0);
+            }
+        }
+
+        let _restore = Restore(FORMAT.replace(self));
+        f()
+    }
+
+    /// Returns the format enums are serialized in on the current thread.
+    pub fn current() -> Self {
+        FORMAT.get()
+    }
+}
+
+/// An enum of the protocol, sent either as the name or as the index of its variant.
+///
+/// Implemented with [`protocol_enum!`], which also implements `Serialize` and `Deserialize`.
+/// Serialization follows [`EnumSerializationFormat::current`],
+/// deserialization accepts both forms.
+pub trait ProtocolEnum: Sized + Clone + PartialEq + 'static {
+    /// Every variant with its name, in the order of their indices.
+    const VARIANTS: &'static [(Self, &'static str)];
+
+    /// The variant values added in newer versions of the server are deserialized as, if any.
+    const FALLBACK: Option<Self> = None;
+}
+
+pub fn serialize<T: ProtocolEnum, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let index = T::VARIANTS
+        .iter()
+        .position(|(variant, _)| variant == value)
+        .expect("Every variant is listed");
+
+    match EnumSerializationFormat::current() {
+        EnumSerializationFormat::String => serializer.serialize_str(T::VARIANTS[index].1),
+        EnumSerializationFormat::Int => serializer.serialize_u64(index as u64),
+    }
+}
+
+pub fn deserialize<'de, T: ProtocolEnum, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_any(ProtocolEnumVisitor(PhantomData))
+}
+
+struct ProtocolEnumVisitor<T>(PhantomData<T>);
+
+impl<T: ProtocolEnum> Visitor<'_> for ProtocolEnumVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<_> = T::VARIANTS.iter().map(|(_, name)| *name).collect();
+        write!(
+            formatter,
+            "one of `{}` or an index below {}",
+            names.join("`, `"),
+            names.len()
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        T::VARIANTS
+            .iter()
+            .find(|(_, name)| *name == value)
+            .map(|(variant, _)| variant.clone())
+            .or(T::FALLBACK)
+            .ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<T, E> {
+        T::VARIANTS
+            .get(value as usize)
+            .map(|(variant, _)| variant.clone())
+            .or(T::FALLBACK)
+            .ok_or_else(|| E::invalid_value(Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<T, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+}
+
+/// Implements [`ProtocolEnum`], `Serialize` and `Deserialize` for an enum of the protocol,
+/// listing its variants with their names in the order of the indices used by the server.
+///
+/// With `else Variant`, values this version of the wrapper does not know are deserialized
+/// as `Variant` instead of failing.
+macro_rules! protocol_enum {
+    ($name:ident { $($variant:ident => $text:literal),+ $(,)? }) => {
+        $crate::ws_client::packet::enum_serialization_format::protocol_enum!(
+            @impl $name { $($variant => $text),+ } None
+        );
+    };
+    ($name:ident { $($variant:ident => $text:literal),+ $(,)? } else $fallback:ident) => {
+        $crate::ws_client::packet::enum_serialization_format::protocol_enum!(
+            @impl $name { $($variant => $text),+ } Some($name::$fallback)
+        );
+    };
+    (@impl $name:ident { $($variant:ident => $text:literal),+ } $fallback:expr) => {
+        impl $crate::ws_client::packet::enum_serialization_format::ProtocolEnum for $name {
+            const VARIANTS: &'static [(Self, &'static str)] = &[$(($name::$variant, $text)),+];
+            const FALLBACK: Option<Self> = $fallback;
+        }
+
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                $crate::ws_client::packet::enum_serialization_format::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                $crate::ws_client::packet::enum_serialization_format::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+pub(crate) use protocol_enum;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+
+    #[test]
+    fn test_serializes_in_scoped_format() {
+        let int = EnumSerializationFormat::Int.scope(|| serde_json::to_string(&Direction::Left));
+        assert_eq!(int.unwrap(), "3");
+        assert_eq!(serde_json::to_string(&Direction::Left).unwrap(), "\"left\"");
+    }
+
+    #[test]
+    fn test_deserializes_both_formats() {
+        assert_eq!(
+            serde_json::from_str::<Direction>("2").unwrap(),
+            Direction::Down
+        );
+        assert_eq!(
+            serde_json::from_str::<Direction>("\"down\"").unwrap(),
+            Direction::Down
+        );
+        assert!(serde_json::from_str::<Direction>("-1").is_err());
+    }
+}
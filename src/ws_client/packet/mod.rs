@@ -1,4 +1,6 @@
+pub mod adjacently_tagged;
 pub mod empty_payload;
+pub mod enum_serialization_format;
 pub mod packet;
 pub mod packets;
-pub mod warning;
\ No newline at end of file
+pub mod warning;
@@ -4,11 +4,18 @@ use super::packets::bot_response::move_direction::MoveDirection;
 use super::packets::game_state::raw_game_state::RawGameState;
 use super::packets::lobby_data::LobbyData;
 use super::packets::{bot_response::rotation::Rotation, game_end::game_end::GameEnd};
+use crate::ws_client::packet::adjacently_tagged::{self, AdjacentlyTagged};
 use crate::ws_client::packet::empty_payload;
-use serde::{Deserialize, Serialize};
+use crate::ws_client::packet::enum_serialization_format::EnumSerializationFormat;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", tag = "type", content = "payload")]
+#[serde(
+    remote = "Self",
+    rename_all = "camelCase",
+    tag = "type",
+    content = "payload"
+)]
 pub enum Packet {
     #[serde(with = "empty_payload")]
     Ping,
@@ -99,11 +106,85 @@ pub enum Packet {
     InvalidPayloadError {
         message: String,
     },
+
+    /// A packet of a type added in a newer version of the server, kept as it was received.
+    #[serde(skip)]
+    Unknown {
+        packet_type: String,
+        payload: serde_json::Value,
+    },
+}
+
+impl Serialize for Packet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Packet::Unknown {
+                packet_type,
+                payload,
+            } => adjacently_tagged::serialize_unknown(packet_type, payload, serializer),
+            _ => Packet::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Packet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        adjacently_tagged::deserialize(deserializer)
+    }
+}
+
+impl AdjacentlyTagged for Packet {
+    const TAGS: &'static [&'static str] = &[
+        "ping",
+        "pong",
+        "connectionAccepted",
+        "connectionRejected",
+        "lobbyData",
+        "lobbyDataRequest",
+        "gameStarting",
+        "gameNotStarted",
+        "gameInProgress",
+        "gameStatusRequest",
+        "readyToReceiveGameState",
+        "gameStarted",
+        "gameState",
+        "movement",
+        "rotation",
+        "abilityUse",
+        "pass",
+        "gameEnded",
+        "playerAlreadyMadeActionWarning",
+        "missingGameStateIdWarning",
+        "slowResponseWarning",
+        "actionIgnoredDueToDeadWarning",
+        "customWarning",
+        "invalidPacketTypeError",
+        "invalidPacketUsageError",
+        "invalidPayloadError",
+    ];
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Packet::deserialize(deserializer)
+    }
+
+    fn unknown(packet_type: String, payload: serde_json::Value) -> Self {
+        Packet::Unknown {
+            packet_type,
+            payload,
+        }
+    }
+}
+
+impl Packet {
+    /// Serializes the packet, encoding its enums in the given format.
+    pub fn to_json(&self, format: EnumSerializationFormat) -> String {
+        format.scope(|| serde_json::to_string(self).unwrap())
+    }
 }
 
 impl From<Packet> for String {
     fn from(packet: Packet) -> Self {
-        serde_json::to_string(&packet).unwrap()
+        packet.to_json(EnumSerializationFormat::String)
     }
 }
 
@@ -135,13 +216,65 @@ impl BotResponse {
             BotResponse::Pass => Packet::Pass { game_state_id },
         }
     }
+
+    /// Extracts the game state ID and the response from a packet sent by a bot.
+    ///
+    /// Returns `None` for packets that are not bot responses.
+    pub fn from_packet(packet: Packet) -> Option<(String, BotResponse)> {
+        match packet {
+            Packet::Movement {
+                game_state_id,
+                direction,
+            } => Some((game_state_id, BotResponse::Movement { direction })),
+            Packet::Rotation {
+                game_state_id,
+                tank_rotation,
+                turret_rotation,
+            } => Some((
+                game_state_id,
+                BotResponse::Rotation {
+                    tank_rotation,
+                    turret_rotation,
+                },
+            )),
+            Packet::AbilityUse {
+                game_state_id,
+                ability_type,
+            } => Some((game_state_id, BotResponse::AbilityUse { ability_type })),
+            Packet::Pass { game_state_id } => Some((game_state_id, BotResponse::Pass)),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ws_client::packet::packets::game_end::game_end_player::GameEndPlayer;
+    use crate::ws_client::packet::packets::game_state::player::Player;
+    use crate::ws_client::packet::packets::game_state::raw_game_state::map::RawMap;
+    use crate::ws_client::packet::packets::game_state::tile::bullet::{Bullet, BulletType};
+    use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+    use crate::ws_client::packet::packets::game_state::tile::item::{Item, ItemType};
+    use crate::ws_client::packet::packets::game_state::tile::laser::{Laser, LaserOrientation};
+    use crate::ws_client::packet::packets::game_state::tile::mine::Mine;
+    use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+    use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+    use crate::ws_client::packet::packets::game_state::zone::{
+        BeingCapturedStatus, BeingContestedStatus, BeingRetakenStatus, CapturedStatus, Zone,
+        ZoneStatus,
+    };
+    use crate::ws_client::packet::packets::lobby_data::lobby_player::LobbyPlayer;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
     use serde_json::{json, Value};
 
+    /// Checks that the packet serializes to exactly the golden JSON and deserializes back from it.
+    fn assert_conforms(packet: Packet, golden: Value) {
+        assert_eq!(serde_json::to_value(&packet).unwrap(), golden);
+        assert_eq!(serde_json::from_value::<Packet>(golden).unwrap(), packet);
+    }
+
     #[test]
     fn test_packet_type_ping() {
         let packet = Packet::Ping;
@@ -158,4 +291,668 @@ mod tests {
         let deserialized: Packet = serde_json::from_value(expected_value).unwrap();
         assert_eq!(deserialized, packet);
     }
+
+    #[test]
+    fn test_packets_without_payload() {
+        let packets = [
+            (Packet::Ping, "ping"),
+            (Packet::Pong, "pong"),
+            (Packet::ConnectionAccepted, "connectionAccepted"),
+            (Packet::LobbyDataRequest, "lobbyDataRequest"),
+            (Packet::GameStarting, "gameStarting"),
+            (Packet::GameNotStarted, "gameNotStarted"),
+            (Packet::GameInProgress, "gameInProgress"),
+            (Packet::GameStatusRequest, "gameStatusRequest"),
+            (Packet::ReadyToReceiveGameState, "readyToReceiveGameState"),
+            (Packet::GameStarted, "gameStarted"),
+            (
+                Packet::PlayerAlreadyMadeActionWarning,
+                "playerAlreadyMadeActionWarning",
+            ),
+            (
+                Packet::MissingGameStateIdWarning,
+                "missingGameStateIdWarning",
+            ),
+            (Packet::SlowResponseWarning, "slowResponseWarning"),
+            (
+                Packet::ActionIgnoredDueToDeadWarning,
+                "actionIgnoredDueToDeadWarning",
+            ),
+            (Packet::InvalidPacketTypeError, "invalidPacketTypeError"),
+            (Packet::InvalidPacketUsageError, "invalidPacketUsageError"),
+        ];
+
+        for (packet, packet_type) in packets {
+            assert_conforms(packet, json!({"type": packet_type, "payload": {}}));
+        }
+    }
+
+    #[test]
+    fn test_packets_with_message() {
+        assert_conforms(
+            Packet::ConnectionRejected {
+                reason: "Invalid join code".to_string(),
+            },
+            json!({"type": "connectionRejected", "payload": {"reason": "Invalid join code"}}),
+        );
+        assert_conforms(
+            Packet::CustomWarning {
+                message: "Slow down".to_string(),
+            },
+            json!({"type": "customWarning", "payload": {"message": "Slow down"}}),
+        );
+        assert_conforms(
+            Packet::InvalidPayloadError {
+                message: "Missing gameStateId".to_string(),
+            },
+            json!({"type": "invalidPayloadError", "payload": {"message": "Missing gameStateId"}}),
+        );
+    }
+
+    #[test]
+    fn test_lobby_data() {
+        let lobby_data = LobbyData::new(
+            "player-1".to_string(),
+            vec![
+                LobbyPlayer::new("player-1".to_string(), "First".to_string(), 4294901760),
+                LobbyPlayer::new("player-2".to_string(), "Second".to_string(), 4278255360),
+            ],
+            ServerSettings::new(
+                24,
+                2,
+                1234,
+                100,
+                false,
+                false,
+                Some(3000),
+                Some("Final".to_string()),
+                "1.0.0".to_string(),
+            ),
+        );
+
+        assert_conforms(
+            Packet::LobbyData(lobby_data),
+            json!({
+                "type": "lobbyData",
+                "payload": {
+                    "playerId": "player-1",
+                    "players": [
+                        {"id": "player-1", "nickname": "First", "color": 4294901760u64},
+                        {"id": "player-2", "nickname": "Second", "color": 4278255360u64}
+                    ],
+                    "serverSettings": {
+                        "gridDimension": 24,
+                        "numberOfPlayers": 2,
+                        "seed": 1234,
+                        "broadcastInterval": 100,
+                        "eagerBroadcast": false,
+                        "sandboxMode": false,
+                        "tick": 3000,
+                        "matchName": "Final",
+                        "version": "1.0.0"
+                    }
+                }
+            }),
+        );
+    }
+
+    #[test]
+    fn test_lobby_data_in_sandbox_mode() {
+        let settings = ServerSettings::new(24, 4, 1, 100, true, true, None, None, "1.0.0".into());
+        let lobby_data = LobbyData::new("player-1".to_string(), vec![], settings);
+        let golden = json!({
+            "type": "lobbyData",
+            "payload": {
+                "playerId": "player-1",
+                "players": [],
+                "serverSettings": {
+                    "gridDimension": 24,
+                    "numberOfPlayers": 4,
+                    "seed": 1,
+                    "broadcastInterval": 100,
+                    "eagerBroadcast": true,
+                    "sandboxMode": true,
+                    "tick": null,
+                    "matchName": null,
+                    "version": "1.0.0"
+                }
+            }
+        });
+        assert_conforms(Packet::LobbyData(lobby_data.clone()), golden.clone());
+
+        // Absent optional fields may also be left out altogether
+        let mut without_optional = golden;
+        let settings = &mut without_optional["payload"]["serverSettings"];
+        settings.as_object_mut().unwrap().remove("tick");
+        settings.as_object_mut().unwrap().remove("matchName");
+        let deserialized: Packet = serde_json::from_value(without_optional).unwrap();
+        assert_eq!(deserialized, Packet::LobbyData(lobby_data));
+    }
+
+    #[test]
+    fn test_lobby_data_for_spectator() {
+        let json = r#"{
+            "type": "lobbyData",
+            "payload": {
+                "playerId": null,
+                "players": [],
+                "serverSettings": {
+                    "gridDimension": 24,
+                    "numberOfPlayers": 4,
+                    "seed": 1,
+                    "broadcastInterval": 100,
+                    "eagerBroadcast": true,
+                    "sandboxMode": false,
+                    "version": "1.0.0"
+                }
+            }
+        }"#;
+
+        let Packet::LobbyData(lobby_data) = serde_json::from_str(json).unwrap() else {
+            panic!("Expected lobby data");
+        };
+        assert_eq!(lobby_data.player_id, "");
+    }
+
+    fn own_tank() -> Tank {
+        Tank::new(
+            Direction::Up,
+            Some(80),
+            "player-1".to_string(),
+            Turret::new(Some(3), Some(10), Direction::Left),
+            Some(ItemType::DoubleBullet),
+        )
+    }
+
+    fn enemy_tank() -> Tank {
+        Tank::new(
+            Direction::Down,
+            None,
+            "player-2".to_string(),
+            Turret::new(None, None, Direction::Right),
+            None,
+        )
+    }
+
+    fn raw_game_state() -> RawGameState {
+        let tiles = vec![
+            vec![
+                vec![TileEntity::Wall],
+                vec![TileEntity::Tank(own_tank())],
+                vec![TileEntity::Bullet(Bullet::new(
+                    Direction::Right,
+                    1,
+                    2.0,
+                    BulletType::Basic,
+                ))],
+            ],
+            vec![
+                vec![TileEntity::Tank(enemy_tank())],
+                vec![
+                    TileEntity::Bullet(Bullet::new(Direction::Left, 2, 1.5, BulletType::Double)),
+                    TileEntity::Laser(Laser::new(3, LaserOrientation::Horizontal)),
+                ],
+                vec![TileEntity::Laser(Laser::new(4, LaserOrientation::Vertical))],
+            ],
+            vec![
+                vec![TileEntity::Mine(Mine::new(5, None))],
+                vec![TileEntity::Mine(Mine::new(6, Some(4)))],
+                vec![
+                    TileEntity::Item(Item::new(ItemType::Unknown)),
+                    TileEntity::Item(Item::new(ItemType::Laser)),
+                    TileEntity::Item(Item::new(ItemType::DoubleBullet)),
+                    TileEntity::Item(Item::new(ItemType::Radar)),
+                    TileEntity::Item(Item::new(ItemType::Mine)),
+                ],
+            ],
+        ];
+
+        let zones = vec![
+            Zone::new(65, 0, 0, 1, 1, ZoneStatus::Neutral),
+            Zone::new(
+                66,
+                1,
+                0,
+                1,
+                1,
+                ZoneStatus::BeingCaptured(BeingCapturedStatus {
+                    remaining_ticks: 12,
+                    player_id: "player-1".to_string(),
+                }),
+            ),
+            Zone::new(
+                67,
+                2,
+                0,
+                1,
+                1,
+                ZoneStatus::Captured(CapturedStatus {
+                    player_id: "player-2".to_string(),
+                }),
+            ),
+            Zone::new(
+                68,
+                0,
+                1,
+                1,
+                1,
+                ZoneStatus::BeingContested(BeingContestedStatus {
+                    captured_by_id: Some("player-1".to_string()),
+                }),
+            ),
+            Zone::new(
+                69,
+                1,
+                1,
+                1,
+                1,
+                ZoneStatus::BeingContested(BeingContestedStatus {
+                    captured_by_id: None,
+                }),
+            ),
+            Zone::new(
+                70,
+                2,
+                1,
+                1,
+                1,
+                ZoneStatus::BeingRetaken(BeingRetakenStatus {
+                    remaining_ticks: 7,
+                    captured_by_id: "player-2".to_string(),
+                    retaken_by_id: "player-1".to_string(),
+                }),
+            ),
+        ];
+
+        let players = vec![
+            Player::new(
+                "player-1".to_string(),
+                "First".to_string(),
+                4294901760,
+                15,
+                Some(120),
+                None,
+                Some(false),
+            ),
+            Player::new(
+                "player-2".to_string(),
+                "Second".to_string(),
+                4278255360,
+                30,
+                None,
+                Some(5),
+                None,
+            ),
+        ];
+
+        let visibility = vec!["110".to_string(), "011".to_string(), "000".to_string()];
+        RawGameState::new(
+            "state-1".to_string(),
+            42,
+            players,
+            RawMap::new(tiles, zones, visibility),
+        )
+    }
+
+    fn raw_game_state_json() -> Value {
+        json!({
+            "type": "gameState",
+            "payload": {
+                "id": "state-1",
+                "tick": 42,
+                "players": [
+                    {
+                        "id": "player-1",
+                        "nickname": "First",
+                        "color": 4294901760u64,
+                        "ping": 15,
+                        "score": 120,
+                        "ticksToRegen": null,
+                        "isUsingRadar": false
+                    },
+                    {
+                        "id": "player-2",
+                        "nickname": "Second",
+                        "color": 4278255360u64,
+                        "ping": 30,
+                        "score": null,
+                        "ticksToRegen": 5,
+                        "isUsingRadar": null
+                    }
+                ],
+                "map": {
+                    "tiles": [
+                        [
+                            [{"type": "wall"}],
+                            [{
+                                "type": "tank",
+                                "payload": {
+                                    "direction": "up",
+                                    "health": 80,
+                                    "ownerId": "player-1",
+                                    "turret": {
+                                        "bulletCount": 3,
+                                        "ticksToRegenBullet": 10,
+                                        "direction": "left"
+                                    },
+                                    "secondaryItem": "doubleBullet"
+                                }
+                            }],
+                            [{
+                                "type": "bullet",
+                                "payload": {"direction": "right", "id": 1, "speed": 2.0, "type": "basic"}
+                            }]
+                        ],
+                        [
+                            [{
+                                "type": "tank",
+                                "payload": {
+                                    "direction": "down",
+                                    "health": null,
+                                    "ownerId": "player-2",
+                                    "turret": {
+                                        "bulletCount": null,
+                                        "ticksToRegenBullet": null,
+                                        "direction": "right"
+                                    },
+                                    "secondaryItem": null
+                                }
+                            }],
+                            [
+                                {
+                                    "type": "bullet",
+                                    "payload": {"direction": "left", "id": 2, "speed": 1.5, "type": "double"}
+                                },
+                                {"type": "laser", "payload": {"id": 3, "orientation": "horizontal"}}
+                            ],
+                            [{"type": "laser", "payload": {"id": 4, "orientation": "vertical"}}]
+                        ],
+                        [
+                            [{"type": "mine", "payload": {"id": 5, "explosionRemainingTicks": null}}],
+                            [{"type": "mine", "payload": {"id": 6, "explosionRemainingTicks": 4}}],
+                            [
+                                {"type": "item", "payload": {"type": "unknown"}},
+                                {"type": "item", "payload": {"type": "laser"}},
+                                {"type": "item", "payload": {"type": "doubleBullet"}},
+                                {"type": "item", "payload": {"type": "radar"}},
+                                {"type": "item", "payload": {"type": "mine"}}
+                            ]
+                        ]
+                    ],
+                    "zones": [
+                        {
+                            "index": 65, "x": 0, "y": 0, "width": 1, "height": 1,
+                            "status": {"type": "neutral"}
+                        },
+                        {
+                            "index": 66, "x": 1, "y": 0, "width": 1, "height": 1,
+                            "status": {"type": "beingCaptured", "remainingTicks": 12, "playerId": "player-1"}
+                        },
+                        {
+                            "index": 67, "x": 2, "y": 0, "width": 1, "height": 1,
+                            "status": {"type": "captured", "playerId": "player-2"}
+                        },
+                        {
+                            "index": 68, "x": 0, "y": 1, "width": 1, "height": 1,
+                            "status": {"type": "beingContested", "capturedById": "player-1"}
+                        },
+                        {
+                            "index": 69, "x": 1, "y": 1, "width": 1, "height": 1,
+                            "status": {"type": "beingContested", "capturedById": null}
+                        },
+                        {
+                            "index": 70, "x": 2, "y": 1, "width": 1, "height": 1,
+                            "status": {
+                                "type": "beingRetaken",
+                                "remainingTicks": 7,
+                                "capturedById": "player-2",
+                                "retakenById": "player-1"
+                            }
+                        }
+                    ],
+                    "visibility": ["110", "011", "000"]
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn test_game_state() {
+        assert_conforms(Packet::GameState(raw_game_state()), raw_game_state_json());
+    }
+
+    #[test]
+    fn test_game_state_without_optional_fields() {
+        let mut golden = raw_game_state_json();
+        let payload = &mut golden["payload"];
+
+        // Fields hidden from other players may be left out instead of being null
+        for player in payload["players"].as_array_mut().unwrap() {
+            let player = player.as_object_mut().unwrap();
+            player.retain(|_, value| !value.is_null());
+        }
+        let enemy_tank = &mut payload["map"]["tiles"][1][0][0]["payload"];
+        enemy_tank.as_object_mut().unwrap().remove("health");
+        enemy_tank.as_object_mut().unwrap().remove("secondaryItem");
+        let turret = enemy_tank["turret"].as_object_mut().unwrap();
+        turret.remove("bulletCount");
+        turret.remove("ticksToRegenBullet");
+        let mine = &mut payload["map"]["tiles"][2][0][0]["payload"];
+        mine.as_object_mut()
+            .unwrap()
+            .remove("explosionRemainingTicks");
+        let contested = &mut payload["map"]["zones"][4]["status"];
+        contested.as_object_mut().unwrap().remove("capturedById");
+
+        let deserialized: Packet = serde_json::from_value(golden).unwrap();
+        assert_eq!(deserialized, Packet::GameState(raw_game_state()));
+    }
+
+    #[test]
+    fn test_game_state_in_int_format() {
+        let packet = Packet::GameState(raw_game_state());
+        let golden: Value =
+            serde_json::from_str(&packet.to_json(EnumSerializationFormat::Int)).unwrap();
+        let tiles = &golden["payload"]["map"]["tiles"];
+        let zones = &golden["payload"]["map"]["zones"];
+
+        // Tags of packets and tile entities stay strings
+        assert_eq!(golden["type"], "gameState");
+        assert_eq!(tiles[0][1][0]["type"], "tank");
+
+        let own_tank = &tiles[0][1][0]["payload"];
+        assert_eq!(own_tank["direction"], 0);
+        assert_eq!(own_tank["turret"]["direction"], 3);
+        assert_eq!(own_tank["secondaryItem"], 2);
+        assert_eq!(tiles[0][2][0]["payload"]["type"], 0);
+        assert_eq!(tiles[1][1][0]["payload"]["type"], 1);
+        assert_eq!(tiles[1][1][1]["payload"]["orientation"], 0);
+        assert_eq!(tiles[1][2][0]["payload"]["orientation"], 1);
+        for (index, item) in tiles[2][2].as_array().unwrap().iter().enumerate() {
+            assert_eq!(item["payload"]["type"], index);
+        }
+        for (index, status_type) in [0, 1, 2, 3, 3, 4].into_iter().enumerate() {
+            assert_eq!(zones[index]["status"]["type"], status_type);
+        }
+        assert_eq!(zones[5]["status"]["retakenById"], "player-1");
+
+        assert_eq!(serde_json::from_value::<Packet>(golden).unwrap(), packet);
+    }
+
+    #[test]
+    fn test_bot_responses_in_int_format() {
+        let rotation = Packet::Rotation {
+            game_state_id: "state-1".to_string(),
+            tank_rotation: None,
+            turret_rotation: Some(Rotation::Right),
+        };
+        let ability_use = Packet::AbilityUse {
+            game_state_id: "state-1".to_string(),
+            ability_type: AbilityType::DropMine,
+        };
+        let movement = Packet::Movement {
+            game_state_id: "state-1".to_string(),
+            direction: MoveDirection::Backward,
+        };
+
+        let goldens = [
+            (
+                rotation,
+                json!({"type": "rotation", "payload": {"gameStateId": "state-1", "tankRotation": null, "turretRotation": 1}}),
+            ),
+            (
+                ability_use,
+                json!({"type": "abilityUse", "payload": {"gameStateId": "state-1", "abilityType": 4}}),
+            ),
+            (
+                movement,
+                json!({"type": "movement", "payload": {"gameStateId": "state-1", "direction": 1}}),
+            ),
+        ];
+        for (packet, golden) in goldens {
+            let serialized = packet.to_json(EnumSerializationFormat::Int);
+            assert_eq!(serde_json::from_str::<Value>(&serialized).unwrap(), golden);
+            assert_eq!(serde_json::from_value::<Packet>(golden).unwrap(), packet);
+        }
+    }
+
+    #[test]
+    fn test_bot_responses() {
+        assert_conforms(
+            Packet::Movement {
+                game_state_id: "state-1".to_string(),
+                direction: MoveDirection::Forward,
+            },
+            json!({"type": "movement", "payload": {"gameStateId": "state-1", "direction": "forward"}}),
+        );
+        assert_conforms(
+            Packet::Movement {
+                game_state_id: "state-1".to_string(),
+                direction: MoveDirection::Backward,
+            },
+            json!({"type": "movement", "payload": {"gameStateId": "state-1", "direction": "backward"}}),
+        );
+        assert_conforms(
+            Packet::Pass {
+                game_state_id: "state-1".to_string(),
+            },
+            json!({"type": "pass", "payload": {"gameStateId": "state-1"}}),
+        );
+
+        let abilities = [
+            (AbilityType::FireBullet, "fireBullet"),
+            (AbilityType::UseLaser, "useLaser"),
+            (AbilityType::FireDoubleBullet, "fireDoubleBullet"),
+            (AbilityType::UseRadar, "useRadar"),
+            (AbilityType::DropMine, "dropMine"),
+        ];
+        for (ability_type, name) in abilities {
+            assert_conforms(
+                Packet::AbilityUse {
+                    game_state_id: "state-1".to_string(),
+                    ability_type,
+                },
+                json!({"type": "abilityUse", "payload": {"gameStateId": "state-1", "abilityType": name}}),
+            );
+        }
+    }
+
+    #[test]
+    fn test_rotation() {
+        assert_conforms(
+            Packet::Rotation {
+                game_state_id: "state-1".to_string(),
+                tank_rotation: Some(Rotation::Left),
+                turret_rotation: Some(Rotation::Right),
+            },
+            json!({
+                "type": "rotation",
+                "payload": {"gameStateId": "state-1", "tankRotation": "left", "turretRotation": "right"}
+            }),
+        );
+
+        // A side that does not rotate is sent as null
+        assert_conforms(
+            Packet::Rotation {
+                game_state_id: "state-1".to_string(),
+                tank_rotation: None,
+                turret_rotation: Some(Rotation::Left),
+            },
+            json!({
+                "type": "rotation",
+                "payload": {"gameStateId": "state-1", "tankRotation": null, "turretRotation": "left"}
+            }),
+        );
+        assert_conforms(
+            Packet::Rotation {
+                game_state_id: "state-1".to_string(),
+                tank_rotation: Some(Rotation::Right),
+                turret_rotation: None,
+            },
+            json!({
+                "type": "rotation",
+                "payload": {"gameStateId": "state-1", "tankRotation": "right", "turretRotation": null}
+            }),
+        );
+    }
+
+    #[test]
+    fn test_game_ended() {
+        let game_end = GameEnd::new(vec![
+            GameEndPlayer::new(
+                "player-1".to_string(),
+                "First".to_string(),
+                4294901760,
+                250,
+                3,
+            ),
+            GameEndPlayer::new(
+                "player-2".to_string(),
+                "Second".to_string(),
+                4278255360,
+                90,
+                0,
+            ),
+        ]);
+
+        assert_conforms(
+            Packet::GameEnded(game_end),
+            json!({
+                "type": "gameEnded",
+                "payload": {
+                    "players": [
+                        {"id": "player-1", "nickname": "First", "color": 4294901760u64, "score": 250, "kills": 3},
+                        {"id": "player-2", "nickname": "Second", "color": 4278255360u64, "score": 90, "kills": 0}
+                    ]
+                }
+            }),
+        );
+    }
+
+    #[test]
+    fn test_unknown_packet_type() {
+        assert_conforms(
+            Packet::Unknown {
+                packet_type: "teamChat".to_string(),
+                payload: json!({"message": "gg"}),
+            },
+            json!({"type": "teamChat", "payload": {"message": "gg"}}),
+        );
+        assert_conforms(
+            Packet::Unknown {
+                packet_type: "newPacket".to_string(),
+                payload: Value::Null,
+            },
+            json!({"type": "newPacket"}),
+        );
+    }
+
+    #[test]
+    fn test_rejects_invalid_known_packet() {
+        let packet = json!({"type": "gameState", "payload": {"id": "state-1"}});
+        assert!(serde_json::from_value::<Packet>(packet).is_err());
+
+        let packet = json!({"payload": {}});
+        assert!(serde_json::from_value::<Packet>(packet).is_err());
+    }
 }
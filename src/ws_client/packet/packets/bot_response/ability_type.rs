@@ -1,7 +1,6 @@
-use serde::{Deserialize, Serialize};
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum AbilityType {
     FireBullet,
     UseLaser,
@@ -9,3 +8,11 @@ pub enum AbilityType {
     UseRadar,
     DropMine,
 }
+
+protocol_enum!(AbilityType {
+    FireBullet => "fireBullet",
+    UseLaser => "useLaser",
+    FireDoubleBullet => "fireDoubleBullet",
+    UseRadar => "useRadar",
+    DropMine => "dropMine",
+});
@@ -3,6 +3,7 @@ use super::move_direction::MoveDirection;
 use super::rotation::Rotation;
 
 /// Represents the various responses a bot can have in the system.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BotResponse {
     /// Represents a movement action for a tank.
     ///
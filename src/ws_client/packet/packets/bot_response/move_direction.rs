@@ -1,22 +1,16 @@
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 use derive_more::derive::IsVariant;
-use serde::{Deserialize, Serialize};
 
 /// Represents the direction of movement.
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    Hash,
-    Serialize,
-    Deserialize,
-    IsVariant,
-)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, IsVariant)]
 pub enum MoveDirection {
     /// Move forward.
     Forward,
     /// Move backward.
     Backward,
 }
+
+protocol_enum!(MoveDirection {
+    Forward => "forward",
+    Backward => "backward",
+});
@@ -1,11 +1,15 @@
-use serde::{Deserialize, Serialize};
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 
 /// Represents the direction of rotation.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Rotation {
     /// Rotate to the left.
     Left,
     /// Rotate to the right.
     Right,
 }
+
+protocol_enum!(Rotation {
+    Left => "left",
+    Right => "right",
+});
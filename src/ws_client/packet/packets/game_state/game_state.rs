@@ -1,4 +1,9 @@
-use super::{player::Player, raw_game_state::RawGameState, tile::tile::Tile, zone::Zone};
+use super::tile::{
+    bullet::Bullet, item::Item, laser::Laser, mine::Mine, tank::Tank, tile::TileEntity,
+};
+use super::{
+    player::Player, position::Position, raw_game_state::RawGameState, tile::tile::Tile, zone::Zone,
+};
 
 use derive_more::Constructor;
 
@@ -63,3 +68,156 @@ impl From<RawGameState> for GameState {
         )
     }
 }
+
+impl GameState {
+    /// Returns the length of the side of the square map.
+    pub fn dimension(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns the tile at the given position, or `None` if it is outside of the map.
+    pub fn tile_at(&self, position: Position) -> Option<&Tile> {
+        self.map.get(position.y)?.get(position.x)
+    }
+
+    /// Returns true if the tile at the given position contains a wall.
+    /// Positions outside of the map are treated as walls.
+    pub fn is_wall(&self, position: Position) -> bool {
+        self.tile_at(position)
+            .map_or(true, |tile| tile.entities.iter().any(TileEntity::is_wall))
+    }
+
+    /// Iterates over all tiles of the map together with their positions, row by row.
+    pub fn tiles(&self) -> impl Iterator<Item = (Position, &Tile)> {
+        self.map.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, tile)| (Position::new(x, y), tile))
+        })
+    }
+
+    /// Iterates over all entities on the map together with their positions.
+    pub fn entities(&self) -> impl Iterator<Item = (Position, &TileEntity)> {
+        self.tiles()
+            .flat_map(|(position, tile)| tile.entities.iter().map(move |entity| (position, entity)))
+    }
+
+    /// Iterates over all visible tanks, including your own.
+    pub fn tanks(&self) -> impl Iterator<Item = (Position, &Tank)> {
+        self.entities()
+            .filter_map(|(position, entity)| match entity {
+                TileEntity::Tank(tank) => Some((position, tank)),
+                _ => None,
+            })
+    }
+
+    /// Iterates over all visible bullets.
+    pub fn bullets(&self) -> impl Iterator<Item = (Position, &Bullet)> {
+        self.entities()
+            .filter_map(|(position, entity)| match entity {
+                TileEntity::Bullet(bullet) => Some((position, bullet)),
+                _ => None,
+            })
+    }
+
+    /// Iterates over all visible lasers. A laser covers several tiles,
+    /// so it is returned once for each of them.
+    pub fn lasers(&self) -> impl Iterator<Item = (Position, &Laser)> {
+        self.entities()
+            .filter_map(|(position, entity)| match entity {
+                TileEntity::Laser(laser) => Some((position, laser)),
+                _ => None,
+            })
+    }
+
+    /// Iterates over all visible mines.
+    pub fn mines(&self) -> impl Iterator<Item = (Position, &Mine)> {
+        self.entities()
+            .filter_map(|(position, entity)| match entity {
+                TileEntity::Mine(mine) => Some((position, mine)),
+                _ => None,
+            })
+    }
+
+    /// Iterates over all visible items lying on the map.
+    pub fn items(&self) -> impl Iterator<Item = (Position, &Item)> {
+        self.entities()
+            .filter_map(|(position, entity)| match entity {
+                TileEntity::Item(item) => Some((position, item)),
+                _ => None,
+            })
+    }
+
+    /// Returns the tank of the given player, or `None` if it is dead.
+    pub fn my_tank(&self, player_id: &str) -> Option<(Position, &Tank)> {
+        self.tanks().find(|(_, tank)| tank.owner_id == player_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::simulator::Simulator;
+    use crate::ws_client::packet::packets::game_state::raw_game_state::map::RawMap;
+    use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+
+    fn game_state() -> GameState {
+        let settings = ServerSettings::new(16, 2, 7, 100, false, false, None, None, "test".into());
+        Simulator::new(7, settings).unwrap().game_state("player-1")
+    }
+
+    #[test]
+    fn test_positions_follow_map_orientation() {
+        // The server sends the tiles by column, so `tiles[x][y]`
+        let mut tiles = vec![vec![vec![]; 4]; 4];
+        tiles[3][1].push(TileEntity::Tank(Tank::new(
+            Direction::Up,
+            None,
+            "player-1".into(),
+            Turret::new(None, None, Direction::Up),
+            None,
+        )));
+        tiles[0][2].push(TileEntity::Wall);
+        let raw_map = RawMap::new(tiles, vec![], vec!["1111".into(); 4]);
+        let game_state: GameState = RawGameState::new("test".into(), 0, vec![], raw_map).into();
+
+        let tank_tiles: Vec<Position> = game_state
+            .tiles()
+            .filter(|(_, tile)| tile.entities.iter().any(TileEntity::is_tank))
+            .map(|(position, _)| position)
+            .collect();
+        assert_eq!(tank_tiles, vec![Position { x: 3, y: 1 }]);
+
+        let tanks: Vec<Position> = game_state.tanks().map(|(position, _)| position).collect();
+        assert_eq!(tanks, vec![Position { x: 3, y: 1 }]);
+
+        let tile = game_state.tile_at(Position { x: 3, y: 1 }).unwrap();
+        assert!(tile.entities.iter().any(TileEntity::is_tank));
+        assert!(game_state
+            .tile_at(Position { x: 1, y: 3 })
+            .unwrap()
+            .entities
+            .is_empty());
+
+        assert!(game_state.is_wall(Position { x: 0, y: 2 }));
+        assert!(!game_state.is_wall(Position { x: 2, y: 0 }));
+        assert_eq!(game_state.tile_at(Position { x: 4, y: 0 }), None);
+    }
+
+    #[test]
+    fn test_my_tank() {
+        let game_state = game_state();
+
+        let (position, tank) = game_state.my_tank("player-1").unwrap();
+        assert_eq!(tank.owner_id, "player-1");
+        assert!(game_state
+            .tile_at(position)
+            .unwrap()
+            .entities
+            .iter()
+            .any(|entity| entity.is_tank()));
+        assert!(game_state.my_tank("player-3").is_none());
+    }
+}
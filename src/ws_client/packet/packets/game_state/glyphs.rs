@@ -0,0 +1,130 @@
+use crate::ws_client::packet::packets::game_state::game_state::GameState;
+use crate::ws_client::packet::packets::game_state::tile::bullet::BulletType;
+use crate::ws_client::packet::packets::game_state::tile::direction::Direction;
+use crate::ws_client::packet::packets::game_state::tile::item::ItemType;
+use crate::ws_client::packet::packets::game_state::tile::laser::LaserOrientation;
+use crate::ws_client::packet::packets::game_state::tile::tile::{Tile, TileEntity};
+
+/// Draws the map of the game state as text, one line per row,
+/// seen by the player with the given ID.
+pub fn map_text(game_state: &GameState, my_id: &str) -> String {
+    game_state
+        .map
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|tile| format!(" {}", tile_glyph(tile, my_id)))
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns the character a tile is drawn with, seen by the player with the given ID.
+///
+/// - `#` wall
+/// - `<`, `>`, `^`, `v` your own tank, `T` other tanks
+/// - `←`, `→`, `↑`, `↓` basic bullets, `⇇`, `⇉`, `⇈`, `⇊` double bullets
+/// - `-`, `|` lasers
+/// - `X` mines
+/// - `?`, `L`, `D`, `R`, `M` items
+/// - the zone letter on empty zone tiles, lowercase when the tile is not visible
+/// - `.` empty visible tiles, a space for tiles hidden by the fog of war
+pub fn tile_glyph(tile: &Tile, my_id: &str) -> char {
+    if tile.entities.iter().any(|entity| entity.is_wall()) {
+        return '#';
+    }
+
+    let entity_symbol = tile.entities.iter().find_map(|entity| match entity {
+        TileEntity::Tank(tank) if tank.owner_id == my_id => Some(match tank.direction {
+            Direction::Left => '<',
+            Direction::Right => '>',
+            Direction::Up => '^',
+            Direction::Down => 'v',
+        }),
+        TileEntity::Tank(_) => Some('T'),
+        TileEntity::Bullet(bullet) => Some(match bullet.bullet_type {
+            BulletType::Basic => match bullet.direction {
+                Direction::Left => '←',
+                Direction::Right => '→',
+                Direction::Up => '↑',
+                Direction::Down => '↓',
+            },
+            BulletType::Double => match bullet.direction {
+                Direction::Left => '⇇',
+                Direction::Right => '⇉',
+                Direction::Up => '⇈',
+                Direction::Down => '⇊',
+            },
+        }),
+        TileEntity::Laser(laser) => Some(match laser.orientation {
+            LaserOrientation::Horizontal => '-',
+            LaserOrientation::Vertical => '|',
+        }),
+        TileEntity::Mine(_) => Some('X'),
+        TileEntity::Item(item) => Some(match item.item_type {
+            ItemType::Unknown => '?',
+            ItemType::Laser => 'L',
+            ItemType::DoubleBullet => 'D',
+            ItemType::Radar => 'R',
+            ItemType::Mine => 'M',
+        }),
+        _ => None,
+    });
+
+    entity_symbol.unwrap_or_else(|| {
+        if let Some(zone_index) = tile.zone_index {
+            if tile.visible {
+                zone_index as char
+            } else {
+                (zone_index + 32) as char
+            }
+        } else if tile.visible {
+            '.'
+        } else {
+            ' '
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ws_client::packet::packets::game_state::tile::tank::Tank;
+    use crate::ws_client::packet::packets::game_state::tile::turret::Turret;
+
+    fn tank(owner_id: &str) -> TileEntity {
+        TileEntity::Tank(Tank::new(
+            Direction::Up,
+            None,
+            owner_id.to_string(),
+            Turret::new(None, None, Direction::Up),
+            None,
+        ))
+    }
+
+    #[test]
+    fn test_tile_glyphs() {
+        let empty = |visible, zone_index| Tile::new(visible, zone_index, vec![]);
+
+        assert_eq!(tile_glyph(&empty(true, None), "me"), '.');
+        assert_eq!(tile_glyph(&empty(false, None), "me"), ' ');
+        assert_eq!(tile_glyph(&empty(true, Some(b'A')), "me"), 'A');
+        assert_eq!(tile_glyph(&empty(false, Some(b'A')), "me"), 'a');
+        assert_eq!(
+            tile_glyph(&Tile::new(true, None, vec![tank("me")]), "me"),
+            '^'
+        );
+        assert_eq!(
+            tile_glyph(&Tile::new(true, Some(b'A'), vec![tank("other")]), "me"),
+            'T'
+        );
+        assert_eq!(
+            tile_glyph(
+                &Tile::new(true, None, vec![tank("me"), TileEntity::Wall]),
+                "me"
+            ),
+            '#'
+        );
+    }
+}
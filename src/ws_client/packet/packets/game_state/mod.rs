@@ -1,5 +1,7 @@
 pub mod game_state;
+pub mod glyphs;
 pub mod player;
+pub mod position;
 pub mod raw_game_state;
 pub mod tile;
 pub mod zone;
@@ -0,0 +1,101 @@
+use super::tile::direction::Direction;
+use derive_more::derive::{Constructor, Display};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Coordinates of a tile on the map.
+///
+/// `x` is the column, growing to the right, and `y` is the row, growing downwards.
+/// The tile at a position is `game_state.map[y][x]`.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Default,
+    Serialize,
+    Deserialize,
+    Constructor,
+    Display,
+)]
+#[display("({x}, {y})")]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Position {
+    /// Returns the neighbouring position in the given direction,
+    /// or `None` if it would be outside of the map on the top or left side.
+    pub fn neighbour(self, direction: Direction) -> Option<Position> {
+        self.offset(direction, 1)
+    }
+
+    /// Returns the position `distance` tiles away in the given direction,
+    /// or `None` if it would be outside of the map on the top or left side.
+    pub fn offset(self, direction: Direction, distance: usize) -> Option<Position> {
+        let (dx, dy) = direction.offset();
+        let x = self.x as i64 + dx * distance as i64;
+        let y = self.y as i64 + dy * distance as i64;
+
+        (x >= 0 && y >= 0).then(|| Position::new(x as usize, y as usize))
+    }
+
+    /// Returns the number of steps between two positions, moving only along the axes.
+    pub fn manhattan_distance(self, other: Position) -> usize {
+        self.x.abs_diff(other.x) + self.y.abs_diff(other.y)
+    }
+
+    /// Returns the direction pointing from this position straight to `other`,
+    /// or `None` if they are not in the same row or column, or are equal.
+    pub fn direction_to(self, other: Position) -> Option<Direction> {
+        match (self.x.cmp(&other.x), self.y.cmp(&other.y)) {
+            (Ordering::Equal, Ordering::Greater) => Some(Direction::Up),
+            (Ordering::Less, Ordering::Equal) => Some(Direction::Right),
+            (Ordering::Equal, Ordering::Less) => Some(Direction::Down),
+            (Ordering::Greater, Ordering::Equal) => Some(Direction::Left),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neighbour() {
+        let position = Position::new(0, 3);
+
+        assert_eq!(position.neighbour(Direction::Up), Some(Position::new(0, 2)));
+        assert_eq!(
+            position.neighbour(Direction::Right),
+            Some(Position::new(1, 3))
+        );
+        assert_eq!(
+            position.neighbour(Direction::Down),
+            Some(Position::new(0, 4))
+        );
+        assert_eq!(position.neighbour(Direction::Left), None);
+    }
+
+    #[test]
+    fn test_direction_to() {
+        let position = Position::new(2, 2);
+
+        assert_eq!(
+            position.direction_to(Position::new(2, 0)),
+            Some(Direction::Up)
+        );
+        assert_eq!(
+            position.direction_to(Position::new(0, 2)),
+            Some(Direction::Left)
+        );
+        assert_eq!(position.direction_to(Position::new(3, 3)), None);
+        assert_eq!(position.direction_to(position), None);
+    }
+}
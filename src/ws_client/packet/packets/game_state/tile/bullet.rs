@@ -1,4 +1,5 @@
 use super::direction::Direction;
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 use derive_more::derive::Constructor;
 use serde::{Deserialize, Serialize};
 
@@ -20,9 +21,13 @@ pub struct Bullet {
     pub bullet_type: BulletType,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum BulletType {
     Basic,
     Double,
 }
+
+protocol_enum!(BulletType {
+    Basic => "basic",
+    Double => "double",
+});
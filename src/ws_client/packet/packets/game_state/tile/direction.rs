@@ -1,19 +1,9 @@
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
+use crate::ws_client::packet::packets::bot_response::rotation::Rotation;
 use derive_more::derive::IsVariant;
-use serde::{Deserialize, Serialize};
 
 /// Represents the four cardinal directions.
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    Hash,
-    IsVariant,
-    Serialize,
-    Deserialize,
-)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, IsVariant)]
 pub enum Direction {
     /// Represents upward direction.
     Up,
@@ -28,6 +18,60 @@ pub enum Direction {
     Left,
 }
 
+protocol_enum!(Direction {
+    Up => "up",
+    Right => "right",
+    Down => "down",
+    Left => "left",
+});
+
+impl Direction {
+    /// All directions in clockwise order, starting from `Up`.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Returns the direction after a 90 degree rotation.
+    pub fn rotate(self, rotation: Rotation) -> Direction {
+        match (self, rotation) {
+            (Direction::Up, Rotation::Right) | (Direction::Down, Rotation::Left) => {
+                Direction::Right
+            }
+            (Direction::Right, Rotation::Right) | (Direction::Left, Rotation::Left) => {
+                Direction::Down
+            }
+            (Direction::Down, Rotation::Right) | (Direction::Up, Rotation::Left) => Direction::Left,
+            (Direction::Left, Rotation::Right) | (Direction::Right, Rotation::Left) => {
+                Direction::Up
+            }
+        }
+    }
+
+    /// Returns the opposite direction.
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    /// Returns the `(dx, dy)` offset of a single step in this direction.
+    /// The `y` axis grows downwards, so `Up` is `(0, -1)`.
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,15 +98,27 @@ mod tests {
         assert_eq!(serde_json::from_str::<Direction>("\"left\"").unwrap(), Direction::Left);
     }
 
+    #[test]
+    fn test_rotate() {
+        assert_eq!(Direction::Up.rotate(Rotation::Right), Direction::Right);
+        assert_eq!(Direction::Up.rotate(Rotation::Left), Direction::Left);
+        assert_eq!(Direction::Left.rotate(Rotation::Right), Direction::Up);
+        assert_eq!(Direction::Down.rotate(Rotation::Left), Direction::Right);
+    }
+
     #[test]
     fn test_deserialize_invalid() {
         let deserialized: Result<Direction, _> = serde_json::from_str("\"invalid\"");
         assert!(deserialized.is_err());
+
+        // Integers are indices of the variants
+        let deserialized: Result<Direction, _> = serde_json::from_str("4");
+        assert!(deserialized.is_err());
     }
 
     #[test]
     fn test_deserialize_invalid_type() {
-        let deserialized: Result<Direction, _> = serde_json::from_str("1");
+        let deserialized: Result<Direction, _> = serde_json::from_str("true");
         assert!(deserialized.is_err());
     }
 }
@@ -1,3 +1,4 @@
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 use derive_more::derive::{Constructor, IsVariant};
 use serde::{Deserialize, Serialize};
 
@@ -8,12 +9,20 @@ pub struct Item {
     pub item_type: ItemType,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, IsVariant)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IsVariant)]
 pub enum ItemType {
+    /// An unknown item, including the ones added in newer versions of the server.
     Unknown,
     Laser,
     DoubleBullet,
     Radar,
     Mine,
 }
+
+protocol_enum!(ItemType {
+    Unknown => "unknown",
+    Laser => "laser",
+    DoubleBullet => "doubleBullet",
+    Radar => "radar",
+    Mine => "mine",
+} else Unknown);
@@ -1,3 +1,4 @@
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 use derive_more::derive::{Constructor, IsVariant};
 use serde::{Deserialize, Serialize};
 
@@ -8,19 +9,13 @@ pub struct Laser {
     pub orientation: LaserOrientation,
 }
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    Hash,
-    IsVariant,
-    Serialize,
-    Deserialize,
-)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, IsVariant)]
 pub enum LaserOrientation {
     Horizontal,
     Vertical,
 }
+
+protocol_enum!(LaserOrientation {
+    Horizontal => "horizontal",
+    Vertical => "vertical",
+});
@@ -1,6 +1,7 @@
 use super::{bullet::Bullet, item::Item, laser::Laser, mine::Mine, tank::Tank};
+use crate::ws_client::packet::adjacently_tagged::{self, AdjacentlyTagged};
 use derive_more::derive::{Constructor, IsVariant};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Represents a tile on the map.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Constructor)]
@@ -15,7 +16,12 @@ pub struct Tile {
 
 /// Enum representing the possible entities (contents) of a tile.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, IsVariant)]
-#[serde(tag = "type", content = "payload", rename_all = "camelCase")]
+#[serde(
+    remote = "Self",
+    tag = "type",
+    content = "payload",
+    rename_all = "camelCase"
+)]
 pub enum TileEntity {
     /// Represents a tile containing a wall.
     Wall,
@@ -29,6 +35,45 @@ pub enum TileEntity {
     Mine(Mine),
     /// A tile containing an item, where `Item` represents the associated item data.
     Item(Item),
+    /// An entity added in a newer version of the server, kept as it was received.
+    #[serde(skip)]
+    Unknown {
+        entity_type: String,
+        payload: serde_json::Value,
+    },
+}
+
+impl Serialize for TileEntity {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            TileEntity::Unknown {
+                entity_type,
+                payload,
+            } => adjacently_tagged::serialize_unknown(entity_type, payload, serializer),
+            _ => TileEntity::serialize(self, serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TileEntity {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        adjacently_tagged::deserialize(deserializer)
+    }
+}
+
+impl AdjacentlyTagged for TileEntity {
+    const TAGS: &'static [&'static str] = &["wall", "tank", "bullet", "laser", "mine", "item"];
+
+    fn deserialize_known<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        TileEntity::deserialize(deserializer)
+    }
+
+    fn unknown(entity_type: String, payload: serde_json::Value) -> Self {
+        TileEntity::Unknown {
+            entity_type,
+            payload,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -133,9 +178,22 @@ mod tests {
         assert_eq!(deserialized, TileEntity::Mine(expected_mine));
     }
 
+    #[test]
+    fn test_deserialize_unknown_type() {
+        let json_data = r#"{"type": "portal", "payload": {"id": 7}}"#;
+        let deserialized: TileEntity = serde_json::from_str(json_data).unwrap();
+        assert_eq!(
+            deserialized,
+            TileEntity::Unknown {
+                entity_type: "portal".to_string(),
+                payload: serde_json::json!({"id": 7}),
+            }
+        );
+    }
+
     #[test]
     fn test_deserialize_invalid_type() {
-        let json_data = r#"{"type": "invalid"}"#;
+        let json_data = r#"{"type": 1}"#;
         let deserialized: Result<TileEntity, _> = serde_json::from_str(json_data);
         assert!(deserialized.is_err());
     }
@@ -154,4 +212,11 @@ mod tests {
         };
         assert_eq!(deserialized, TileEntity::Item(expected_item));
     }
+
+    #[test]
+    fn test_deserialize_unknown_item() {
+        let json_data = r#"{"type": "item", "payload": {"type": "shield"}}"#;
+        let deserialized: TileEntity = serde_json::from_str(json_data).unwrap();
+        assert_eq!(deserialized, TileEntity::Item(Item::new(ItemType::Unknown)));
+    }
 }
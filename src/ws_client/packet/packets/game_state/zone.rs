@@ -1,5 +1,8 @@
+use super::position::Position;
+use crate::ws_client::packet::enum_serialization_format::protocol_enum;
 use derive_more::derive::{Constructor, IsVariant};
-use serde::{Deserialize, Serialize};
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// Represents a zone in the game world.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Constructor)]
@@ -19,11 +22,34 @@ pub struct Zone {
     pub status: ZoneStatus,
 }
 
+impl Zone {
+    /// Returns true if the position lies inside the zone.
+    pub fn contains(&self, position: Position) -> bool {
+        (self.x..self.x + self.width).contains(&(position.x as u64))
+            && (self.y..self.y + self.height).contains(&(position.y as u64))
+    }
+
+    /// Returns the number of steps from the position to the closest tile of the zone,
+    /// moving only along the axes. An empty zone is treated as its top-left tile.
+    pub fn distance(&self, position: Position) -> usize {
+        let axis_distance = |value: usize, start: u64, length: u64| {
+            let value = value as u64;
+            if value < start {
+                start - value
+            } else {
+                value.saturating_sub((start + length).saturating_sub(1))
+            }
+        };
+
+        (axis_distance(position.x, self.x, self.width)
+            + axis_distance(position.y, self.y, self.height)) as usize
+    }
+}
+
 /// Represents the status of a zone.
 ///
 /// The status of a zone can be neutral, being captured, captured, being contested, or being retaken.
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, IsVariant)]
-#[serde(tag = "type", rename_all = "camelCase")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, IsVariant)]
 pub enum ZoneStatus {
     /// The zone is neutral and not controlled by any player.
     Neutral,
@@ -41,6 +67,110 @@ pub enum ZoneStatus {
     BeingRetaken(BeingRetakenStatus),
 }
 
+impl ZoneStatus {
+    /// Returns the ID of the player who holds the zone and receives points for it.
+    pub fn owner(&self) -> Option<&str> {
+        match self {
+            ZoneStatus::Captured(captured) => Some(&captured.player_id),
+            ZoneStatus::BeingContested(contested) => contested.captured_by_id.as_deref(),
+            ZoneStatus::BeingRetaken(retaking) => Some(&retaking.captured_by_id),
+            ZoneStatus::Neutral | ZoneStatus::BeingCaptured(_) => None,
+        }
+    }
+
+    /// Returns the ID of the player who is capturing or retaking the zone.
+    pub fn capturer(&self) -> Option<&str> {
+        match self {
+            ZoneStatus::BeingCaptured(capturing) => Some(&capturing.player_id),
+            ZoneStatus::BeingRetaken(retaking) => Some(&retaking.retaken_by_id),
+            _ => None,
+        }
+    }
+
+    /// Returns the remaining ticks until the capturer takes over the zone.
+    pub fn remaining_ticks(&self) -> Option<u64> {
+        match self {
+            ZoneStatus::BeingCaptured(capturing) => Some(capturing.remaining_ticks),
+            ZoneStatus::BeingRetaken(retaking) => Some(retaking.remaining_ticks),
+            _ => None,
+        }
+    }
+}
+
+/// The `type` tag of a [`ZoneStatus`], which follows the enum serialization format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ZoneStatusType {
+    Neutral,
+    BeingCaptured,
+    Captured,
+    BeingContested,
+    BeingRetaken,
+}
+
+protocol_enum!(ZoneStatusType {
+    Neutral => "neutral",
+    BeingCaptured => "beingCaptured",
+    Captured => "captured",
+    BeingContested => "beingContested",
+    BeingRetaken => "beingRetaken",
+});
+
+/// A zone status with its tag, flattened into a single object like `{"type": "captured", "playerId": "..."}`.
+#[derive(Serialize, Deserialize)]
+struct TaggedZoneStatus<T> {
+    #[serde(rename = "type")]
+    status_type: ZoneStatusType,
+    #[serde(flatten)]
+    status: T,
+}
+
+impl Serialize for ZoneStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        fn tagged<T: Serialize, S: Serializer>(
+            status_type: ZoneStatusType,
+            status: &T,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            TaggedZoneStatus {
+                status_type,
+                status,
+            }
+            .serialize(serializer)
+        }
+
+        match self {
+            ZoneStatus::Neutral => tagged(ZoneStatusType::Neutral, &(), serializer),
+            ZoneStatus::BeingCaptured(status) => {
+                tagged(ZoneStatusType::BeingCaptured, status, serializer)
+            }
+            ZoneStatus::Captured(status) => tagged(ZoneStatusType::Captured, status, serializer),
+            ZoneStatus::BeingContested(status) => {
+                tagged(ZoneStatusType::BeingContested, status, serializer)
+            }
+            ZoneStatus::BeingRetaken(status) => {
+                tagged(ZoneStatusType::BeingRetaken, status, serializer)
+            }
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ZoneStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        fn status<T: DeserializeOwned, E: Error>(fields: serde_json::Value) -> Result<T, E> {
+            serde_json::from_value(fields).map_err(E::custom)
+        }
+
+        let tagged = TaggedZoneStatus::<serde_json::Value>::deserialize(deserializer)?;
+        match tagged.status_type {
+            ZoneStatusType::Neutral => Ok(ZoneStatus::Neutral),
+            ZoneStatusType::BeingCaptured => status(tagged.status).map(ZoneStatus::BeingCaptured),
+            ZoneStatusType::Captured => status(tagged.status).map(ZoneStatus::Captured),
+            ZoneStatusType::BeingContested => status(tagged.status).map(ZoneStatus::BeingContested),
+            ZoneStatusType::BeingRetaken => status(tagged.status).map(ZoneStatus::BeingRetaken),
+        }
+    }
+}
+
 /// Represents the status of a zone being captured.
 ///
 /// This struct contains information about the player capturing the zone and the remaining time
@@ -88,3 +218,26 @@ pub struct BeingRetakenStatus {
     /// The ID of the player retaking the zone.
     pub retaken_by_id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance() {
+        let zone = Zone::new(0, 2, 3, 2, 2, ZoneStatus::Neutral);
+
+        assert_eq!(zone.distance(Position::new(2, 3)), 0);
+        assert_eq!(zone.distance(Position::new(3, 4)), 0);
+        assert_eq!(zone.distance(Position::new(0, 0)), 5);
+        assert_eq!(zone.distance(Position::new(6, 4)), 3);
+    }
+
+    #[test]
+    fn test_distance_to_empty_zone() {
+        let zone = Zone::new(0, 0, 0, 0, 0, ZoneStatus::Neutral);
+
+        assert_eq!(zone.distance(Position::new(0, 0)), 0);
+        assert_eq!(zone.distance(Position::new(2, 1)), 3);
+    }
+}
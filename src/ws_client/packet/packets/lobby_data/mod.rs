@@ -3,7 +3,7 @@ pub mod server_settings;
 
 use derive_more::Constructor;
 use lobby_player::LobbyPlayer;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use server_settings::ServerSettings;
 
 /// Represents the data for a game lobby.
@@ -11,6 +11,8 @@ use server_settings::ServerSettings;
 #[serde(rename_all = "camelCase")]
 pub struct LobbyData {
     /// A unique identifier for the player.
+    /// Empty for spectators, who are sent `null` by the server.
+    #[serde(deserialize_with = "deserialize_player_id")]
     pub player_id: String,
 
     /// A list of players currently in the lobby.
@@ -19,3 +21,7 @@ pub struct LobbyData {
     /// The settings for the server hosting the lobby.
     pub server_settings: ServerSettings,
 }
+
+fn deserialize_player_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
@@ -1,6 +1,16 @@
+// The derived constructor of `ServerSettings` takes every setting, an attribute
+// on the struct itself does not reach the generated code
+#![allow(clippy::too_many_arguments)]
+
 use derive_more::derive::Constructor;
 use serde::{Deserialize, Serialize};
 
+/// The version of the server this wrapper speaks the protocol of.
+///
+/// Servers with another major or minor version may send packets and entities
+/// the wrapper does not know, which are skipped.
+pub const SUPPORTED_SERVER_VERSION: &str = "1.0.0";
+
 /// Represents the configuration settings for the server.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Hash, Constructor)]
 #[serde(rename_all = "camelCase")]
@@ -35,3 +45,38 @@ pub struct ServerSettings {
     /// The version of the game running on the server.
     pub version: String,
 }
+
+impl ServerSettings {
+    /// Returns true if the server runs the major and minor version this wrapper supports,
+    /// see [`SUPPORTED_SERVER_VERSION`].
+    pub fn is_supported_version(&self) -> bool {
+        let version = major_minor(&self.version);
+        version.is_some() && version == major_minor(SUPPORTED_SERVER_VERSION)
+    }
+}
+
+/// Returns the major and minor part of a version like `1.2.3` or `v1.2`.
+fn major_minor(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.trim_start_matches('v').split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(version: &str) -> ServerSettings {
+        ServerSettings::new(24, 2, 1, 100, false, false, None, None, version.to_string())
+    }
+
+    #[test]
+    fn test_is_supported_version() {
+        assert!(settings(SUPPORTED_SERVER_VERSION).is_supported_version());
+        assert!(settings("v1.0.7").is_supported_version());
+        assert!(!settings("1.1.0").is_supported_version());
+        assert!(!settings("2.0.0").is_supported_version());
+        assert!(!settings("unknown").is_supported_version());
+    }
+}
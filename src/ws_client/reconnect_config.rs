@@ -0,0 +1,65 @@
+use derive_more::Constructor;
+use std::time::Duration;
+
+/// Configuration of the reconnection attempts made after the connection to the server drops.
+///
+/// The delay before each attempt grows exponentially, starting at
+/// `initial_delay` and doubling after each failed attempt up to `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Constructor)]
+pub struct ReconnectConfig {
+    /// The maximum number of reconnection attempts. Zero disables reconnecting.
+    pub max_attempts: u32,
+
+    /// The delay before the first attempt.
+    pub initial_delay: Duration,
+
+    /// The upper bound of the delay between attempts.
+    pub max_delay: Duration,
+}
+
+impl ReconnectConfig {
+    /// A configuration that never reconnects.
+    pub const DISABLED: ReconnectConfig = ReconnectConfig {
+        max_attempts: 0,
+        initial_delay: Duration::ZERO,
+        max_delay: Duration::ZERO,
+    };
+
+    /// Returns the delay before the given attempt, counted from zero.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt);
+        self.initial_delay
+            .saturating_mul(factor)
+            .min(self.max_delay)
+    }
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        ReconnectConfig {
+            max_attempts: 5,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay_grows_exponentially_up_to_max() {
+        let config = ReconnectConfig {
+            max_attempts: 10,
+            initial_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+
+        assert_eq!(config.delay(0), Duration::from_millis(100));
+        assert_eq!(config.delay(1), Duration::from_millis(200));
+        assert_eq!(config.delay(3), Duration::from_millis(800));
+        assert_eq!(config.delay(4), Duration::from_millis(1000));
+        assert_eq!(config.delay(40), Duration::from_millis(1000));
+    }
+}
@@ -0,0 +1,153 @@
+use super::packet::packet::Packet;
+use derive_more::IsVariant;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::error;
+
+/// Whether a recorded packet was received from or sent to the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, IsVariant)]
+#[serde(rename_all = "camelCase")]
+pub enum PacketDirection {
+    Received,
+    Sent,
+}
+
+/// A single line of a recording file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedPacket {
+    /// Milliseconds since the UNIX epoch at the moment the packet was received or sent.
+    pub timestamp: u64,
+
+    pub direction: PacketDirection,
+
+    /// The packet, or `None` if the message could not be parsed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packet: Option<Packet>,
+
+    /// The text of a message that could not be parsed as a packet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+}
+
+/// Reads all packets from a file written by a [`Recorder`].
+pub fn read_recording(path: &Path) -> io::Result<Vec<RecordedPacket>> {
+    let file = File::open(path)?;
+
+    BufReader::new(file)
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Ok(line) if line.trim().is_empty()))
+        .map(|(index, line)| {
+            serde_json::from_str(&line?).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("line {}: {}", index + 1, e),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Same as [`RecordedPacket`], but borrows the packet to avoid cloning game states.
+#[derive(Serialize)]
+struct RecordedPacketRef<'a> {
+    timestamp: u64,
+    direction: PacketDirection,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    packet: Option<&'a Packet>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<&'a str>,
+}
+
+/// Appends every packet exchanged with the server to a JSON Lines file,
+/// one [`RecordedPacket`] per line.
+pub struct Recorder {
+    file: Mutex<BufWriter<File>>,
+}
+
+impl Recorder {
+    /// Opens the file for appending, creating it if it does not exist.
+    pub fn create(path: &Path) -> io::Result<Recorder> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Recorder {
+            file: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    pub fn record(&self, direction: PacketDirection, packet: &Packet) {
+        self.write(direction, Some(packet), None);
+    }
+
+    /// Records the text of a message that could not be parsed as a packet.
+    pub fn record_unparsed(&self, direction: PacketDirection, text: &str) {
+        self.write(direction, None, Some(text));
+    }
+
+    fn write(&self, direction: PacketDirection, packet: Option<&Packet>, text: Option<&str>) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_millis() as u64);
+
+        let recorded_packet = RecordedPacketRef {
+            timestamp,
+            direction,
+            packet,
+            text,
+        };
+
+        // Recording is a debugging aid, so failures must not interrupt the game
+        let mut file = self.file.lock().unwrap();
+        let result = serde_json::to_writer(&mut *file, &recorded_packet)
+            .map_err(io::Error::from)
+            .and_then(|_| writeln!(file))
+            .and_then(|_| file.flush());
+
+        if let Err(e) = result {
+            error!("🚨 Error recording packet -> {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_appends_packets_as_json_lines() {
+        let path =
+            std::env::temp_dir().join(format!("monotanks-record-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let recorder = Recorder::create(&path).unwrap();
+        recorder.record(PacketDirection::Received, &Packet::Ping);
+        recorder.record(
+            PacketDirection::Sent,
+            &Packet::Pass {
+                game_state_id: "id".to_string(),
+            },
+        );
+        recorder.record_unparsed(PacketDirection::Received, "{\"type\":");
+
+        let recorded = read_recording(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(recorded.len(), 3);
+        assert!(recorded[0].direction.is_received());
+        assert_eq!(recorded[0].packet, Some(Packet::Ping));
+        assert!(recorded[1].direction.is_sent());
+        assert_eq!(
+            recorded[1].packet,
+            Some(Packet::Pass {
+                game_state_id: "id".to_string()
+            })
+        );
+        assert_eq!(recorded[2].packet, None);
+        assert_eq!(recorded[2].text.as_deref(), Some("{\"type\":"));
+    }
+}
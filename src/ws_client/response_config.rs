@@ -0,0 +1,27 @@
+use derive_more::{Constructor, IsVariant};
+use std::time::Duration;
+
+/// What the client sends when the bot does not return its move in time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, IsVariant, clap::ValueEnum)]
+pub enum FallbackResponse {
+    /// Send `BotResponse::Pass`.
+    #[default]
+    Pass,
+
+    /// Repeat the last move the bot returned in time, or pass if there is none.
+    LastMove,
+}
+
+/// Configuration of how long the bot may think about its next move.
+///
+/// `next_move` runs on a blocking thread. If it does not finish within the
+/// timeout, the fallback response is sent instead and the late move is discarded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Constructor)]
+pub struct ResponseConfig {
+    /// The maximum time `next_move` may take.
+    /// If `None`, the deadline of the tick is used.
+    pub timeout: Option<Duration>,
+
+    /// The response sent when the bot overruns.
+    pub fallback: FallbackResponse,
+}
@@ -0,0 +1,42 @@
+/// Returns the base url of the server, using `wss://` if `secure` is set.
+pub fn server_url(host: &str, port: u16, secure: bool) -> String {
+    let scheme = if secure { "wss" } else { "ws" };
+    format!("{}://{}:{}", scheme, host, port)
+}
+
+/// Appends the query parameters to the base url of the server,
+/// percent-encoding their names and values.
+pub fn connection_url(server_url: &str, parameters: &[(&str, &str)]) -> String {
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(parameters)
+        .finish();
+
+    format!("{}/?{}", server_url.trim_end_matches('/'), query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_server_url() {
+        assert_eq!(server_url("localhost", 5000, false), "ws://localhost:5000");
+        assert_eq!(
+            server_url("example.com", 443, true),
+            "wss://example.com:443"
+        );
+    }
+
+    #[test]
+    fn test_connection_url_encodes_parameters() {
+        let url = connection_url(
+            "ws://localhost:5000/",
+            &[("nickname", "Bot 1&2"), ("joinCode", "a=b?")],
+        );
+
+        assert_eq!(
+            url,
+            "ws://localhost:5000/?nickname=Bot+1%262&joinCode=a%3Db%3F"
+        );
+    }
+}
@@ -0,0 +1,359 @@
+use crate::observer_trait::ObserverTrait;
+#[cfg(feature = "tui")]
+use crate::tui::viewer::Viewer;
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::net::TcpStream;
+use tokio::sync::Mutex;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, info_span, trace, warn, Instrument, Span};
+
+use super::client_error::ClientError;
+use super::compatibility::{warn_about_unknown_entities, warn_if_unsupported_version};
+use super::packet::enum_serialization_format::EnumSerializationFormat;
+use super::packet::packet::Packet;
+use super::packet::packets::game_state::game_state::GameState;
+use super::recorder::{PacketDirection, Recorder};
+use super::server_url::connection_url;
+use super::tls_config::TlsConfig;
+
+/// Watches a match as a spectator and passes everything that happens to an observer.
+///
+/// Spectators do not take a player slot, never respond to game states and see
+/// the whole map, so an observer can follow matches the bot is not playing in.
+pub struct SpectatorClient<O: ObserverTrait> {
+    /// Locked by the callbacks, which run on a blocking thread.
+    observer: Arc<Mutex<O>>,
+    enum_format: EnumSerializationFormat,
+    recorder: Option<Recorder>,
+    #[cfg(feature = "tui")]
+    viewer: Option<Arc<Viewer>>,
+    /// Whether the server version has been checked, which is done with the first lobby data.
+    version_checked: bool,
+    /// Entity types unknown to this version of the wrapper that have already been logged.
+    reported_entity_types: HashSet<String>,
+    websocket_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    cancel_token: CancellationToken,
+}
+
+impl<O: ObserverTrait + 'static> SpectatorClient<O> {
+    /// Connects to the server at the given base url, e.g. `wss://example.com:5000`,
+    /// as a spectator. [`SpectatorClient::run`] then passes the match to the `observer`.
+    ///
+    /// The `tls_config` is only used for `wss://` urls. The server encodes the enums
+    /// in the packets it sends in `enum_format`.
+    pub async fn connect_to(
+        server_url: &str,
+        tls_config: TlsConfig,
+        enum_format: EnumSerializationFormat,
+        code: &str,
+        observer: O,
+        cancel_token: CancellationToken,
+    ) -> Result<SpectatorClient<O>, ClientError> {
+        let url = Self::construct_url(server_url, enum_format, code);
+
+        info!("📞 Connecting to the server as a spectator: {}", url);
+        let websocket_stream = match tls_config.connect(&url).await {
+            Ok((stream, _)) => {
+                info!("🌟 Successfully connected to the server");
+                stream
+            }
+            Err(e) => return Err(ClientError::Connect(e)),
+        };
+
+        Ok(SpectatorClient {
+            observer: Arc::new(Mutex::new(observer)),
+            enum_format,
+            recorder: None,
+            #[cfg(feature = "tui")]
+            viewer: None,
+            version_checked: false,
+            reported_entity_types: HashSet::new(),
+            websocket_stream,
+            cancel_token,
+        })
+    }
+
+    /// Appends the connection parameters of a spectator to the base url of the server.
+    pub fn construct_url(
+        server_url: &str,
+        enum_format: EnumSerializationFormat,
+        code: &str,
+    ) -> String {
+        let enum_format = enum_format.to_string();
+        let mut parameters = vec![
+            ("playerType", "spectator"),
+            ("enumSerializationFormat", enum_format.as_str()),
+        ];
+
+        if !code.is_empty() {
+            parameters.push(("joinCode", code));
+        }
+
+        connection_url(server_url, &parameters)
+    }
+
+    /// Records every packet received from and sent to the server.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Shares every game state with a viewer, e.g. the one drawn by [`crate::tui::tui::run_tui`].
+    #[cfg(feature = "tui")]
+    pub fn with_viewer(mut self, viewer: Arc<Viewer>) -> Self {
+        self.viewer = Some(viewer);
+        self
+    }
+
+    /// Watches until the game ends, the client is cancelled or the connection is closed.
+    ///
+    /// Returns the observer, e.g. to read the statistics it gathered,
+    /// or the error that ended the client.
+    pub async fn run(mut self) -> Result<O, ClientError> {
+        loop {
+            let message = tokio::select! {
+                message = self.websocket_stream.next() => message,
+                _ = self.cancel_token.cancelled() => {
+                    info!("👋 Spectator client shutting down...");
+                    let _ = self.websocket_stream.close(None).await;
+                    return Ok(self.into_observer());
+                }
+            };
+
+            let text = match message {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => {
+                    info!("🔌 Connection closed by server");
+                    return Ok(self.into_observer());
+                }
+                // Pings are answered by tungstenite itself
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Err(ClientError::WebSocket(e)),
+            };
+
+            let packet: Packet = match serde_json::from_str(&text) {
+                Ok(packet) => packet,
+                Err(source) => {
+                    if let Some(recorder) = &self.recorder {
+                        recorder.record_unparsed(PacketDirection::Received, &text);
+                    }
+                    let payload = text.to_string();
+                    error!("🚨 {}", ClientError::Parse { payload, source });
+                    continue;
+                }
+            };
+
+            if let Some(recorder) = &self.recorder {
+                recorder.record(PacketDirection::Received, &packet);
+            }
+
+            if let Packet::GameEnded(game_end) = packet {
+                info!("🏁 Game ended");
+                self.observe(|observer| observer.on_game_ended(game_end))
+                    .await?;
+                let _ = self.websocket_stream.close(None).await;
+                return Ok(self.into_observer());
+            }
+
+            self.process_packet(packet).await?;
+        }
+    }
+
+    async fn process_packet(&mut self, packet: Packet) -> Result<(), ClientError> {
+        match packet {
+            Packet::Ping => self.send(Packet::Pong).await?,
+
+            Packet::ConnectionRejected { reason } => {
+                warn!("🚨 Connection rejected -> {}", reason);
+                return Err(ClientError::ConnectionRejected { reason });
+            }
+
+            Packet::ConnectionAccepted => {
+                info!("🎉 Connection accepted");
+                self.send(Packet::LobbyDataRequest).await?;
+                debug!("🎳 Lobby data request sent");
+            }
+
+            Packet::GameNotStarted => info!("🕒 Game not started yet"),
+            Packet::GameInProgress => info!("🏃 Game in progress"),
+            Packet::GameStarting => info!("🎲 Game starting"),
+            Packet::GameStarted => info!("🎲 Game started"),
+
+            Packet::LobbyData(lobby_data) => {
+                info!("🎳 Lobby data received");
+                if !self.version_checked {
+                    warn_if_unsupported_version(&lobby_data.server_settings);
+                    self.version_checked = true;
+                }
+                self.observe(|observer| observer.on_lobby_data(lobby_data))
+                    .await?;
+            }
+
+            Packet::GameState(raw_game_state) => {
+                let span = info_span!(
+                    "tick",
+                    game_state_id = %raw_game_state.id,
+                    tick = raw_game_state.tick
+                );
+                trace!(parent: &span, "🎮 Game state received");
+
+                // Observers still get the game state, just without these entities
+                span.in_scope(|| {
+                    warn_about_unknown_entities(&raw_game_state, &mut self.reported_entity_types)
+                });
+
+                let game_state: GameState = raw_game_state.into();
+                #[cfg(feature = "tui")]
+                if let Some(viewer) = &self.viewer {
+                    viewer.set_game_state(game_state.clone());
+                }
+                self.observe(|observer| observer.on_game_state(game_state))
+                    .instrument(span)
+                    .await?;
+            }
+
+            Packet::CustomWarning { message } => warn!("⚠️ Warning received -> {}", message),
+
+            // Errors
+            Packet::InvalidPacketTypeError => error!("🚨 {}", ClientError::InvalidPacketType),
+            Packet::InvalidPacketUsageError => error!("🚨 {}", ClientError::InvalidPacketUsage),
+            Packet::InvalidPayloadError { message } => {
+                error!("🚨 {}", ClientError::InvalidPayload { message })
+            }
+
+            // Packets added in newer versions of the server
+            Packet::Unknown { packet_type, .. } => {
+                warn!("⚠️ Unknown packet received: {}", packet_type);
+            }
+
+            // Spectators make no moves, so they get no warnings about them
+            packet => warn!("⚠️ Unexpected packet received: {:?}", packet),
+        }
+
+        Ok(())
+    }
+
+    /// Runs a callback of the observer on a blocking thread, within the current span,
+    /// so a slow observer does not stall the runtime. The next packet is read once it returns.
+    async fn observe<F>(&self, callback: F) -> Result<(), ClientError>
+    where
+        F: FnOnce(&mut O) + Send + 'static,
+    {
+        let mut observer = self.observer.clone().lock_owned().await;
+        let span = Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            callback(&mut observer);
+        })
+        .await
+        .map_err(ClientError::Task)
+    }
+
+    /// Returns the observer once the client has stopped.
+    fn into_observer(self) -> O {
+        // Every callback has finished and released its lock by now
+        match Arc::try_unwrap(self.observer) {
+            Ok(observer) => observer.into_inner(),
+            Err(_) => unreachable!("the observer is still used by a callback"),
+        }
+    }
+
+    async fn send(&mut self, packet: Packet) -> Result<(), ClientError> {
+        if let Some(recorder) = &self.recorder {
+            recorder.record(PacketDirection::Sent, &packet);
+        }
+
+        let text = packet.to_json(self.enum_format);
+        self.websocket_stream
+            .send(Message::Text(text))
+            .await
+            .map_err(ClientError::WebSocket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::simulator::Simulator;
+    use crate::testing::mock_server::{MockServer, Step};
+    use crate::ws_client::packet::packets::game_end::game_end::GameEnd;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+    use crate::ws_client::packet::packets::lobby_data::LobbyData;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        lobby_data: Vec<LobbyData>,
+        ticks: Vec<u64>,
+        game_end: Option<GameEnd>,
+    }
+
+    impl ObserverTrait for RecordingObserver {
+        fn on_lobby_data(&mut self, lobby_data: LobbyData) {
+            self.lobby_data.push(lobby_data);
+        }
+
+        fn on_game_state(&mut self, game_state: GameState) {
+            self.ticks.push(game_state.tick);
+        }
+
+        fn on_game_ended(&mut self, game_end: GameEnd) {
+            self.game_end = Some(game_end);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watches_game_against_mock_server() {
+        let server = MockServer::bind().await.unwrap();
+        let server_url = format!("ws://127.0.0.1:{}", server.port());
+        let client = tokio::spawn(async move {
+            SpectatorClient::connect_to(
+                &server_url,
+                TlsConfig::default(),
+                EnumSerializationFormat::String,
+                "code",
+                RecordingObserver::default(),
+                CancellationToken::new(),
+            )
+            .await?
+            .run()
+            .await
+        });
+        let mut connection = server.accept().await.unwrap();
+        assert!(connection.query().contains("playerType=spectator"));
+        assert!(connection.query().contains("joinCode=code"));
+        assert!(!connection.query().contains("nickname"));
+
+        let settings = ServerSettings::new(16, 2, 1, 100, false, false, None, None, "test".into());
+        let mut simulator = Simulator::new(1, settings).unwrap();
+        let lobby_data = simulator.lobby_data("");
+
+        let mut steps = vec![
+            Step::Send(Packet::ConnectionAccepted),
+            Step::Expect("lobbyDataRequest"),
+            Step::Send(Packet::LobbyData(lobby_data.clone())),
+            Step::Send(Packet::GameStarting),
+            Step::Send(Packet::GameStarted),
+        ];
+        for _ in 0..3 {
+            steps.push(Step::Send(Packet::GameState(
+                simulator.spectator_raw_game_state(),
+            )));
+            simulator.tick(Default::default());
+        }
+        // Spectators never answer game states
+        steps.push(Step::ExpectSilence(Duration::from_millis(200)));
+        steps.push(Step::Send(Packet::GameEnded(simulator.game_end())));
+        connection.play(steps).await;
+        connection.close().await;
+
+        let observer = client.await.unwrap().unwrap();
+        assert_eq!(observer.lobby_data, vec![lobby_data]);
+        assert_eq!(observer.ticks, vec![0, 1, 2]);
+        assert_eq!(observer.game_end, Some(simulator.game_end()));
+    }
+}
@@ -0,0 +1,80 @@
+use std::path::Path;
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::tungstenite::Error;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+
+#[cfg(feature = "rustls")]
+use rustls::{pki_types::pem::PemObject, pki_types::CertificateDer, ClientConfig, RootCertStore};
+#[cfg(feature = "rustls")]
+use std::sync::Arc;
+
+/// TLS settings used when connecting to a `wss://` server.
+///
+/// The default configuration trusts the bundled Mozilla root certificates,
+/// which is enough for hosted servers. Servers using a self-signed certificate
+/// can be trusted with [`TlsConfig::with_certificate`].
+///
+/// Requires the `rustls` feature, which is enabled by default.
+#[derive(Debug, Clone, Default)]
+pub struct TlsConfig {
+    #[cfg(feature = "rustls")]
+    client_config: Option<Arc<ClientConfig>>,
+}
+
+impl TlsConfig {
+    /// Trusts the PEM encoded certificates from the given file,
+    /// in addition to the bundled root certificates.
+    #[cfg(feature = "rustls")]
+    pub fn with_certificate(path: &Path) -> Result<TlsConfig, String> {
+        let mut root_store = RootCertStore::empty();
+        root_store.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+        let certificates = CertificateDer::pem_file_iter(path)
+            .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+            .map_err(|e| format!("Invalid certificate {} -> {}", path.display(), e))?;
+
+        if certificates.is_empty() {
+            return Err(format!("No certificates found in {}", path.display()));
+        }
+
+        for certificate in certificates {
+            root_store
+                .add(certificate)
+                .map_err(|e| format!("Invalid certificate {} -> {}", path.display(), e))?;
+        }
+
+        let client_config = ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+
+        Ok(TlsConfig {
+            client_config: Some(Arc::new(client_config)),
+        })
+    }
+
+    /// Trusts the PEM encoded certificates from the given file,
+    /// in addition to the bundled root certificates.
+    #[cfg(not(feature = "rustls"))]
+    pub fn with_certificate(_path: &Path) -> Result<TlsConfig, String> {
+        Err("TLS support requires the `rustls` feature".to_string())
+    }
+
+    /// Opens a WebSocket connection, using TLS for `wss://` urls.
+    pub(super) async fn connect(
+        &self,
+        url: &str,
+    ) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response), Error> {
+        #[cfg(feature = "rustls")]
+        {
+            let connector = self
+                .client_config
+                .clone()
+                .map(tokio_tungstenite::Connector::Rustls);
+            tokio_tungstenite::connect_async_tls_with_config(url, None, false, connector).await
+        }
+
+        #[cfg(not(feature = "rustls"))]
+        tokio_tungstenite::connect_async(url).await
+    }
+}
@@ -2,6 +2,7 @@ use futures_util::stream::SplitSink;
 use futures_util::SinkExt;
 use tokio::net::TcpStream;
 use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::error;
 
 pub fn create_writer_task(
     mut write: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
@@ -18,7 +19,7 @@ pub fn create_writer_task(
         while let Some(message) = rx.recv().await {
             match write.send(message).await {
                 Ok(_) => {}
-                Err(e) => eprintln!("[System] 🌋 WebSocket send error: {}", e),
+                Err(e) => error!("🌋 WebSocket send error: {}", e),
             }
         }
     });
@@ -1,311 +1,848 @@
-use crate::bot::Bot;
-use futures_util::stream::{SplitSink, SplitStream};
-use futures_util::{SinkExt, StreamExt};
-use std::sync::Arc;
-use tokio::net::TcpStream;
-use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::Mutex;
-use tokio::task::JoinHandle;
-use tokio_tungstenite::tungstenite::Error;
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
-use tokio_util::sync::CancellationToken;
-
-use super::handlers::handle_game_ended::handle_game_ended;
-use super::handlers::handle_next_move::handle_next_move;
-use super::handlers::handle_on_warning_received::handle_on_warning_received;
-use super::handlers::handle_prepare_to_game::handle_prepare_to_game;
-use super::packet::packet::Packet;
-use super::packet::warning::Warning;
-
-pub struct WebSocketClient {
-    read_task: JoinHandle<Result<(), Error>>,
-    writer_task: JoinHandle<Result<(), Error>>,
-    cancel_token: CancellationToken,
-}
-
-impl WebSocketClient {
-    pub async fn connect(
-        host: &str,
-        port: u16,
-        code: &str,
-        nickname: &str,
-        cancel_token: CancellationToken,
-    ) -> Result<WebSocketClient, Error> {
-        // Construct proper url
-        let url = Self::construct_url(host, port, code, nickname);
-
-        // Connect to the server
-        println!("[System] 📞 Connecting to the server: {}", url);
-        let websocket_stream = match connect_async(&url).await {
-            Ok((stream, _)) => {
-                println!("[System] 🌟 Successfully connected to the server");
-                stream
-            }
-            Err(e) => return Err(e),
-        };
-
-        // Split the stream into write and read parts
-        let (write, read) = websocket_stream.split();
-
-        let (tx, rx) = tokio::sync::mpsc::channel(100);
-        let bot = Arc::new(Mutex::new(None));
-
-        let writer_task = Self::create_writer_task(write, rx, cancel_token.clone());
-        let read_task = Self::create_reader_task(read, tx, bot, cancel_token.clone());
-
-        Ok(WebSocketClient {
-            read_task,
-            writer_task,
-            cancel_token,
-        })
-    }
-
-    pub async fn run(self) -> Result<(), Box<dyn std::error::Error>> {
-        let WebSocketClient {
-            read_task,
-            writer_task,
-            cancel_token,
-        } = self;
-
-        tokio::select! {
-            _ = cancel_token.cancelled() => {
-                println!("[System] 👋 WebSocket client shutting down...");
-            }
-            read_result = read_task => {
-                if let Err(e) = read_result? {
-                    eprintln!("[System] 📚 Read task error: {}", e);
-                }
-            }
-            write_result = writer_task => {
-                if let Err(e) = write_result? {
-                    eprintln!("[System] 📝 Write task error: {}", e);
-                }
-            }
-        }
-
-        Ok(())
-    }
-
-    pub fn construct_url(host: &str, port: u16, code: &str, nickname: &str) -> String {
-        let mut url = format!("ws://{}:{}/?nickname={}", host, port, nickname);
-
-        url.push_str("&enumSerializationFormat=string");
-        url.push_str("&playerType=hackathonBot");
-
-        if !code.is_empty() {
-            url.push_str("&joinCode=");
-            url.push_str(code);
-        }
-
-        url
-    }
-
-    fn create_writer_task(
-        mut write: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
-        mut rx: Receiver<Message>,
-        cancel_token: CancellationToken,
-    ) -> JoinHandle<Result<(), Error>> {
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    message = rx.recv() => {
-                        match message {
-                            Some(message) => {
-                                if let Err(e) = write.send(message).await {
-                                    eprintln!("[System] 🌋 WebSocket send error: {}", e);
-                                    break Err(e);
-                                }
-                            }
-                            None => break Ok(()),
-                        }
-                    }
-                    _ = cancel_token.cancelled() => {
-                        if let Err(e) = write.close().await {
-                            eprintln!("[System] 🌋 Error closing WebSocket connection: {}", e);
-                        }
-                        break Ok(());
-                    }
-                }
-            }
-        })
-    }
-
-    fn create_reader_task(
-        mut read: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
-        tx: Sender<Message>,
-        bot: Arc<Mutex<Option<Bot>>>,
-        cancel_token: CancellationToken,
-    ) -> JoinHandle<Result<(), Error>> {
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    message = read.next() => {
-                        match message {
-                            Some(Ok(message)) => {
-                                Self::process_message(message, tx.clone(), bot.clone()).await;
-                            }
-                            Some(Err(e)) => {
-                                eprintln!("[System] 🌋 WebSocket receive error: {}", e);
-                                cancel_token.cancel();
-                                break Err(e);
-                            }
-                            None => {
-                                println!("[System] 🔌 Connection closed by server");
-                                cancel_token.cancel();
-                                break Ok(());
-                            }
-                        }
-                    }
-                    _ = cancel_token.cancelled() => {
-                        break Ok(());
-                    }
-                }
-            }
-        })
-    }
-
-    async fn process_message(message: Message, tx: Sender<Message>, bot: Arc<Mutex<Option<Bot>>>) {
-        match message {
-            Message::Text(message) => {
-                let tx_clone = tx.clone();
-                let bot_clone = bot.clone();
-                tokio::task::spawn(async move {
-                    if let Err(e) =
-                        Self::process_text_message(message.clone(), tx_clone, bot_clone).await
-                    {
-                        eprintln!("[System] 🚨 Error processing text message -> {}", e);
-                    }
-                });
-            }
-            Message::Ping(message) => {
-                tx.send(Message::Pong(message)).await.unwrap();
-            }
-            Message::Pong(_) => {}
-            Message::Close(_) => {
-                println!("[System] 🚪 Connection closed");
-            }
-            Message::Binary(_) => {
-                println!("[System] 🔢 Received Binary message");
-            }
-            Message::Frame(_) => {
-                println!("[System] 🖼 Received Frame message");
-            }
-        }
-    }
-
-    async fn process_text_message(
-        message: String,
-        tx: tokio::sync::mpsc::Sender<Message>,
-        bot: Arc<Mutex<Option<Bot>>>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        let packet: Packet = serde_json::from_str(&message)
-            .map_err(|e| format!("🚨 Error parsing message -> {}", e))?;
-
-        match packet {
-            Packet::Ping => tx
-                .send(Message::Text(Packet::Pong.into()))
-                .await
-                .map_err(|e| format!("🚨 Error sending Pong -> {}", e))?,
-
-            Packet::ConnectionRejected { reason } => {
-                println!("[System] 🚨 Connection rejected -> {}", reason);
-            }
-
-            Packet::ConnectionAccepted => {
-                println!("[System] 🎉 Connection accepted");
-
-                match tx
-                    .send(Message::Text(Packet::LobbyDataRequest.into()))
-                    .await
-                {
-                    Ok(_) => println!("[System] 🎳 Lobby data request sent"),
-                    Err(e) => eprintln!("[System] 🚨 Error sending LobbyDataRequest -> {}", e),
-                }
-            }
-
-            Packet::GameNotStarted => {
-                println!("[System] 🕒 Game not started yet");
-            }
-
-            Packet::GameInProgress => {
-                println!("[System] 🏃 Game in progress");
-            }
-
-            Packet::GameStarting => {
-                println!("[System] 🎲 Game starting");
-
-                // Wait until bot is not None
-                while bot.lock().await.is_none() {
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                }
-
-                tx.send(Message::Text(Packet::ReadyToReceiveGameState.into()))
-                    .await
-                    .map_err(|e| format!("🚨 Error sending ReadyToReceiveGameState -> {}", e))?;
-            }
-
-            Packet::LobbyData(lobby_data) => {
-                println!("[System] 🎳 Lobby data received");
-                handle_prepare_to_game(tx, bot, lobby_data).await?
-            }
-
-            Packet::GameStarted => println!("[System] 🎲 Game started"),
-            Packet::GameState(raw_game_state) => {
-                // println!("🎮 Game state received");
-                handle_next_move(tx, bot, raw_game_state).await?
-            }
-
-            Packet::GameEnded(game_end) => {
-                println!("[System] 🏁 Game ended");
-                handle_game_ended(bot, game_end).await?
-            }
-
-            // Warnings
-            Packet::PlayerAlreadyMadeActionWarning => {
-                let warning = Warning::PlayerAlreadyMadeActionWarning;
-                handle_on_warning_received(bot, warning).await?;
-            }
-            Packet::MissingGameStateIdWarning => {
-                let warning = Warning::MissingGameStateIdWarning;
-                handle_on_warning_received(bot, warning).await?;
-            }
-            Packet::SlowResponseWarning => {
-                let warning = Warning::SlowResponseWarning;
-                handle_on_warning_received(bot, warning).await?;
-            }
-            Packet::ActionIgnoredDueToDeadWarning => {
-                let warning = Warning::ActionIgnoredDueToDeadWarning;
-                handle_on_warning_received(bot, warning).await?;
-            }
-            Packet::CustomWarning { message } => {
-                let warning = Warning::CustomWarning { message };
-                handle_on_warning_received(bot, warning).await?;
-            }
-
-            // Errors
-            Packet::InvalidPacketTypeError => {
-                println!("[System] 🚨 WebSocket client sent an invalid packet type error");
-            }
-            Packet::InvalidPacketUsageError => {
-                println!("[System] 🚨 WebSocket client used packet in invalid way");
-            }
-            Packet::InvalidPayloadError { message } => {
-                println!("[System] 🚨 Invalid payload error -> {}", message);
-            }
-
-            // These packets are never send by the server
-            Packet::Pong
-            | Packet::LobbyDataRequest
-            | Packet::GameStatusRequest
-            | Packet::ReadyToReceiveGameState { .. }
-            | Packet::Movement { .. }
-            | Packet::Rotation { .. }
-            | Packet::AbilityUse { .. }
-            | Packet::Pass { .. } => {
-                println!("[System] ⚠️ Unexpected packet received: {:?}", packet);
-            }
-        };
-
-        Ok(())
-    }
-}
+use crate::bot_trait::BotTrait;
+#[cfg(feature = "tui")]
+use crate::tui::viewer::Viewer;
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{Receiver, Sender};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Error;
+use tokio_tungstenite::{tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, info_span, trace, warn, Instrument, Level};
+
+use super::client_error::{ClientError, ErrorHandler};
+use super::client_state::ClientState;
+use super::handlers::handle_game_ended::handle_game_ended;
+use super::handlers::handle_next_move::handle_next_move;
+use super::handlers::handle_on_warning_received::handle_on_warning_received;
+use super::handlers::handle_prepare_to_game::handle_prepare_to_game;
+use super::packet::enum_serialization_format::EnumSerializationFormat;
+use super::packet::packet::Packet;
+use super::packet::packets::game_state::glyphs::map_text;
+use super::packet::warning::Warning;
+use super::reconnect_config::ReconnectConfig;
+use super::recorder::{PacketDirection, Recorder};
+use super::response_config::ResponseConfig;
+use super::server_url::{connection_url, server_url};
+use super::tls_config::TlsConfig;
+
+pub struct WebSocketClient<B: BotTrait + 'static> {
+    url: String,
+    tls_config: TlsConfig,
+    enum_format: EnumSerializationFormat,
+    reconnect_config: ReconnectConfig,
+    response_config: ResponseConfig,
+    recorder: Option<Recorder>,
+    #[cfg(feature = "tui")]
+    viewer: Option<Arc<Viewer>>,
+    error_handler: Option<ErrorHandler>,
+    websocket_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    cancel_token: CancellationToken,
+    bot: PhantomData<B>,
+}
+
+/// Tasks handling a single WebSocket connection.
+struct Connection {
+    read_task: JoinHandle<Result<(), Error>>,
+    writer_task: JoinHandle<Result<(), Error>>,
+    /// Cancelled when the connection is lost, child of the client's token.
+    cancel_token: CancellationToken,
+}
+
+impl<B: BotTrait + 'static> WebSocketClient<B> {
+    /// Connects to the server, [`WebSocketClient::run`] then drives a bot of type `B`.
+    ///
+    /// The bot instance is created with [`BotTrait::on_joining_lobby`]
+    /// once the first lobby data is received from the server.
+    pub async fn connect(
+        host: &str,
+        port: u16,
+        code: &str,
+        nickname: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<WebSocketClient<B>, ClientError> {
+        let server_url = server_url(host, port, false);
+        Self::connect_to(
+            &server_url,
+            TlsConfig::default(),
+            EnumSerializationFormat::default(),
+            code,
+            nickname,
+            cancel_token,
+        )
+        .await
+    }
+
+    /// Connects to the server at the given base url, e.g. `wss://example.com:5000`.
+    ///
+    /// The `tls_config` is only used for `wss://` urls. The server encodes the enums
+    /// in the packets it sends in `enum_format`, and the client does the same.
+    pub async fn connect_to(
+        server_url: &str,
+        tls_config: TlsConfig,
+        enum_format: EnumSerializationFormat,
+        code: &str,
+        nickname: &str,
+        cancel_token: CancellationToken,
+    ) -> Result<WebSocketClient<B>, ClientError> {
+        // Construct proper url
+        let url = Self::construct_url_for(server_url, enum_format, code, nickname);
+
+        // Connect to the server
+        info!("📞 Connecting to the server: {}", url);
+        let websocket_stream = match tls_config.connect(&url).await {
+            Ok((stream, _)) => {
+                info!("🌟 Successfully connected to the server");
+                stream
+            }
+            Err(e) => return Err(ClientError::Connect(e)),
+        };
+
+        Ok(WebSocketClient {
+            url,
+            tls_config,
+            enum_format,
+            reconnect_config: ReconnectConfig::default(),
+            response_config: ResponseConfig::default(),
+            recorder: None,
+            #[cfg(feature = "tui")]
+            viewer: None,
+            error_handler: None,
+            websocket_stream,
+            cancel_token,
+            bot: PhantomData,
+        })
+    }
+
+    /// Sets how the client reconnects when the connection drops mid-game.
+    ///
+    /// By default [`ReconnectConfig::default`] is used.
+    pub fn with_reconnect_config(mut self, reconnect_config: ReconnectConfig) -> Self {
+        self.reconnect_config = reconnect_config;
+        self
+    }
+
+    /// Sets how long the bot may take to respond and what is sent when it overruns.
+    ///
+    /// By default the deadline of each tick is used and the bot passes when it overruns.
+    pub fn with_response_config(mut self, response_config: ResponseConfig) -> Self {
+        self.response_config = response_config;
+        self
+    }
+
+    /// Records every packet received from and sent to the server.
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    /// Shares every game state, response and warning with a viewer,
+    /// e.g. the one drawn by [`crate::tui::tui::run_tui`].
+    ///
+    /// The viewer draws the map, so it is no longer logged every tick.
+    #[cfg(feature = "tui")]
+    pub fn with_viewer(mut self, viewer: Arc<Viewer>) -> Self {
+        self.viewer = Some(viewer);
+        self
+    }
+
+    /// Calls the handler with every error that does not end the client,
+    /// e.g. when the bot panics or a message from the server cannot be parsed.
+    pub fn with_error_handler(
+        mut self,
+        error_handler: impl Fn(&ClientError) + Send + Sync + 'static,
+    ) -> Self {
+        self.error_handler = Some(Arc::new(error_handler));
+        self
+    }
+
+    /// Plays until the game ends, the client is cancelled or the connection cannot be kept.
+    ///
+    /// Returns the error that ended the client, for example
+    /// [`ClientError::ConnectionRejected`] when the nickname is already taken.
+    pub async fn run(self) -> Result<(), ClientError> {
+        let WebSocketClient {
+            url,
+            tls_config,
+            enum_format,
+            reconnect_config,
+            response_config,
+            recorder,
+            #[cfg(feature = "tui")]
+            viewer,
+            error_handler,
+            websocket_stream,
+            cancel_token,
+            bot: _,
+        } = self;
+
+        let state = ClientState::new(response_config, enum_format, recorder, error_handler);
+        #[cfg(feature = "tui")]
+        let state = state.with_viewer(viewer);
+        let state = Arc::new(state);
+        let mut connection_number = 1;
+        let mut connection = Self::start_connection(
+            websocket_stream,
+            state.clone(),
+            &cancel_token,
+            connection_number,
+        );
+
+        let result = loop {
+            let connection_result = tokio::select! {
+                _ = cancel_token.cancelled() => {
+                    info!("👋 WebSocket client shutting down...");
+                    break Ok(());
+                }
+                read_result = &mut connection.read_task => read_result,
+                write_result = &mut connection.writer_task => write_result,
+            };
+
+            connection.cancel_token.cancel();
+            let connection_result = match connection_result {
+                Ok(connection_result) => connection_result,
+                Err(e) => break Err(ClientError::Task(e)),
+            };
+            if state.is_finished() {
+                break Ok(());
+            }
+            if reconnect_config.max_attempts == 0 {
+                break connection_result.map_err(ClientError::WebSocket);
+            }
+            if let Err(e) = connection_result {
+                state.report_error(ClientError::WebSocket(e));
+            }
+
+            let Some(websocket_stream) =
+                Self::reconnect(&url, &tls_config, &reconnect_config, &cancel_token).await
+            else {
+                if cancel_token.is_cancelled() {
+                    break Ok(());
+                }
+                break Err(ClientError::Reconnect {
+                    attempts: reconnect_config.max_attempts,
+                });
+            };
+            connection_number += 1;
+            connection = Self::start_connection(
+                websocket_stream,
+                state.clone(),
+                &cancel_token,
+                connection_number,
+            );
+        };
+
+        cancel_token.cancel();
+        match state.take_fatal_error() {
+            Some(error) => Err(error),
+            None => result,
+        }
+    }
+
+    /// Tries to connect to the server again, waiting before each attempt
+    /// as configured. Returns `None` if all attempts failed or the client was cancelled.
+    async fn reconnect(
+        url: &str,
+        tls_config: &TlsConfig,
+        reconnect_config: &ReconnectConfig,
+        cancel_token: &CancellationToken,
+    ) -> Option<WebSocketStream<MaybeTlsStream<TcpStream>>> {
+        for attempt in 0..reconnect_config.max_attempts {
+            let delay = reconnect_config.delay(attempt);
+            info!(
+                "🔁 Reconnecting in {} ms (attempt {}/{})",
+                delay.as_millis(),
+                attempt + 1,
+                reconnect_config.max_attempts
+            );
+
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = cancel_token.cancelled() => return None,
+            }
+
+            match tls_config.connect(url).await {
+                Ok((stream, _)) => {
+                    info!("🌟 Successfully reconnected to the server");
+                    return Some(stream);
+                }
+                Err(e) => warn!("🌋 Reconnection attempt failed -> {}", e),
+            }
+        }
+
+        None
+    }
+
+    /// Starts the tasks of a connection, logging within a span with its number,
+    /// which is incremented with every reconnection.
+    fn start_connection(
+        websocket_stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+        state: Arc<ClientState<B>>,
+        cancel_token: &CancellationToken,
+        number: u32,
+    ) -> Connection {
+        let span = info_span!("connection", number);
+        let _entered = span.enter();
+
+        // Split the stream into write and read parts
+        let (write, read) = websocket_stream.split();
+
+        let (tx, rx) = tokio::sync::mpsc::channel(100);
+        let cancel_token = cancel_token.child_token();
+
+        let writer_task = Self::create_writer_task(write, rx, cancel_token.clone());
+        let read_task = Self::create_reader_task(read, tx, state, cancel_token.clone());
+
+        Connection {
+            read_task,
+            writer_task,
+            cancel_token,
+        }
+    }
+
+    pub fn construct_url(host: &str, port: u16, code: &str, nickname: &str) -> String {
+        Self::construct_url_for(
+            &server_url(host, port, false),
+            EnumSerializationFormat::default(),
+            code,
+            nickname,
+        )
+    }
+
+    /// Appends the connection parameters to the base url of the server.
+    pub fn construct_url_for(
+        server_url: &str,
+        enum_format: EnumSerializationFormat,
+        code: &str,
+        nickname: &str,
+    ) -> String {
+        let enum_format = enum_format.to_string();
+        let mut parameters = vec![
+            ("nickname", nickname),
+            ("enumSerializationFormat", enum_format.as_str()),
+            ("playerType", "hackathonBot"),
+        ];
+
+        if !code.is_empty() {
+            parameters.push(("joinCode", code));
+        }
+
+        connection_url(server_url, &parameters)
+    }
+
+    fn create_writer_task(
+        mut write: SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>,
+        mut rx: Receiver<Message>,
+        cancel_token: CancellationToken,
+    ) -> JoinHandle<Result<(), Error>> {
+        tokio::spawn(
+            async move {
+                loop {
+                    tokio::select! {
+                        message = rx.recv() => {
+                            match message {
+                                Some(message) => {
+                                    if let Err(e) = write.send(message).await {
+                                        break Err(e);
+                                    }
+                                }
+                                None => break Ok(()),
+                            }
+                        }
+                        _ = cancel_token.cancelled() => {
+                            if let Err(e) = write.close().await {
+                                error!("🌋 Error closing WebSocket connection: {}", e);
+                            }
+                            break Ok(());
+                        }
+                    }
+                }
+            }
+            .in_current_span(),
+        )
+    }
+
+    fn create_reader_task(
+        mut read: SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+        tx: Sender<Message>,
+        state: Arc<ClientState<B>>,
+        cancel_token: CancellationToken,
+    ) -> JoinHandle<Result<(), Error>> {
+        tokio::spawn(
+            async move {
+                loop {
+                    tokio::select! {
+                        message = read.next() => {
+                            match message {
+                                Some(Ok(message)) => {
+                                    Self::process_message(message, tx.clone(), state.clone()).await;
+                                }
+                                Some(Err(e)) => {
+                                    cancel_token.cancel();
+                                    break Err(e);
+                                }
+                                None => {
+                                    info!("🔌 Connection closed by server");
+                                    cancel_token.cancel();
+                                    break Ok(());
+                                }
+                            }
+                        }
+                        _ = cancel_token.cancelled() => {
+                            break Ok(());
+                        }
+                    }
+                }
+            }
+            .in_current_span(),
+        )
+    }
+
+    async fn process_message(message: Message, tx: Sender<Message>, state: Arc<ClientState<B>>) {
+        match message {
+            Message::Text(message) => {
+                // The deadline of the tick is counted from the moment the message arrived
+                let received_at = Instant::now();
+
+                let packet: Packet = match serde_json::from_str(&message) {
+                    Ok(packet) => packet,
+                    Err(source) => {
+                        state.record_unparsed(&message);
+                        state.report_error(ClientError::Parse {
+                            payload: message.to_string(),
+                            source,
+                        });
+                        return;
+                    }
+                };
+
+                if let Some(recorder) = &state.recorder {
+                    recorder.record(PacketDirection::Received, &packet);
+                }
+
+                // Tracked before spawning, so handlers of older game states can tell they are stale
+                if let Packet::GameState(raw_game_state) = &packet {
+                    state.set_latest_game_state_id(raw_game_state.id.clone());
+                }
+
+                // Marked before spawning, so the client knows not to reconnect
+                // even if the connection is closed right after this packet
+                match &packet {
+                    Packet::GameEnded(_) => state.mark_finished(),
+                    Packet::ConnectionRejected { reason } => {
+                        state.fail(ClientError::ConnectionRejected {
+                            reason: reason.clone(),
+                        })
+                    }
+                    _ => {}
+                }
+
+                tokio::task::spawn(
+                    async move {
+                        let result =
+                            Self::process_packet(packet, received_at, tx, state.clone()).await;
+                        if let Err(e) = result {
+                            state.report_error(e);
+                        }
+                    }
+                    .in_current_span(),
+                );
+            }
+            Message::Ping(message) => {
+                if tx.send(Message::Pong(message)).await.is_err() {
+                    state.report_error(ClientError::Send { packet: "Pong" });
+                }
+            }
+            Message::Pong(_) => {}
+            Message::Close(_) => {
+                info!("🚪 Connection closed");
+            }
+            Message::Binary(_) => {
+                debug!("🔢 Received Binary message");
+            }
+            Message::Frame(_) => {
+                debug!("🖼 Received Frame message");
+            }
+        }
+    }
+
+    async fn process_packet(
+        packet: Packet,
+        received_at: Instant,
+        tx: tokio::sync::mpsc::Sender<Message>,
+        state: Arc<ClientState<B>>,
+    ) -> Result<(), ClientError> {
+        let bot = state.bot.clone();
+
+        match packet {
+            Packet::Ping => tx
+                .send(state.message(Packet::Pong))
+                .await
+                .map_err(|_| ClientError::Send { packet: "Pong" })?,
+
+            Packet::ConnectionRejected { reason } => {
+                warn!("🚨 Connection rejected -> {}", reason);
+            }
+
+            Packet::ConnectionAccepted => {
+                info!("🎉 Connection accepted");
+
+                tx.send(state.message(Packet::LobbyDataRequest))
+                    .await
+                    .map_err(|_| ClientError::Send {
+                        packet: "LobbyDataRequest",
+                    })?;
+                debug!("🎳 Lobby data request sent");
+
+                // After reconnecting, ask whether the game is still running
+                if bot.lock().await.is_some() {
+                    tx.send(state.message(Packet::GameStatusRequest))
+                        .await
+                        .map_err(|_| ClientError::Send {
+                            packet: "GameStatusRequest",
+                        })?;
+                }
+            }
+
+            Packet::GameNotStarted => {
+                info!("🕒 Game not started yet");
+            }
+
+            Packet::GameInProgress => {
+                info!("🏃 Game in progress");
+            }
+
+            Packet::GameStarting => {
+                info!("🎲 Game starting");
+
+                // The bot is created once the lobby data arrives, which may come later
+                state.wait_for_bot().await;
+
+                tx.send(state.message(Packet::ReadyToReceiveGameState))
+                    .await
+                    .map_err(|_| ClientError::Send {
+                        packet: "ReadyToReceiveGameState",
+                    })?;
+            }
+
+            Packet::LobbyData(lobby_data) => {
+                info!("🎳 Lobby data received");
+                state.set_lobby_data(lobby_data.clone());
+                #[cfg(feature = "tui")]
+                if let Some(viewer) = &state.viewer {
+                    viewer.set_player_id(lobby_data.player_id.clone());
+                }
+                handle_prepare_to_game(tx, state, lobby_data).await?
+            }
+
+            Packet::GameStarted => info!("🎲 Game started"),
+            Packet::GameState(raw_game_state) => {
+                let span = info_span!(
+                    "tick",
+                    game_state_id = %raw_game_state.id,
+                    tick = raw_game_state.tick
+                );
+                trace!(parent: &span, "🎮 Game state received");
+
+                // The bot still plays, it just does not see these entities
+                span.in_scope(|| state.warn_about_unknown_entities(&raw_game_state));
+
+                let context = state.tick_context(&raw_game_state, received_at);
+                #[cfg(feature = "tui")]
+                if let Some(viewer) = &state.viewer {
+                    viewer.set_game_state(raw_game_state.clone().into());
+                }
+
+                // Log the map, unless it is already drawn by a viewer.
+                // Run with `--log-level debug` to see it
+                if !state.has_viewer() && tracing::enabled!(Level::DEBUG) {
+                    let player_id = state.player_id().unwrap_or_default();
+                    let map = map_text(&raw_game_state.clone().into(), &player_id);
+                    debug!(parent: &span, "Map:\n{}", map);
+                }
+                handle_next_move(tx, state, raw_game_state, context)
+                    .instrument(span)
+                    .await?
+            }
+
+            Packet::GameEnded(game_end) => {
+                info!("🏁 Game ended");
+                handle_game_ended(bot, game_end).await?
+            }
+
+            // Warnings
+            Packet::PlayerAlreadyMadeActionWarning => {
+                Self::handle_warning(&state, Warning::PlayerAlreadyMadeActionWarning).await?;
+            }
+            Packet::MissingGameStateIdWarning => {
+                Self::handle_warning(&state, Warning::MissingGameStateIdWarning).await?;
+            }
+            Packet::SlowResponseWarning => {
+                Self::handle_warning(&state, Warning::SlowResponseWarning).await?;
+            }
+            Packet::ActionIgnoredDueToDeadWarning => {
+                Self::handle_warning(&state, Warning::ActionIgnoredDueToDeadWarning).await?;
+            }
+            Packet::CustomWarning { message } => {
+                Self::handle_warning(&state, Warning::CustomWarning { message }).await?;
+            }
+
+            // Errors
+            Packet::InvalidPacketTypeError => return Err(ClientError::InvalidPacketType),
+            Packet::InvalidPacketUsageError => return Err(ClientError::InvalidPacketUsage),
+            Packet::InvalidPayloadError { message } => {
+                return Err(ClientError::InvalidPayload { message })
+            }
+
+            // Packets added in newer versions of the server
+            Packet::Unknown { packet_type, .. } => {
+                warn!("⚠️ Unknown packet received: {}", packet_type);
+            }
+
+            // These packets are never send by the server
+            Packet::Pong
+            | Packet::LobbyDataRequest
+            | Packet::GameStatusRequest
+            | Packet::ReadyToReceiveGameState
+            | Packet::Movement { .. }
+            | Packet::Rotation { .. }
+            | Packet::AbilityUse { .. }
+            | Packet::Pass { .. } => {
+                warn!("⚠️ Unexpected packet received: {:?}", packet);
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Shows the warning in the viewer, if any, and passes it to the bot.
+    async fn handle_warning(state: &ClientState<B>, warning: Warning) -> Result<(), ClientError> {
+        #[cfg(feature = "tui")]
+        if let Some(viewer) = &state.viewer {
+            viewer.push_warning(warning.clone());
+        }
+
+        handle_on_warning_received(state, warning).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bot_trait::tick_context::TickContext;
+    use crate::simulator::simulator::Simulator;
+    use crate::testing::mock_server::{MockConnection, MockServer, Step};
+    use crate::ws_client::packet::packets::bot_response::bot_response::BotResponse;
+    use crate::ws_client::packet::packets::game_state::game_state::GameState;
+    use crate::ws_client::packet::packets::game_state::tile::tile::TileEntity;
+    use crate::ws_client::packet::packets::lobby_data::server_settings::ServerSettings;
+    use crate::ws_client::packet::packets::lobby_data::LobbyData;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    const PLAYER_ID: &str = "player-1";
+    const SILENCE: Duration = Duration::from_millis(200);
+
+    static WARNINGS: Mutex<Vec<Warning>> = Mutex::new(vec![]);
+
+    /// Passes every tick, like `PassBot`, and keeps the warnings it receives.
+    struct WarningBot;
+
+    impl BotTrait for WarningBot {
+        fn on_joining_lobby(_: LobbyData) -> Self {
+            WarningBot
+        }
+
+        fn next_move(&mut self, _: GameState, _: &TickContext) -> BotResponse {
+            BotResponse::Pass
+        }
+
+        fn on_warning_received(&mut self, warning: Warning) {
+            WARNINGS.lock().unwrap().push(warning);
+        }
+    }
+
+    fn simulator(sandbox_mode: bool) -> Simulator {
+        let settings = ServerSettings::new(
+            16,
+            2,
+            1,
+            100,
+            false,
+            sandbox_mode,
+            None,
+            None,
+            "test".into(),
+        );
+        Simulator::new(1, settings).unwrap()
+    }
+
+    async fn start_client(
+        server: &MockServer,
+    ) -> (JoinHandle<Result<(), ClientError>>, MockConnection) {
+        let port = server.port();
+        let client = tokio::spawn(async move {
+            WebSocketClient::<WarningBot>::connect(
+                "127.0.0.1",
+                port,
+                "",
+                "bot",
+                CancellationToken::new(),
+            )
+            .await?
+            .with_reconnect_config(ReconnectConfig::DISABLED)
+            .run()
+            .await
+        });
+        let connection = server.accept().await.unwrap();
+
+        (client, connection)
+    }
+
+    #[tokio::test]
+    async fn test_plays_game_against_mock_server() {
+        let server = MockServer::bind().await.unwrap();
+        let (client, mut connection) = start_client(&server).await;
+        assert!(connection.query().contains("nickname=bot"));
+
+        let simulator = simulator(false);
+        let raw_game_state = simulator.raw_game_state(PLAYER_ID);
+        let received = connection
+            .play(vec![
+                Step::Send(Packet::ConnectionAccepted),
+                Step::Expect("lobbyDataRequest"),
+                Step::Send(Packet::LobbyData(simulator.lobby_data(PLAYER_ID))),
+                Step::ExpectSilence(SILENCE),
+                Step::Send(Packet::GameStarting),
+                Step::Expect("readyToReceiveGameState"),
+                Step::Send(Packet::GameStarted),
+                Step::Send(Packet::GameState(raw_game_state.clone())),
+                Step::Expect("pass"),
+                Step::Send(Packet::SlowResponseWarning),
+                Step::Send(Packet::CustomWarning {
+                    message: "custom".to_string(),
+                }),
+                Step::ExpectSilence(SILENCE),
+                Step::Send(Packet::GameEnded(simulator.game_end())),
+            ])
+            .await;
+        connection.close().await;
+
+        assert_eq!(
+            received[2],
+            Packet::Pass {
+                game_state_id: raw_game_state.id
+            }
+        );
+        assert!(client.await.unwrap().is_ok());
+
+        let warnings = WARNINGS.lock().unwrap();
+        assert!(warnings.contains(&Warning::SlowResponseWarning));
+        assert!(warnings.contains(&Warning::CustomWarning {
+            message: "custom".to_string()
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_requests_game_status_in_sandbox_mode() {
+        let server = MockServer::bind().await.unwrap();
+        let (client, mut connection) = start_client(&server).await;
+
+        let simulator = simulator(true);
+        connection
+            .play(vec![
+                Step::Send(Packet::ConnectionAccepted),
+                Step::Expect("lobbyDataRequest"),
+                Step::Send(Packet::LobbyData(simulator.lobby_data(PLAYER_ID))),
+                Step::Expect("readyToReceiveGameState"),
+                Step::Expect("gameStatusRequest"),
+                Step::Send(Packet::GameState(simulator.raw_game_state(PLAYER_ID))),
+                Step::Expect("pass"),
+                Step::Send(Packet::GameEnded(simulator.game_end())),
+            ])
+            .await;
+        connection.close().await;
+
+        assert!(client.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_answers_game_starting_received_before_lobby_data() {
+        let server = MockServer::bind().await.unwrap();
+        let (client, mut connection) = start_client(&server).await;
+
+        let simulator = simulator(false);
+        connection
+            .play(vec![
+                Step::Send(Packet::GameStarting),
+                Step::ExpectSilence(SILENCE),
+                Step::Send(Packet::LobbyData(simulator.lobby_data(PLAYER_ID))),
+            ])
+            .await;
+
+        // Polling for the bot would delay the answer, it must follow the lobby data right away
+        let ready = connection.receive(Duration::from_millis(50)).await;
+        assert_eq!(ready, Some(Packet::ReadyToReceiveGameState));
+
+        connection
+            .send(Packet::GameEnded(simulator.game_end()))
+            .await;
+        connection.close().await;
+        assert!(client.await.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_returns_rejection() {
+        let server = MockServer::bind().await.unwrap();
+        let (client, mut connection) = start_client(&server).await;
+
+        connection
+            .play(vec![Step::Send(Packet::ConnectionRejected {
+                reason: "InvalidJoinCode".to_string(),
+            })])
+            .await;
+        connection.close().await;
+
+        match client.await.unwrap() {
+            Err(ClientError::ConnectionRejected { reason }) => {
+                assert_eq!(reason, "InvalidJoinCode")
+            }
+            result => panic!("expected a rejection, got {:?}", result),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plays_on_with_unknown_packets_and_entities() {
+        let server = MockServer::bind().await.unwrap();
+        let (client, mut connection) = start_client(&server).await;
+
+        let simulator = simulator(false);
+        let mut raw_game_state = simulator.raw_game_state(PLAYER_ID);
+        raw_game_state.map.tiles[0][0].push(TileEntity::Unknown {
+            entity_type: "portal".to_string(),
+            payload: serde_json::json!({"id": 1}),
+        });
+
+        connection
+            .play(vec![
+                Step::Send(Packet::LobbyData(simulator.lobby_data(PLAYER_ID))),
+                Step::Send(Packet::GameStarting),
+                Step::Expect("readyToReceiveGameState"),
+                Step::Send(Packet::Unknown {
+                    packet_type: "teamChat".to_string(),
+                    payload: serde_json::json!({"message": "gg"}),
+                }),
+                Step::Send(Packet::GameState(raw_game_state)),
+                Step::Expect("pass"),
+                Step::Send(Packet::GameEnded(simulator.game_end())),
+            ])
+            .await;
+        connection.close().await;
+
+        assert!(client.await.unwrap().is_ok());
+    }
+}